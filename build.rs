@@ -0,0 +1,155 @@
+// ビルドスクリプト
+// memory.x のメモリ配置をリンカに渡します
+// また、japanese フィーチャーが有効なときに、サンプルとライブラリの文字列リテラルで
+// 使われている文字だけを集めて、日本語フォントのサブセットを作ります
+// 変換には tools/fontconv と同じ処理を使います
+//
+// 元のフォントは環境変数で指定します
+//   WIO_JP_FONT       UnicodeのBDF (GNU Unifontなど) またはTTF/OTFのパス
+//   WIO_JP_FONT_SIZE  TTF/OTFをラスタライズする大きさ [px] (既定は16)
+//   WIO_JP_CHARS      実行時に組み立てる文字列などで、追加で含める文字を書いたファイル
+
+fn main() {
+    // フラッシュの最後のブロックを除いたメモリ配置 (memory.x) をリンカに渡す
+    // クレートのルートの memory.x が wio_terminal クレートのものより優先される
+    println!("cargo:rustc-link-search={}", env!("CARGO_MANIFEST_DIR"));
+    println!("cargo:rerun-if-changed=memory.x");
+
+    #[cfg(feature = "japanese")]
+    japanese::generate();
+}
+
+#[cfg(feature = "japanese")]
+#[path = "tools/fontconv/src/bdf.rs"]
+mod bdf;
+#[cfg(feature = "japanese")]
+#[path = "tools/fontconv/src/encode.rs"]
+mod encode;
+#[cfg(feature = "japanese")]
+#[path = "tools/fontconv/src/ttf.rs"]
+mod ttf;
+
+#[cfg(feature = "japanese")]
+mod japanese {
+    use std::collections::BTreeSet;
+    use std::env;
+    use std::fs;
+    use std::path::{Path, PathBuf};
+
+    // 文字列リテラルの中の文字を集めます (コメントは読み飛ばす)
+    fn collect_literals(source: &str, chars: &mut BTreeSet<char>) {
+        let mut it = source.chars().peekable();
+        let mut in_string = false;
+        let mut escaped = false;
+        let mut previous = '\0';
+        while let Some(c) = it.next() {
+            if in_string {
+                if escaped {
+                    escaped = false;
+                } else if c == '\\' {
+                    escaped = true;
+                } else if c == '"' {
+                    in_string = false;
+                } else if !c.is_ascii() && !c.is_control() {
+                    chars.insert(c);
+                }
+            } else if c == '/' && it.peek() == Some(&'/') {
+                while it.peek().map_or(false, |&c| c != '\n') {
+                    it.next();
+                }
+            } else if c == '/' && it.peek() == Some(&'*') {
+                while let Some(c) = it.next() {
+                    if c == '*' && it.peek() == Some(&'/') {
+                        it.next();
+                        break;
+                    }
+                }
+            } else if c == '"' && previous != '\'' {
+                in_string = true;
+            }
+            previous = c;
+        }
+    }
+
+    fn collect_dir(dir: &Path, chars: &mut BTreeSet<char>) {
+        println!("cargo:rerun-if-changed={}", dir.display());
+        let entries = match fs::read_dir(dir) {
+            Ok(entries) => entries,
+            Err(_) => return,
+        };
+        for path in entries.filter_map(|e| e.ok()).map(|e| e.path()) {
+            // フォントの処理 (禁則処理の文字の表など) は表示する文字ではない
+            if path.ends_with("src/font") {
+                continue;
+            }
+            if path.is_dir() {
+                collect_dir(&path, chars);
+            } else if path.extension().map_or(false, |e| e == "rs") {
+                println!("cargo:rerun-if-changed={}", path.display());
+                if let Ok(source) = fs::read_to_string(&path) {
+                    collect_literals(&source, chars);
+                }
+            }
+        }
+    }
+
+    fn fail(message: String) -> ! {
+        panic!("japanese font: {}", message);
+    }
+
+    pub fn generate() {
+        println!("cargo:rerun-if-env-changed=WIO_JP_FONT");
+        println!("cargo:rerun-if-env-changed=WIO_JP_FONT_SIZE");
+        println!("cargo:rerun-if-env-changed=WIO_JP_CHARS");
+        let font_path = env::var_os("WIO_JP_FONT").map(PathBuf::from);
+        let font_path = font_path.unwrap_or_else(|| {
+            fail("set WIO_JP_FONT to a Unicode BDF or TTF/OTF font".into())
+        });
+        println!("cargo:rerun-if-changed={}", font_path.display());
+
+        // ASCIIの表示できる文字は、日本語と混ぜて使うので常に含める
+        let mut chars: BTreeSet<char> =
+            (0x20u8..=0x7e).map(char::from).collect();
+        let root = PathBuf::from(env::var_os("CARGO_MANIFEST_DIR").unwrap());
+        collect_dir(&root.join("src"), &mut chars);
+        collect_dir(&root.join("examples"), &mut chars);
+        if let Some(path) = env::var_os("WIO_JP_CHARS") {
+            println!("cargo:rerun-if-changed={}", Path::new(&path).display());
+            let text = fs::read_to_string(&path)
+                .unwrap_or_else(|e| fail(format!("{:?}: {}", path, e)));
+            chars.extend(text.chars().filter(|c| !c.is_control()));
+        }
+        let chars: Vec<char> = chars.into_iter().collect();
+
+        let source = fs::read(&font_path).unwrap_or_else(|e| {
+            fail(format!("{}: {}", font_path.display(), e))
+        });
+        let is_bdf = font_path
+            .extension()
+            .map_or(false, |e| e.eq_ignore_ascii_case("bdf"));
+        let (font, bpp) = if is_bdf {
+            let source = String::from_utf8_lossy(&source);
+            let font =
+                super::bdf::parse(&source, |c| chars.binary_search(&c).is_ok());
+            (font, 1)
+        } else {
+            let size = env::var("WIO_JP_FONT_SIZE")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(16.0);
+            (super::ttf::rasterize(&source, size, &chars), 4)
+        };
+        let font = font.unwrap_or_else(|e| fail(e));
+        let missing: String = chars
+            .iter()
+            .filter(|&&c| !font.glyphs.iter().any(|g| g.codepoint == c as u32))
+            .collect();
+        if !missing.is_empty() {
+            println!("cargo:warning=not in the Japanese font: {}", missing);
+        }
+        let out = super::encode::encode(&font, bpp).unwrap_or_else(|e| fail(e));
+        let out_dir = PathBuf::from(env::var_os("OUT_DIR").unwrap());
+        fs::write(out_dir.join("japanese.bin"), out)
+            .unwrap_or_else(|e| fail(e.to_string()));
+    }
+}
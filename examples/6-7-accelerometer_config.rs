@@ -0,0 +1,90 @@
+//! 6-7 加速度センサ/I2Cのサンプルコードです。
+//! LIS3DHのフルスケール、データレート、動作モードを設定して、
+//! 100ミリ秒ごとに加速度センサから値を読み出します。
+//!
+//! ### 実行方法
+//! ```sh
+//! $ cargo hf2 --example 6-7-accelerometer_config
+//! ```
+
+#![no_std]
+#![no_main]
+
+use panic_halt as _;
+use wio_terminal as wio;
+
+use core::fmt::Write;
+use wio::entry;
+use wio::hal::clock::GenericClockController;
+use wio::hal::delay::Delay;
+use wio::hal::gpio::*;
+use wio::hal::sercom::*;
+use wio::pac::{CorePeripherals, Peripherals};
+use wio::prelude::*;
+use wio_examples::lis3dh::{
+    Config, DataRate, HighPassFilter, HighPassMode, Lis3dh, Mode, Range,
+    ADDRESS,
+};
+
+#[entry]
+fn main() -> ! {
+    let mut peripherals = Peripherals::take().unwrap();
+    let core = CorePeripherals::take().unwrap();
+    let mut clocks = GenericClockController::with_external_32kosc(
+        peripherals.GCLK,
+        &mut peripherals.MCLK,
+        &mut peripherals.OSC32KCTRL,
+        &mut peripherals.OSCCTRL,
+        &mut peripherals.NVMCTRL,
+    );
+
+    let mut sets = wio::Pins::new(peripherals.PORT).split();
+    let mut delay = Delay::new(core.SYST, &mut clocks);
+
+    // UARTドライバオブジェクトを初期化する
+    let mut serial = sets.uart.init(
+        &mut clocks,
+        115200.hz(),
+        peripherals.SERCOM2,
+        &mut peripherals.MCLK,
+        &mut sets.port,
+    );
+
+    // 加速度センサが接続されているSERCOM4をI2Cとして初期化する
+    let gclk0 = clocks.gclk0();
+    let i2c: I2CMaster4<Sercom4Pad0<Pa13<PfD>>, Sercom4Pad1<Pa12<PfD>>> =
+        I2CMaster4::new(
+            &clocks.sercom4_core(&gclk0).unwrap(),
+            400.khz(),
+            peripherals.SERCOM4,
+            &mut peripherals.MCLK,
+            sets.accelerometer.sda.into_pad(&mut sets.port),
+            sets.accelerometer.scl.into_pad(&mut sets.port),
+        );
+    let mut accel = Lis3dh::new(i2c, ADDRESS).unwrap();
+
+    // ±4[g]、100[Hz]の高分解能モードで、重力成分をハイパスフィルタで取り除く
+    accel
+        .configure(Config {
+            range: Range::G4,
+            data_rate: DataRate::Hz100,
+            mode: Mode::HighResolution,
+            high_pass: Some(HighPassFilter {
+                mode: HighPassMode::Normal,
+                cutoff: 0,
+            }),
+        })
+        .unwrap();
+    writeln!(&mut serial, "config: {:?}", accel.config()).unwrap();
+
+    loop {
+        let accel = accel.read_g().unwrap();
+        writeln!(
+            &mut serial,
+            "X: {:.3}, Y: {:.3}, Z: {:.3}",
+            accel.x, accel.y, accel.z
+        )
+        .unwrap();
+        delay.delay_ms(100u16);
+    }
+}
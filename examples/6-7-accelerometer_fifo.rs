@@ -0,0 +1,156 @@
+//! 6-7 加速度センサ/I2Cのサンプルコードです。
+//! LIS3DHのFIFOをStreamモードで使い、1344[Hz]のサンプルをまとめて読み出します。
+//! ウォーターマーク割り込みのたびに、読み出したサンプル数と各軸の振れ幅を
+//! シリアルターミナルに出力します。
+//!
+//! ### 実行方法
+//! ```sh
+//! $ cargo hf2 --example 6-7-accelerometer_fifo --release
+//! ```
+
+#![no_std]
+#![no_main]
+
+use panic_halt as _;
+use wio_terminal as wio;
+
+use core::fmt::Write;
+use core::sync::atomic::{AtomicBool, Ordering};
+use cortex_m::peripheral::{DWT, NVIC};
+use wio::entry;
+use wio::hal::clock::GenericClockController;
+use wio::hal::gpio::*;
+use wio::hal::sercom::*;
+use wio::pac::{interrupt, CorePeripherals, Peripherals};
+use wio::prelude::*;
+use wio_examples::lis3dh::int1::{
+    clear_int1, configure_eic, configure_int1_pin,
+};
+use wio_examples::lis3dh::{
+    Config, DataRate, FifoMode, Lis3dh, Mode, Range, ADDRESS,
+};
+
+// ウォーターマーク。FIFOに24サンプル溜まったら割り込みを発生させる
+const WATERMARK: u8 = 24;
+// CPUクロック [MHz]。タイムスタンプの計算に使う
+const CPU_MHZ: u32 = 120;
+
+// 割り込みハンドラからmain()関数へ、INT1がアサートされたことを通知する
+static INT1_ASSERTED: AtomicBool = AtomicBool::new(false);
+
+#[entry]
+fn main() -> ! {
+    let mut peripherals = Peripherals::take().unwrap();
+    let mut core = CorePeripherals::take().unwrap();
+    let mut clocks = GenericClockController::with_external_32kosc(
+        peripherals.GCLK,
+        &mut peripherals.MCLK,
+        &mut peripherals.OSC32KCTRL,
+        &mut peripherals.OSCCTRL,
+        &mut peripherals.NVMCTRL,
+    );
+
+    // タイムスタンプ用にサイクルカウンタを動かす
+    core.DCB.enable_trace();
+    core.DWT.enable_cycle_counter();
+
+    // PORTはPins構造体に渡してしまうので、先にPC21をEICに接続しておく
+    configure_int1_pin(&peripherals.PORT);
+    let mut sets = wio::Pins::new(peripherals.PORT).split();
+
+    // UARTドライバオブジェクトを初期化する
+    let mut serial = sets.uart.init(
+        &mut clocks,
+        115200.hz(),
+        peripherals.SERCOM2,
+        &mut peripherals.MCLK,
+        &mut sets.port,
+    );
+
+    // 加速度センサが接続されているSERCOM4をI2Cとして初期化する
+    let gclk0 = clocks.gclk0();
+    let i2c: I2CMaster4<Sercom4Pad0<Pa13<PfD>>, Sercom4Pad1<Pa12<PfD>>> =
+        I2CMaster4::new(
+            &clocks.sercom4_core(&gclk0).unwrap(),
+            400.khz(),
+            peripherals.SERCOM4,
+            &mut peripherals.MCLK,
+            sets.accelerometer.sda.into_pad(&mut sets.port),
+            sets.accelerometer.scl.into_pad(&mut sets.port),
+        );
+    let mut accel = Lis3dh::new(i2c, ADDRESS).unwrap();
+    accel
+        .configure(Config {
+            range: Range::G8,
+            data_rate: DataRate::Hz1344,
+            mode: Mode::Normal,
+            high_pass: None,
+        })
+        .unwrap();
+    accel.enable_fifo(FifoMode::Stream, WATERMARK).unwrap();
+
+    // INT1の立ち上がりエッジで割り込みが発生するようにする
+    configure_eic(&peripherals.EIC, &mut peripherals.MCLK);
+    unsafe {
+        NVIC::unmask(interrupt::EIC_EXTINT_5);
+    }
+
+    // サイクルカウンタは約36秒でオーバーフローするので、差分をマイクロ秒に積算する
+    let mut last_cycles = DWT::cycle_count();
+    let mut elapsed_cycles = 0u32;
+    let mut timestamp_us = 0u32;
+    let mut now_us = move || {
+        let cycles = DWT::cycle_count();
+        elapsed_cycles += cycles.wrapping_sub(last_cycles);
+        last_cycles = cycles;
+        timestamp_us = timestamp_us.wrapping_add(elapsed_cycles / CPU_MHZ);
+        elapsed_cycles %= CPU_MHZ;
+        timestamp_us
+    };
+
+    loop {
+        if !INT1_ASSERTED.swap(false, Ordering::Acquire) {
+            continue;
+        }
+        let batch = accel.read_fifo(now_us()).unwrap();
+        if batch.is_empty() {
+            continue;
+        }
+
+        let mut min = [f32::MAX; 3];
+        let mut max = [f32::MIN; 3];
+        for sample in &batch {
+            let values = [sample.accel.x, sample.accel.y, sample.accel.z];
+            // f32::min/maxはfminf/fmaxfを必要とするので比較で求める
+            for axis in 0..3 {
+                if values[axis] < min[axis] {
+                    min[axis] = values[axis];
+                }
+                if values[axis] > max[axis] {
+                    max[axis] = values[axis];
+                }
+            }
+        }
+        let first = batch.sample(0).timestamp_us;
+        let last = batch.sample(batch.len() - 1).timestamp_us;
+        writeln!(
+            &mut serial,
+            "{} samples [{}..{} us] p-p X: {:.3}, Y: {:.3}, Z: {:.3}",
+            batch.len(),
+            first,
+            last,
+            max[0] - min[0],
+            max[1] - min[1],
+            max[2] - min[2],
+        )
+        .unwrap();
+    }
+}
+
+/// LIS3DHのINT1の割り込みハンドラ
+/// ウォーターマークに達するとWTMビットが立ち、FIFOを読み出すとクリアされる
+#[interrupt]
+fn EIC_EXTINT_5() {
+    clear_int1();
+    INT1_ASSERTED.store(true, Ordering::Release);
+}
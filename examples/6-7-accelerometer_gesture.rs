@@ -0,0 +1,111 @@
+//! 6-7 加速度センサ/I2Cのサンプルコードです。
+//! LIS3DHのタップ、ダブルタップ、自由落下、向き検出をINT1ピンの割り込みで受け取り、
+//! 検出したイベントをシリアルターミナルに出力します。
+//!
+//! ### 実行方法
+//! ```sh
+//! $ cargo hf2 --example 6-7-accelerometer_gesture
+//! ```
+
+#![no_std]
+#![no_main]
+
+use panic_halt as _;
+use wio_terminal as wio;
+
+use core::fmt::Write;
+use core::sync::atomic::{AtomicBool, Ordering};
+use cortex_m::peripheral::NVIC;
+use wio::entry;
+use wio::hal::clock::GenericClockController;
+use wio::hal::gpio::*;
+use wio::hal::sercom::*;
+use wio::pac::{interrupt, Peripherals};
+use wio::prelude::*;
+use wio_examples::lis3dh::int1::{
+    clear_int1, configure_eic, configure_int1_pin,
+};
+use wio_examples::lis3dh::{
+    Config, DataRate, FreeFallConfig, GestureConfig, Lis3dh, Mode, Range,
+    TapConfig, ADDRESS,
+};
+
+// 割り込みハンドラからmain()関数へ、INT1がアサートされたことを通知する
+static INT1_ASSERTED: AtomicBool = AtomicBool::new(false);
+
+#[entry]
+fn main() -> ! {
+    let mut peripherals = Peripherals::take().unwrap();
+    let mut clocks = GenericClockController::with_external_32kosc(
+        peripherals.GCLK,
+        &mut peripherals.MCLK,
+        &mut peripherals.OSC32KCTRL,
+        &mut peripherals.OSCCTRL,
+        &mut peripherals.NVMCTRL,
+    );
+
+    // PORTはPins構造体に渡してしまうので、先にPC21をEICに接続しておく
+    configure_int1_pin(&peripherals.PORT);
+    let mut sets = wio::Pins::new(peripherals.PORT).split();
+
+    // UARTドライバオブジェクトを初期化する
+    let mut serial = sets.uart.init(
+        &mut clocks,
+        115200.hz(),
+        peripherals.SERCOM2,
+        &mut peripherals.MCLK,
+        &mut sets.port,
+    );
+
+    // 加速度センサが接続されているSERCOM4をI2Cとして初期化する
+    let gclk0 = clocks.gclk0();
+    let i2c: I2CMaster4<Sercom4Pad0<Pa13<PfD>>, Sercom4Pad1<Pa12<PfD>>> =
+        I2CMaster4::new(
+            &clocks.sercom4_core(&gclk0).unwrap(),
+            400.khz(),
+            peripherals.SERCOM4,
+            &mut peripherals.MCLK,
+            sets.accelerometer.sda.into_pad(&mut sets.port),
+            sets.accelerometer.scl.into_pad(&mut sets.port),
+        );
+    let mut accel = Lis3dh::new(i2c, ADDRESS).unwrap();
+    // タップの検出には400[Hz]程度のデータレートが必要
+    accel
+        .configure(Config {
+            range: Range::G4,
+            data_rate: DataRate::Hz400,
+            mode: Mode::Normal,
+            high_pass: None,
+        })
+        .unwrap();
+    accel
+        .enable_gestures(&GestureConfig {
+            tap: Some(TapConfig::default()),
+            free_fall: Some(FreeFallConfig::default()),
+            orientation: true,
+        })
+        .unwrap();
+
+    // INT1の立ち上がりエッジで割り込みが発生するようにする
+    configure_eic(&peripherals.EIC, &mut peripherals.MCLK);
+    unsafe {
+        NVIC::unmask(interrupt::EIC_EXTINT_5);
+    }
+
+    writeln!(&mut serial, "waiting for gestures...").unwrap();
+    loop {
+        if INT1_ASSERTED.swap(false, Ordering::Acquire) {
+            for event in accel.read_events().unwrap().iter() {
+                writeln!(&mut serial, "{:?}", event).unwrap();
+            }
+        }
+    }
+}
+
+/// LIS3DHのINT1の割り込みハンドラ
+/// I2Cの通信には時間がかかるので、イベントの読み出しはmain()関数で行う
+#[interrupt]
+fn EIC_EXTINT_5() {
+    clear_int1();
+    INT1_ASSERTED.store(true, Ordering::Release);
+}
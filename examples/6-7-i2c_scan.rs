@@ -0,0 +1,188 @@
+//! 6-7 I2Cバスのスキャンのサンプルコードです。
+//! 左側のGroveポート (SERCOM3) と内蔵のLIS3DHがつながっているバス (SERCOM4) の
+//! 7ビットアドレスをすべて調べて、応答したデバイスのアドレスをシリアルターミナルとLCDに表示します。
+//! その後、内蔵のバスをLIS3DHのドライバと共有し、加速度の読み出しと再スキャンを交互に行います。
+//! ボタン1 (一番右のボタン) を押すと再スキャンします。
+//!
+//! ### 実行方法
+//! ```sh
+//! $ cargo hf2 --example 6-7-i2c_scan
+//! ```
+
+#![no_std]
+#![no_main]
+
+use panic_halt as _;
+use wio_terminal as wio;
+
+use core::fmt::Write;
+use eg::{
+    egrectangle, egtext, fonts::Font8x16, pixelcolor::Rgb565, prelude::*,
+    primitive_style, text_style,
+};
+use embedded_graphics as eg;
+use heapless::consts::*;
+use heapless::String;
+use wio::hal::clock::GenericClockController;
+use wio::hal::delay::Delay;
+use wio::hal::gpio::*;
+use wio::hal::sercom::*;
+use wio::pac::{CorePeripherals, Peripherals};
+use wio::prelude::*;
+use wio::{entry, Pins};
+use wio_examples::i2c_bus::{scan, Addresses, LocalBus, SharedBus};
+use wio_examples::lis3dh::{Lis3dh, ADDRESS};
+
+const SCREEN_WIDTH: i32 = 320;
+const SCREEN_HEIGHT: i32 = 240;
+const LINE_HEIGHT: i32 = 18;
+// スキャンするバスの名前
+const BUS_NAMES: [&str; 2] = ["Grove (SERCOM3)", "Internal (SERCOM4)"];
+
+#[entry]
+fn main() -> ! {
+    let mut peripherals = Peripherals::take().unwrap();
+    let core = CorePeripherals::take().unwrap();
+    let mut clocks = GenericClockController::with_external_32kosc(
+        peripherals.GCLK,
+        &mut peripherals.MCLK,
+        &mut peripherals.OSC32KCTRL,
+        &mut peripherals.OSCCTRL,
+        &mut peripherals.NVMCTRL,
+    );
+    let mut delay = Delay::new(core.SYST, &mut clocks);
+    // Groveポートのピンはsplit()で分けられたセットに含まれないので、Pinsから直接使う
+    let mut pins = Pins::new(peripherals.PORT);
+
+    // UARTドライバオブジェクトを初期化する
+    let mut serial = wio::UART {
+        rx: pins.rxd,
+        tx: pins.txd,
+    }
+    .init(
+        &mut clocks,
+        115200.hz(),
+        peripherals.SERCOM2,
+        &mut peripherals.MCLK,
+        &mut pins.port,
+    );
+
+    // LCDの初期化
+    let (mut display, _backlight) = wio::Display {
+        miso: pins.lcd_miso,
+        mosi: pins.lcd_mosi,
+        sck: pins.lcd_sck,
+        cs: pins.lcd_cs,
+        dc: pins.lcd_dc,
+        reset: pins.lcd_reset,
+        backlight: pins.lcd_backlight,
+    }
+    .init(
+        &mut clocks,
+        peripherals.SERCOM7,
+        &mut peripherals.MCLK,
+        &mut pins.port,
+        58.mhz(),
+        &mut delay,
+    )
+    .unwrap();
+
+    // 左側のGroveポートはSERCOM3のI2C (SDA: PA17, SCL: PA16)
+    let gclk0 = clocks.gclk0();
+    let mut grove: I2CMaster3<Sercom3Pad0<Pa17<PfD>>, Sercom3Pad1<Pa16<PfD>>> =
+        I2CMaster3::new(
+            &clocks.sercom3_core(&gclk0).unwrap(),
+            100.khz(),
+            peripherals.SERCOM3,
+            &mut peripherals.MCLK,
+            pins.i2c1_sda.into_pad(&mut pins.port),
+            pins.i2c1_scl.into_pad(&mut pins.port),
+        );
+
+    // 内蔵のLIS3DHはSERCOM4のI2C (SDA: PA13, SCL: PA12)
+    let internal: I2CMaster4<Sercom4Pad0<Pa13<PfD>>, Sercom4Pad1<Pa12<PfD>>> =
+        I2CMaster4::new(
+            &clocks.sercom4_core(&gclk0).unwrap(),
+            100.khz(),
+            peripherals.SERCOM4,
+            &mut peripherals.MCLK,
+            pins.i2c0_sda.into_pad(&mut pins.port),
+            pins.i2c0_scl.into_pad(&mut pins.port),
+        );
+
+    // 内蔵のバスを共有して、スキャン用とLIS3DH用のハンドルを作る
+    let bus: LocalBus<_> = SharedBus::new(internal);
+    let mut scanner = bus.acquire();
+    let mut found = [scan(&mut grove), scan(&mut scanner)];
+    report(&mut serial, &found);
+    draw(&mut display, &found).unwrap();
+
+    let mut accel = Lis3dh::new(bus.acquire(), ADDRESS).unwrap();
+    let button = pins.button1.into_floating_input(&mut pins.port);
+    loop {
+        if button.is_low().unwrap() {
+            found = [scan(&mut grove), scan(&mut scanner)];
+            report(&mut serial, &found);
+            draw(&mut display, &found).unwrap();
+        }
+        let value = accel.read_g().unwrap();
+        writeln!(
+            &mut serial,
+            "X: {:.2}, Y: {:.2}, Z: {:.2}",
+            value.x, value.y, value.z
+        )
+        .unwrap();
+        delay.delay_ms(500u16);
+    }
+}
+
+// 見つかったアドレスをシリアルターミナルに出力する
+fn report<W: Write>(serial: &mut W, found: &[Addresses; 2]) {
+    for (name, addresses) in BUS_NAMES.iter().zip(found.iter()) {
+        writeln!(serial, "{}: {} device(s) found", name, addresses.len())
+            .unwrap();
+        for address in addresses.iter() {
+            writeln!(serial, "  0x{:02X}", address).unwrap();
+        }
+    }
+}
+
+// 見つかったアドレスをバスごとにLCDに一覧表示する
+fn draw<T>(display: &mut T, found: &[Addresses; 2]) -> Result<(), T::Error>
+where
+    T: DrawTarget<Rgb565>,
+{
+    egrectangle!(
+        top_left = (0, 0),
+        bottom_right = (SCREEN_WIDTH - 1, SCREEN_HEIGHT - 1),
+        style = primitive_style!(fill_color = Rgb565::BLACK)
+    )
+    .draw(display)?;
+
+    let style = text_style!(font = Font8x16, text_color = Rgb565::WHITE);
+    // 画面の上半分にGroveポート、下半分に内蔵のバスを表示する
+    let half = SCREEN_HEIGHT / 2;
+    for (i, (name, addresses)) in BUS_NAMES.iter().zip(found.iter()).enumerate()
+    {
+        let top = half * i as i32;
+        let mut text = String::<U32>::new();
+        write!(text, "{}: {}", name, addresses.len()).unwrap();
+        egtext!(text = text.as_str(), top_left = (4, top + 4), style = style)
+            .draw(display)?;
+
+        // 収まる分を4列に並べる
+        for (j, address) in addresses.iter().enumerate() {
+            let x = 4 + (j as i32 % 4) * 72;
+            let y = top + 4 + LINE_HEIGHT * (1 + j as i32 / 4);
+            if y + LINE_HEIGHT > top + half {
+                break;
+            }
+            // heapless 0.5のString::clear()は範囲外を参照するので使わない
+            text = String::new();
+            write!(text, "0x{:02X}", address).unwrap();
+            egtext!(text = text.as_str(), top_left = (x, y), style = style)
+                .draw(display)?;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,116 @@
+//! 6-8 Grove温湿度センサのサンプルコードです。
+//! 左側のGroveポート (I2C) に温湿度センサ (SHT31) を、
+//! 右側のGroveポートの D0 に温湿度センサ (DHT11) を接続します。
+//! 2秒ごとに両方のセンサで測定し、結果をシリアルターミナルに出力します。
+//!
+//! ### 実行方法
+//! ```sh
+//! $ cargo hf2 --example 6-8-grove_temperature_humidity
+//! ```
+
+#![no_std]
+#![no_main]
+
+use panic_halt as _;
+use wio_terminal as wio;
+
+use core::fmt::Write;
+use wio::entry;
+use wio::hal::clock::GenericClockController;
+use wio::hal::delay::Delay;
+use wio::hal::gpio::*;
+use wio::hal::sercom::*;
+use wio::pac::{CorePeripherals, Peripherals};
+use wio::prelude::*;
+use wio_examples::grove::dht::{self, Dht, Kind};
+use wio_examples::grove::sht3x::{self, Repeatability, Sht3x};
+
+// 測定の間隔 [ms] (DHT11は1秒以上空ける必要がある)
+const INTERVAL_MS: u16 = 2000;
+
+#[entry]
+fn main() -> ! {
+    let mut peripherals = Peripherals::take().unwrap();
+    let core = CorePeripherals::take().unwrap();
+    let mut clocks = GenericClockController::with_external_32kosc(
+        peripherals.GCLK,
+        &mut peripherals.MCLK,
+        &mut peripherals.OSC32KCTRL,
+        &mut peripherals.OSCCTRL,
+        &mut peripherals.NVMCTRL,
+    );
+    let mut delay = Delay::new(core.SYST, &mut clocks);
+
+    // Groveポートのピンはsplit()で分けられたセットに含まれないので、Pinsから直接使う
+    let mut pins = wio::Pins::new(peripherals.PORT);
+    // UARTドライバオブジェクトを初期化する
+    let mut serial = wio::UART {
+        rx: pins.rxd,
+        tx: pins.txd,
+    }
+    .init(
+        &mut clocks,
+        115200.hz(),
+        peripherals.SERCOM2,
+        &mut peripherals.MCLK,
+        &mut pins.port,
+    );
+
+    // 左側のGroveポートはSERCOM3のI2C (SDA: PA17, SCL: PA16)
+    let gclk0 = clocks.gclk0();
+    let i2c: I2CMaster3<Sercom3Pad0<Pa17<PfD>>, Sercom3Pad1<Pa16<PfD>>> =
+        I2CMaster3::new(
+            &clocks.sercom3_core(&gclk0).unwrap(),
+            100.khz(),
+            peripherals.SERCOM3,
+            &mut peripherals.MCLK,
+            pins.i2c1_sda.into_pad(&mut pins.port),
+            pins.i2c1_scl.into_pad(&mut pins.port),
+        );
+    let mut sht31 = Sht3x::new(i2c, sht3x::ADDRESS);
+
+    // DHT11は1本の信号線で双方向に通信するので、読み出し可能なオープンドレイン出力にする
+    let sig = pins.a0_d0.into_readable_open_drain_output(&mut pins.port);
+    let mut dht11 = Dht::new(sig, Kind::Dht11).unwrap();
+
+    // 電源を入れてからセンサが安定するまで待つ
+    delay.delay_ms(1000u16);
+    loop {
+        match sht31.measure(Repeatability::High, &mut delay) {
+            Ok(m) => writeln!(
+                &mut serial,
+                "SHT31: {:.1} C, {:.1} %",
+                m.temperature, m.humidity
+            )
+            .unwrap(),
+            Err(sht3x::Error::Crc) => {
+                writeln!(&mut serial, "SHT31: CRC error").unwrap()
+            }
+            Err(sht3x::Error::I2c(e)) => {
+                writeln!(&mut serial, "SHT31: I2C error {:?}", e).unwrap()
+            }
+        }
+
+        // ビットの長さをマイクロ秒単位で測るので、読み出し中は割り込みを禁止する
+        let result = cortex_m::interrupt::free(|_| dht11.read(&mut delay));
+        match result {
+            Ok(m) => writeln!(
+                &mut serial,
+                "DHT11: {:.1} C, {:.1} %",
+                m.temperature, m.humidity
+            )
+            .unwrap(),
+            Err(dht::Error::Timeout) => {
+                writeln!(&mut serial, "DHT11: no response").unwrap()
+            }
+            Err(dht::Error::Checksum) => {
+                writeln!(&mut serial, "DHT11: checksum error").unwrap()
+            }
+            Err(dht::Error::Pin(e)) => {
+                writeln!(&mut serial, "DHT11: pin error {:?}", e).unwrap()
+            }
+        }
+
+        delay.delay_ms(INTERVAL_MS);
+    }
+}
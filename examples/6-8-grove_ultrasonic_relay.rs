@@ -0,0 +1,98 @@
+//! 6-8 Groveモジュールのサンプルコードです。
+//! 右側のGroveポートの D0 に超音波距離センサを、D1 にリレーを接続します。
+//! 100[mm]より近くに物体があるとリレーをオンにし、距離をシリアルターミナルに出力します。
+//!
+//! ### 実行方法
+//! ```sh
+//! $ cargo hf2 --example 6-8-grove_ultrasonic_relay
+//! ```
+
+#![no_std]
+#![no_main]
+
+use panic_halt as _;
+use wio_terminal as wio;
+
+use core::fmt::Write;
+use cortex_m::peripheral::DWT;
+use wio::entry;
+use wio::hal::clock::GenericClockController;
+use wio::hal::delay::Delay;
+use wio::pac::{CorePeripherals, Peripherals};
+use wio::prelude::*;
+use wio_examples::grove::relay::Relay;
+use wio_examples::grove::ultrasonic::{Error, Ultrasonic};
+
+// リレーをオンにする距離 [mm]
+const THRESHOLD_MM: f32 = 100.0;
+// CPUクロック [MHz]。時刻の計算に使う
+const CPU_MHZ: u32 = 120;
+
+#[entry]
+fn main() -> ! {
+    let mut peripherals = Peripherals::take().unwrap();
+    let mut core = CorePeripherals::take().unwrap();
+    let mut clocks = GenericClockController::with_external_32kosc(
+        peripherals.GCLK,
+        &mut peripherals.MCLK,
+        &mut peripherals.OSC32KCTRL,
+        &mut peripherals.OSCCTRL,
+        &mut peripherals.NVMCTRL,
+    );
+    let mut delay = Delay::new(core.SYST, &mut clocks);
+
+    // エコーの長さを測るためにサイクルカウンタを動かす
+    core.DCB.enable_trace();
+    core.DWT.enable_cycle_counter();
+
+    let mut sets = wio::Pins::new(peripherals.PORT).split();
+    // UARTドライバオブジェクトを初期化する
+    let mut serial = sets.uart.init(
+        &mut clocks,
+        115200.hz(),
+        peripherals.SERCOM2,
+        &mut peripherals.MCLK,
+        &mut sets.port,
+    );
+
+    // SIG線で入出力を切り替えるので、読み出し可能なオープンドレイン出力にする
+    let sig = sets
+        .header_pins
+        .a0_d0
+        .into_readable_open_drain_output(&mut sets.port);
+    let mut ranger = Ultrasonic::new(sig).unwrap();
+    let relay_pin =
+        sets.header_pins.a1_d1.into_push_pull_output(&mut sets.port);
+    let mut relay = Relay::new(relay_pin).unwrap();
+
+    // サイクルカウンタは約36秒でオーバーフローするので、差分をマイクロ秒に積算する
+    let mut last_cycles = DWT::get_cycle_count();
+    let mut elapsed_cycles = 0u32;
+    let mut timestamp_us = 0u32;
+    let mut now_us = move || {
+        let cycles = DWT::get_cycle_count();
+        elapsed_cycles += cycles.wrapping_sub(last_cycles);
+        last_cycles = cycles;
+        timestamp_us = timestamp_us.wrapping_add(elapsed_cycles / CPU_MHZ);
+        elapsed_cycles %= CPU_MHZ;
+        timestamp_us
+    };
+    loop {
+        match ranger.measure_mm(&mut delay, &mut now_us) {
+            Ok(distance) => {
+                relay.set(distance < THRESHOLD_MM).unwrap();
+                writeln!(&mut serial, "{:.0} mm", distance).unwrap();
+            }
+            Err(Error::Timeout) => {
+                relay.turn_off().unwrap();
+                writeln!(&mut serial, "out of range").unwrap();
+            }
+            Err(Error::Pin(e)) => {
+                relay.turn_off().unwrap();
+                writeln!(&mut serial, "pin error: {:?}", e).unwrap();
+            }
+        }
+        // 前回のエコーが収まるまで待つ
+        delay.delay_ms(100u16);
+    }
+}
@@ -0,0 +1,135 @@
+//! 7-10 日本語の表示のサンプルコードです。
+//! ビルド時に作った日本語フォントのサブセットで、見出しと折り返した文章、
+//! 日本語のメニューを表示します。5方向スイッチの上下で項目を選びます。
+//!
+//! フォントには、このサンプルとライブラリの文字列リテラルで使われている文字だけが含まれます。
+//! 元のフォントにはUnicodeのBDF (GNU Unifontなど) かTTF/OTFを指定します。
+//!
+//! ### 実行方法
+//! ```sh
+//! $ WIO_JP_FONT=/path/to/unifont.bdf cargo hf2 --example 7-10-japanese --features japanese --release
+//! ```
+
+#![no_std]
+#![no_main]
+
+use embedded_graphics as eg;
+use panic_halt as _;
+use wio_terminal as wio;
+
+use eg::prelude::*;
+use eg::primitives::Rectangle;
+use wio::hal::clock::GenericClockController;
+use wio::hal::delay::Delay;
+use wio::pac::{CorePeripherals, Peripherals};
+use wio::prelude::*;
+use wio::{entry, Pins};
+use wio_examples::font::{self, japanese, TextStyle};
+use wio_examples::ui::layout::{self, inset, row, split_top, Alignment};
+use wio_examples::ui::{fill, Theme};
+
+const MESSAGE: &str = "ビルド時に、文字列で使われている文字だけを集めて\
+                       フォントを作ります。漢字やかなを全部入れなくても、\
+                       日本語の表示ができます。";
+const ITEMS: [&str; 4] = ["温度", "湿度", "明るさ", "設定"];
+
+#[entry]
+fn main() -> ! {
+    let mut peripherals = Peripherals::take().unwrap();
+    let core = CorePeripherals::take().unwrap();
+    let mut clocks = GenericClockController::with_external_32kosc(
+        peripherals.GCLK,
+        &mut peripherals.MCLK,
+        &mut peripherals.OSC32KCTRL,
+        &mut peripherals.OSCCTRL,
+        &mut peripherals.NVMCTRL,
+    );
+    let mut delay = Delay::new(core.SYST, &mut clocks);
+    let mut sets = Pins::new(peripherals.PORT).split();
+
+    // ディスプレイドライバを初期化する
+    let (mut display, _backlight) = sets
+        .display
+        .init(
+            &mut clocks,
+            peripherals.SERCOM7,
+            &mut peripherals.MCLK,
+            &mut sets.port,
+            58.mhz(),
+            &mut delay,
+        )
+        .unwrap();
+
+    // 5方向スイッチ (押されるとLowになる)
+    let up = sets.buttons.switch_x.into_floating_input(&mut sets.port);
+    let down = sets.buttons.switch_b.into_floating_input(&mut sets.port);
+
+    let theme = Theme::DARK;
+    let jp = japanese::font();
+    let (title, body) = split_top(inset(layout::screen(), 8), jp.line_height());
+    let (text, menu) = split_top(body, jp.line_height() * 4 + 8);
+
+    display.clear(theme.background).unwrap();
+    font::draw_wrapped(
+        &mut display,
+        &jp,
+        "こんにちは、Wio Terminal",
+        title,
+        Alignment::Center,
+        &TextStyle::new(theme.accent, theme.background),
+    )
+    .unwrap();
+    font::draw_wrapped(
+        &mut display,
+        &jp,
+        MESSAGE,
+        text,
+        Alignment::Left,
+        &TextStyle::new(theme.foreground, theme.background),
+    )
+    .unwrap();
+
+    let mut selected = 0;
+    let mut changed = true;
+    let mut was_moved = false;
+    loop {
+        // スイッチの変化 (押された瞬間) で項目を選ぶ
+        let moved = up.is_low().unwrap() || down.is_low().unwrap();
+        if moved && !was_moved {
+            selected = if up.is_low().unwrap() {
+                (selected + ITEMS.len() - 1) % ITEMS.len()
+            } else {
+                (selected + 1) % ITEMS.len()
+            };
+            changed = true;
+        }
+        was_moved = moved;
+
+        if changed {
+            for (i, item) in ITEMS.iter().enumerate() {
+                let area = row(menu, i as i32, ITEMS.len() as i32, 2);
+                let (color, background) = if i == selected {
+                    (theme.background, theme.accent)
+                } else {
+                    (theme.foreground, theme.background)
+                };
+                fill(&mut display, area, background).unwrap();
+                let area = Rectangle::new(
+                    area.top_left + Point::new(8, 0),
+                    area.bottom_right,
+                );
+                font::draw_wrapped(
+                    &mut display,
+                    &jp,
+                    item,
+                    area,
+                    Alignment::Left,
+                    &TextStyle::new(color, background),
+                )
+                .unwrap();
+            }
+            changed = false;
+        }
+        delay.delay_ms(20u16);
+    }
+}
@@ -0,0 +1,115 @@
+//! 7-3 LCD/SPIのサンプルコードです。
+//! ILI9341のコマンドドライバを使って、ディスプレイIDとDisplay Power Modeを取得し、
+//! 画面を塗りつぶしてから垂直スクロールさせます。
+//!
+//! ### 実行方法
+//! ```sh
+//! $ cargo hf2 --example 7-3-lcd_commands
+//! ```
+
+#![no_std]
+#![no_main]
+
+use panic_halt as _;
+use wio_terminal as wio;
+
+use core::fmt::Write;
+use wio::entry;
+use wio::hal::clock::GenericClockController;
+use wio::hal::delay::Delay;
+use wio::hal::gpio::*;
+use wio::hal::hal::spi;
+use wio::hal::sercom::*;
+use wio::pac::{CorePeripherals, Peripherals};
+use wio::prelude::*;
+use wio_examples::lcd::{Lcd, MemoryAccess, HEIGHT, WIDTH};
+
+// LCDが接続されているSPI (MISO: PB18, MOSI: PB19, SCK: PB20)
+type Spi = SPIMaster7<
+    Sercom7Pad2<Pb18<PfD>>,
+    Sercom7Pad3<Pb19<PfD>>,
+    Sercom7Pad1<Pb20<PfD>>,
+>;
+
+#[entry]
+fn main() -> ! {
+    let mut peripherals = Peripherals::take().unwrap();
+    let core = CorePeripherals::take().unwrap();
+    let mut clocks = GenericClockController::with_external_32kosc(
+        peripherals.GCLK,
+        &mut peripherals.MCLK,
+        &mut peripherals.OSC32KCTRL,
+        &mut peripherals.OSCCTRL,
+        &mut peripherals.NVMCTRL,
+    );
+    let mut delay = Delay::new(core.SYST, &mut clocks);
+
+    let mut sets = wio::Pins::new(peripherals.PORT).split();
+    // UARTドライバオブジェクトを初期化する
+    let mut serial = sets.uart.init(
+        &mut clocks,
+        115200.hz(),
+        peripherals.SERCOM2,
+        &mut peripherals.MCLK,
+        &mut sets.port,
+    );
+
+    // LCDが接続されているSERCOM7をSPIとして初期化する
+    let gclk0 = clocks.gclk0();
+    let spi: Spi = SPIMaster7::new(
+        &clocks.sercom7_core(&gclk0).unwrap(),
+        20.mhz(),
+        spi::MODE_0,
+        peripherals.SERCOM7,
+        &mut peripherals.MCLK,
+        (
+            sets.display.miso.into_pad(&mut sets.port),
+            sets.display.mosi.into_pad(&mut sets.port),
+            sets.display.sck.into_pad(&mut sets.port),
+        ),
+    );
+    let dc = sets.display.dc.into_push_pull_output(&mut sets.port);
+    let cs = sets.display.cs.into_push_pull_output(&mut sets.port);
+    let rst = sets.display.reset.into_push_pull_output(&mut sets.port);
+    let mut backlight =
+        sets.display.backlight.into_push_pull_output(&mut sets.port);
+    backlight.set_high().unwrap();
+
+    let mut lcd = Lcd::new(spi, dc, cs, rst).unwrap();
+    lcd.hard_reset(&mut delay).unwrap();
+    let id = lcd.read_display_id().unwrap();
+    let power_mode = lcd.read_power_mode().unwrap();
+    writeln!(
+        &mut serial,
+        "ID: {:02X} {:02X} {:02X}, power mode: {:02X}",
+        id.manufacturer, id.version, id.module, power_mode.0
+    )
+    .unwrap();
+
+    lcd.init(MemoryAccess::landscape(), &mut delay).unwrap();
+    let power_mode = lcd.read_power_mode().unwrap();
+    writeln!(
+        &mut serial,
+        "sleep out: {}, display on: {}",
+        power_mode.sleep_out(),
+        power_mode.display_on()
+    )
+    .unwrap();
+
+    // 16ピクセル幅の縞模様を描く
+    let colors = [0xF800u16, 0x07E0, 0x001F, 0xFFFF];
+    for (i, x) in (0..WIDTH).step_by(16).enumerate() {
+        let color = colors[i % colors.len()];
+        let pixels = [color; 16 * HEIGHT as usize];
+        lcd.write_pixels(x, 0, x + 15, HEIGHT - 1, &pixels).unwrap();
+    }
+
+    // フレームメモリの行 (横長表示では列) 全体をスクロール領域にして動かす
+    lcd.set_scroll_area(0, WIDTH, 0).unwrap();
+    let mut line = 0;
+    loop {
+        lcd.set_scroll_start(line).unwrap();
+        line = (line + 1) % WIDTH;
+        delay.delay_ms(10u16);
+    }
+}
@@ -0,0 +1,123 @@
+//! 7-4 LCDの自動回転のサンプルコードです。
+//! 加速度センサで本体の傾きを検出して、LCDの表示を縦横に回転させます。
+//!
+//! ### 実行方法
+//! ```sh
+//! $ cargo hf2 --example 7-4-auto_rotate
+//! ```
+
+#![no_std]
+#![no_main]
+
+use embedded_graphics as eg;
+use panic_halt as _;
+use wio_terminal as wio;
+
+use accelerometer::Accelerometer;
+use core::fmt::Write;
+use eg::{
+    egrectangle, fonts::*, pixelcolor::Rgb565, prelude::*, primitive_style,
+    style::*,
+};
+use heapless::consts::*;
+use heapless::String;
+use wio::hal::clock::GenericClockController;
+use wio::hal::delay::Delay;
+use wio::pac::{CorePeripherals, Peripherals};
+use wio::prelude::*;
+use wio::{entry, Pins};
+use wio_examples::rotation::{AutoRotate, AutoRotateConfig, Tilt};
+
+#[entry]
+fn main() -> ! {
+    let mut peripherals = Peripherals::take().unwrap();
+    let core = CorePeripherals::take().unwrap();
+    let mut clocks = GenericClockController::with_external_32kosc(
+        peripherals.GCLK,
+        &mut peripherals.MCLK,
+        &mut peripherals.OSC32KCTRL,
+        &mut peripherals.OSCCTRL,
+        &mut peripherals.NVMCTRL,
+    );
+    let mut delay = Delay::new(core.SYST, &mut clocks);
+    let mut sets = Pins::new(peripherals.PORT).split();
+
+    // ディスプレイドライバを初期化する
+    let (mut display, _backlight) = sets
+        .display
+        .init(
+            &mut clocks,
+            peripherals.SERCOM7,
+            &mut peripherals.MCLK,
+            &mut sets.port,
+            58.mhz(),
+            &mut delay,
+        )
+        .unwrap();
+
+    // 加速度センサドライバオブジェクトを初期化する
+    let mut accel = sets.accelerometer.init(
+        &mut clocks,
+        peripherals.SERCOM4,
+        &mut peripherals.MCLK,
+        &mut sets.port,
+    );
+
+    let mut rotate = AutoRotate::new(AutoRotateConfig::default(), Tilt::TopUp);
+    display
+        .set_orientation(rotate.tilt().orientation())
+        .unwrap();
+    draw(&mut display, rotate.tilt()).unwrap();
+
+    loop {
+        let value = accel.accel_norm().unwrap();
+        // 向きが変わったら、LCDの向きを変えてから画面全体を描き直す
+        if let Some(tilt) = rotate.update(value) {
+            display.set_orientation(tilt.orientation()).unwrap();
+            draw(&mut display, tilt).unwrap();
+        }
+        delay.delay_ms(50u16);
+    }
+}
+
+// 画面の大きさは向きによって変わるので、size()から配置を決める
+fn draw<T>(display: &mut T, tilt: Tilt) -> Result<(), T::Error>
+where
+    T: DrawTarget<Rgb565>,
+{
+    let size = display.size();
+    let (width, height) = (size.width as i32, size.height as i32);
+
+    egrectangle!(
+        top_left = (0, 0),
+        bottom_right = (width - 1, height - 1),
+        style = primitive_style!(
+            stroke_color = Rgb565::GREEN,
+            stroke_width = 4,
+            fill_color = Rgb565::BLACK
+        )
+    )
+    .draw(display)?;
+
+    let mut text = String::<U32>::new();
+    write!(text, "{:?} {}x{}", tilt, width, height).unwrap();
+    let font_size = Font12x16::CHARACTER_SIZE;
+    let text_width = font_size.width as i32 * text.len() as i32;
+    Text::new(
+        text.as_str(),
+        Point::new(
+            (width - text_width) / 2,
+            (height - font_size.height as i32) / 2,
+        ),
+    )
+    .into_styled(TextStyle::new(Font12x16, Rgb565::WHITE))
+    .draw(display)?;
+
+    // 画面の上端に印を付けて、どちらが上になっているか分かるようにする
+    egrectangle!(
+        top_left = (width / 2 - 20, 8),
+        bottom_right = (width / 2 + 20, 16),
+        style = primitive_style!(fill_color = Rgb565::RED)
+    )
+    .draw(display)
+}
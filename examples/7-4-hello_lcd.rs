@@ -39,6 +39,7 @@ fn main() -> ! {
     // TODO: LCDを黒色で塗りつぶす
 
     // TODO: 画面情報に「Hello Wio Terminal!」と表示する
+    //       描画の例は`wio_examples::screens::hello()`にある
 
     loop {}
 }
@@ -0,0 +1,65 @@
+//! 7-4 Wio TerminalのLCDにHello World!のサンプルコードです。
+//! リポジトリ内の画像アセットを使って、Wio Terminal の LCD にスプラッシュ画面を表示します。
+//! `7-4-splash` と違い、wio_splash クレートは使いません。
+//! 画像は tools/imgconv で PNG/BMP から変換したアセットにも置き換えられます。
+//!
+//! ### 実行方法
+//! ```sh
+//! $ cargo hf2 --example 7-4-splash_asset --release
+//! ```
+
+#![no_std]
+#![no_main]
+
+use embedded_graphics as eg;
+use panic_halt as _;
+use wio_terminal as wio;
+
+use eg::{pixelcolor::*, prelude::*};
+use wio::hal::clock::GenericClockController;
+use wio::hal::delay::Delay;
+use wio::pac::{CorePeripherals, Peripherals};
+use wio::prelude::*;
+use wio::{entry, Pins};
+use wio_examples::image::Asset;
+use wio_examples::splash::Splash;
+
+// 86x64ピクセルのフェリス (ヘッダなしのRGB565、ビッグエンディアン)
+const FERRIS: &[u8] = include_bytes!("assets/ferris.raw");
+const FERRIS_WIDTH: u16 = 86;
+
+#[entry]
+fn main() -> ! {
+    let mut peripherals = Peripherals::take().unwrap();
+    let core = CorePeripherals::take().unwrap();
+    let mut clocks = GenericClockController::with_external_32kosc(
+        peripherals.GCLK,
+        &mut peripherals.MCLK,
+        &mut peripherals.OSC32KCTRL,
+        &mut peripherals.OSCCTRL,
+        &mut peripherals.NVMCTRL,
+    );
+    let mut delay = Delay::new(core.SYST, &mut clocks);
+    let mut sets = Pins::new(peripherals.PORT).split();
+
+    // ディスプレイドライバを初期化する
+    let (mut display, _backlight) = sets
+        .display
+        .init(
+            &mut clocks,
+            peripherals.SERCOM7,
+            &mut peripherals.MCLK,
+            &mut sets.port,
+            58.mhz(),
+            &mut delay,
+        )
+        .unwrap();
+
+    // スプラッシュ画面を描画する
+    // ヘッダ付きのアセットの場合は Asset::parse() で読み込む
+    let image = Asset::from_raw(FERRIS, FERRIS_WIDTH).unwrap();
+    let splash = Splash::new(image, Rgb565::BLACK);
+    splash.fade_in(&mut display, 16, 30, &mut delay).unwrap();
+
+    loop {}
+}
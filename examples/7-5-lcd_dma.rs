@@ -0,0 +1,149 @@
+//! 7-5 DMAを使ったLCD転送のサンプルコードです。
+//! 画面を40ライン単位の帯に分け、1本の帯をDMAで転送している間に次の帯を描画します。
+//! 2面のバッファを交互に使うことで、CPUの描画とSPIの転送を並行して進めます。
+//!
+//! ### 実行方法
+//! ```sh
+//! $ cargo hf2 --example 7-5-lcd_dma --release
+//! ```
+
+#![no_std]
+#![no_main]
+
+use panic_halt as _;
+use wio_terminal as wio;
+
+use core::ptr::addr_of_mut;
+use cortex_m::peripheral::NVIC;
+use wio::entry;
+use wio::hal::clock::GenericClockController;
+use wio::hal::delay::Delay;
+use wio::hal::gpio::*;
+use wio::hal::hal::spi;
+use wio::hal::sercom::*;
+use wio::pac::{interrupt, CorePeripherals, Peripherals};
+use wio::prelude::*;
+use wio_examples::dmac::DmaController;
+use wio_examples::lcd::{Lcd, MemoryAccess, HEIGHT, WIDTH};
+use wio_examples::lcd_dma::{to_lcd_order, LcdDma};
+
+// 1回に転送する帯の高さ
+const BAND_HEIGHT: u16 = 40;
+const BAND_PIXELS: usize = WIDTH as usize * BAND_HEIGHT as usize;
+
+type Spi = SPIMaster7<
+    Sercom7Pad2<Pb18<PfD>>,
+    Sercom7Pad3<Pb19<PfD>>,
+    Sercom7Pad1<Pb20<PfD>>,
+>;
+type Display = LcdDma<
+    Spi,
+    Pc6<Output<PushPull>>,
+    Pb21<Output<PushPull>>,
+    Pc7<Output<PushPull>>,
+>;
+
+// 描画用と転送用のバッファ2面分
+static mut BUFFERS: [[u16; BAND_PIXELS]; 2] = [[0; BAND_PIXELS]; 2];
+// main() 関数とDMAの割り込みハンドラで共有するリソース
+static mut DISPLAY: Option<Display> = None;
+
+#[entry]
+fn main() -> ! {
+    let mut peripherals = Peripherals::take().unwrap();
+    let core = CorePeripherals::take().unwrap();
+    let mut clocks = GenericClockController::with_external_32kosc(
+        peripherals.GCLK,
+        &mut peripherals.MCLK,
+        &mut peripherals.OSC32KCTRL,
+        &mut peripherals.OSCCTRL,
+        &mut peripherals.NVMCTRL,
+    );
+    let mut delay = Delay::new(core.SYST, &mut clocks);
+    let mut sets = wio::Pins::new(peripherals.PORT).split();
+
+    // LCDが接続されているSERCOM7をSPIとして初期化する
+    let gclk0 = clocks.gclk0();
+    let spi: Spi = SPIMaster7::new(
+        &clocks.sercom7_core(&gclk0).unwrap(),
+        48.mhz(),
+        spi::MODE_0,
+        peripherals.SERCOM7,
+        &mut peripherals.MCLK,
+        (
+            sets.display.miso.into_pad(&mut sets.port),
+            sets.display.mosi.into_pad(&mut sets.port),
+            sets.display.sck.into_pad(&mut sets.port),
+        ),
+    );
+    let dc = sets.display.dc.into_push_pull_output(&mut sets.port);
+    let cs = sets.display.cs.into_push_pull_output(&mut sets.port);
+    let rst = sets.display.reset.into_push_pull_output(&mut sets.port);
+    let mut backlight =
+        sets.display.backlight.into_push_pull_output(&mut sets.port);
+    backlight.set_high().unwrap();
+
+    let mut lcd = Lcd::new(spi, dc, cs, rst).unwrap();
+    lcd.init(MemoryAccess::landscape(), &mut delay).unwrap();
+
+    // DMAコントローラを初期化し、チャネル0をLCDへの転送に使う
+    let dmac = DmaController::new(peripherals.DMAC, &mut peripherals.MCLK);
+    let channels = dmac.split();
+    unsafe {
+        DISPLAY = Some(LcdDma::new(lcd, channels.0));
+        NVIC::unmask(interrupt::DMAC_0);
+    }
+
+    // 1面に描画している間に、もう1面をDMAで転送する
+    // 転送中のバッファはLcdDmaが預かるので、描画と転送が同じバッファを触ることはない
+    let [first, second] = unsafe { &mut *addr_of_mut!(BUFFERS) };
+    let mut buffer: &'static mut [u16] = first;
+    let mut spare: Option<&'static mut [u16]> = Some(second);
+    let mut frame = 0u16;
+    loop {
+        for band in 0..HEIGHT / BAND_HEIGHT {
+            let y0 = band * BAND_HEIGHT;
+            let y1 = y0 + BAND_HEIGHT - 1;
+            render(buffer, y0, frame);
+
+            // 前の帯の転送が終わるのを待ってから、次の転送を始める
+            let mut pixels = buffer;
+            let previous = loop {
+                // 割り込みハンドラと同時にアクセスしないように、割り込みを禁止して呼び出す
+                let result = cortex_m::interrupt::free(move |_| {
+                    let display =
+                        unsafe { (*addr_of_mut!(DISPLAY)).as_mut().unwrap() };
+                    display.start_blit(0, y0, WIDTH - 1, y1, pixels)
+                });
+                match result {
+                    Ok(previous) => break previous,
+                    Err((nb::Error::WouldBlock, returned)) => pixels = returned,
+                    Err((nb::Error::Other(e), _)) => panic!("{:?}", e),
+                }
+            };
+            // 転送が終わって返ってきたバッファに次の帯を描く (最初は空いている方)
+            buffer = previous.or_else(|| spare.take()).unwrap();
+        }
+        frame = frame.wrapping_add(1);
+    }
+}
+
+// 時間とともに流れる格子模様を描く
+fn render(buffer: &mut [u16], top: u16, frame: u16) {
+    for (i, pixel) in buffer.iter_mut().enumerate() {
+        let x = (i % WIDTH as usize) as u16;
+        let y = top + (i / WIDTH as usize) as u16;
+        let r = (x.wrapping_add(frame) >> 3) & 0x1F;
+        let g = ((x ^ y).wrapping_add(frame) >> 2) & 0x3F;
+        let b = (y.wrapping_sub(frame) >> 3) & 0x1F;
+        *pixel = to_lcd_order((r << 11) | (g << 5) | b);
+    }
+}
+
+// LCDへの転送に使うDMAチャネル0の割り込みハンドラ
+#[interrupt]
+fn DMAC_0() {
+    if let Some(display) = unsafe { (*addr_of_mut!(DISPLAY)).as_mut() } {
+        display.on_interrupt();
+    }
+}
@@ -0,0 +1,129 @@
+//! 7-6 前のフレームとの差分だけを転送するフレームバッファのサンプルコードです。
+//! 描画したピクセルを小さなタイルに溜めて、フレームの最後に前のフレームから変わったタイルだけをLCDに転送します。
+//! 毎フレーム四角形を新しい位置に描くだけで、古い位置は背景色で転送されるので、消さずにちらつかずに動きます。
+//!
+//! ### 実行方法
+//! ```sh
+//! $ cargo hf2 --example 7-6-strip_framebuffer --release
+//! ```
+
+#![no_std]
+#![no_main]
+
+use panic_halt as _;
+use wio_terminal as wio;
+
+use core::fmt::Write;
+use eg::{
+    egrectangle, egtext, fonts::Font24x32, pixelcolor::Rgb565, prelude::*,
+    primitive_style, text_style,
+};
+use embedded_graphics as eg;
+use heapless::consts::*;
+use heapless::String;
+use wio::entry;
+use wio::hal::clock::GenericClockController;
+use wio::hal::delay::Delay;
+use wio::hal::gpio::*;
+use wio::hal::hal::spi;
+use wio::hal::sercom::*;
+use wio::pac::{CorePeripherals, Peripherals};
+use wio::prelude::*;
+use wio_examples::framebuffer::StripRenderer;
+use wio_examples::lcd::{Lcd, MemoryAccess};
+
+const SCREEN_WIDTH: i32 = 320;
+const SCREEN_HEIGHT: i32 = 240;
+const BOX_SIZE: i32 = 40;
+
+// LCDが接続されているSPI (MISO: PB18, MOSI: PB19, SCK: PB20)
+type Spi = SPIMaster7<
+    Sercom7Pad2<Pb18<PfD>>,
+    Sercom7Pad3<Pb19<PfD>>,
+    Sercom7Pad1<Pb20<PfD>>,
+>;
+
+#[entry]
+fn main() -> ! {
+    let mut peripherals = Peripherals::take().unwrap();
+    let core = CorePeripherals::take().unwrap();
+    let mut clocks = GenericClockController::with_external_32kosc(
+        peripherals.GCLK,
+        &mut peripherals.MCLK,
+        &mut peripherals.OSC32KCTRL,
+        &mut peripherals.OSCCTRL,
+        &mut peripherals.NVMCTRL,
+    );
+    let mut delay = Delay::new(core.SYST, &mut clocks);
+    let mut sets = wio::Pins::new(peripherals.PORT).split();
+
+    // LCDが接続されているSERCOM7をSPIとして初期化する
+    let gclk0 = clocks.gclk0();
+    let spi: Spi = SPIMaster7::new(
+        &clocks.sercom7_core(&gclk0).unwrap(),
+        48.mhz(),
+        spi::MODE_0,
+        peripherals.SERCOM7,
+        &mut peripherals.MCLK,
+        (
+            sets.display.miso.into_pad(&mut sets.port),
+            sets.display.mosi.into_pad(&mut sets.port),
+            sets.display.sck.into_pad(&mut sets.port),
+        ),
+    );
+    let dc = sets.display.dc.into_push_pull_output(&mut sets.port);
+    let cs = sets.display.cs.into_push_pull_output(&mut sets.port);
+    let rst = sets.display.reset.into_push_pull_output(&mut sets.port);
+    let mut backlight =
+        sets.display.backlight.into_push_pull_output(&mut sets.port);
+    backlight.set_high().unwrap();
+
+    let mut lcd = Lcd::new(spi, dc, cs, rst).unwrap();
+    lcd.init(MemoryAccess::landscape(), &mut delay).unwrap();
+
+    // 最初のフレームでは、描画しなかった部分も背景色 (黒) で転送される
+    let mut renderer = StripRenderer::new(Size::new(
+        SCREEN_WIDTH as u32,
+        SCREEN_HEIGHT as u32,
+    ));
+    let mut count = 0u32;
+    let mut x = 0;
+    let mut dx = 4;
+    loop {
+        renderer
+            .render(&mut lcd, |frame| draw(frame, count, x))
+            .unwrap();
+        count = count.wrapping_add(1);
+        x += dx;
+        if x + dx < 0 || x + dx > SCREEN_WIDTH - BOX_SIZE {
+            dx = -dx;
+        }
+    }
+}
+
+// 1フレーム分の画面全体 (xの位置の四角形とカウンタ) を描く
+// 前のフレームの表示を消す必要はない
+fn draw<T>(display: &mut T, count: u32, x: i32) -> Result<(), T::Error>
+where
+    T: DrawTarget<Rgb565>,
+{
+    egrectangle!(
+        top_left = (x, 160),
+        bottom_right = (x + BOX_SIZE - 1, 160 + BOX_SIZE - 1),
+        style = primitive_style!(fill_color = Rgb565::GREEN)
+    )
+    .draw(display)?;
+
+    let mut text = String::<U16>::new();
+    write!(text, "{:08}", count).unwrap();
+    egtext!(
+        text = text.as_str(),
+        top_left = (64, 60),
+        style = text_style!(
+            font = Font24x32,
+            text_color = Rgb565::WHITE,
+            background_color = Rgb565::BLACK
+        )
+    )
+    .draw(display)
+}
@@ -0,0 +1,106 @@
+//! 7-7 圧縮画像とスプライトのサンプルコードです。
+//! QOI形式で圧縮したフェリスを展開しながらLCDに描画し、
+//! 黒を透過色にしたスプライトとして背景の上を左右に動かします。
+//!
+//! 画像は tools/imgconv で変換したものです。
+//! ```sh
+//! $ cargo run --target x86_64-unknown-linux-gnu -- ferris.png ferris_qoi.bin --qoi
+//! ```
+//!
+//! ### 実行方法
+//! ```sh
+//! $ cargo hf2 --example 7-7-sprites --release
+//! ```
+
+#![no_std]
+#![no_main]
+
+use embedded_graphics as eg;
+use panic_halt as _;
+use wio_terminal as wio;
+
+use eg::{
+    egrectangle, image::Image, pixelcolor::*, prelude::*, primitive_style,
+};
+use wio::hal::clock::GenericClockController;
+use wio::hal::delay::Delay;
+use wio::pac::{CorePeripherals, Peripherals};
+use wio::prelude::*;
+use wio::{entry, Pins};
+use wio_examples::image::Asset;
+use wio_examples::sprite::SpriteSheet;
+
+// 86x64ピクセルのフェリス (11008バイトを1798バイトに圧縮したもの)
+const FERRIS: &[u8] = include_bytes!("assets/ferris_qoi.bin");
+const BACKGROUND: Rgb565 = Rgb565::new(4, 24, 20);
+const SPRITE_Y: i32 = 160;
+
+#[entry]
+fn main() -> ! {
+    let mut peripherals = Peripherals::take().unwrap();
+    let core = CorePeripherals::take().unwrap();
+    let mut clocks = GenericClockController::with_external_32kosc(
+        peripherals.GCLK,
+        &mut peripherals.MCLK,
+        &mut peripherals.OSC32KCTRL,
+        &mut peripherals.OSCCTRL,
+        &mut peripherals.NVMCTRL,
+    );
+    let mut delay = Delay::new(core.SYST, &mut clocks);
+    let mut sets = Pins::new(peripherals.PORT).split();
+
+    // ディスプレイドライバを初期化する
+    let (mut display, _backlight) = sets
+        .display
+        .init(
+            &mut clocks,
+            peripherals.SERCOM7,
+            &mut peripherals.MCLK,
+            &mut sets.port,
+            58.mhz(),
+            &mut delay,
+        )
+        .unwrap();
+
+    egrectangle!(
+        top_left = (0, 0),
+        bottom_right = (319, 239),
+        style = primitive_style!(fill_color = BACKGROUND)
+    )
+    .draw(&mut display)
+    .unwrap();
+
+    // 圧縮された画像は、フレームバッファを使わずに展開しながら描画する
+    let ferris = Asset::parse(FERRIS).unwrap();
+    Image::new(&ferris, Point::new(117, 24))
+        .draw(&mut display)
+        .unwrap();
+
+    // 画像全体を1フレームのスプライトとして、黒い部分を透過させる
+    let sheet = SpriteSheet::new(ferris, ferris.width(), ferris.height())
+        .with_key(Rgb565::BLACK);
+    let size = sheet.frame_size();
+    let mut x = 0;
+    let mut step = 4;
+    loop {
+        sheet
+            .draw_frame(&mut display, 0, Point::new(x, SPRITE_Y))
+            .unwrap();
+        delay.delay_ms(30u16);
+
+        // 透過させた部分に前の位置の絵が残らないように、背景で塗りつぶす
+        egrectangle!(
+            top_left = (x, SPRITE_Y),
+            bottom_right =
+                (x + size.width as i32 - 1, SPRITE_Y + size.height as i32 - 1),
+            style = primitive_style!(fill_color = BACKGROUND)
+        )
+        .draw(&mut display)
+        .unwrap();
+
+        x += step;
+        if x <= 0 || x + size.width as i32 >= 320 {
+            step = -step;
+        }
+    }
+}
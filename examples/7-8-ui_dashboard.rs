@@ -0,0 +1,138 @@
+//! 7-8 UIツールキットのサンプルコードです。
+//! ステータスバー、数値表示、プログレスバー、メーター、ボタンを並べたダッシュボードを表示します。
+//! 5方向スイッチの左右でボタンのフォーカスを移動し、押し込むとボタンを押せます。
+//!
+//! ### 実行方法
+//! ```sh
+//! $ cargo hf2 --example 7-8-ui_dashboard --release
+//! ```
+
+#![no_std]
+#![no_main]
+
+use embedded_graphics as eg;
+use panic_halt as _;
+use wio_terminal as wio;
+
+use eg::fonts::{Font12x16, Font24x32, Font8x16};
+use eg::prelude::*;
+use wio::hal::clock::GenericClockController;
+use wio::hal::delay::Delay;
+use wio::pac::{CorePeripherals, Peripherals};
+use wio::prelude::*;
+use wio::{entry, Pins};
+use wio_examples::ui::layout::{self, column, inset, row, split_top};
+use wio_examples::ui::status_bar;
+use wio_examples::ui::{
+    Button, Focus, Gauge, Label, NumericReadout, ProgressBar, StatusBar, Theme,
+    Widget,
+};
+
+#[entry]
+fn main() -> ! {
+    let mut peripherals = Peripherals::take().unwrap();
+    let core = CorePeripherals::take().unwrap();
+    let mut clocks = GenericClockController::with_external_32kosc(
+        peripherals.GCLK,
+        &mut peripherals.MCLK,
+        &mut peripherals.OSC32KCTRL,
+        &mut peripherals.OSCCTRL,
+        &mut peripherals.NVMCTRL,
+    );
+    let mut delay = Delay::new(core.SYST, &mut clocks);
+    let mut sets = Pins::new(peripherals.PORT).split();
+
+    // ディスプレイドライバを初期化する
+    let (mut display, _backlight) = sets
+        .display
+        .init(
+            &mut clocks,
+            peripherals.SERCOM7,
+            &mut peripherals.MCLK,
+            &mut sets.port,
+            58.mhz(),
+            &mut delay,
+        )
+        .unwrap();
+
+    // 5方向スイッチ (押されるとLowになる)
+    let left = sets.buttons.switch_y.into_floating_input(&mut sets.port);
+    let right = sets.buttons.switch_z.into_floating_input(&mut sets.port);
+    let press = sets.buttons.switch_u.into_floating_input(&mut sets.port);
+
+    // 画面を上下左右に分けてウィジェットを配置する
+    let theme = Theme::DARK;
+    let (_, body) = split_top(layout::screen(), status_bar::HEIGHT);
+    let body = inset(body, 8);
+    let (top, bottom) = split_top(body, 150);
+    let left_area = column(top, 0, 2, 8);
+    let right_area = column(top, 1, 2, 8);
+
+    let mut status = StatusBar::new(theme);
+    status.set_title("Dashboard");
+    let mut caption = Label::new(row(left_area, 0, 4, 4), Font8x16, theme);
+    caption.set_text("Counter");
+    let mut counter =
+        NumericReadout::new(row(left_area, 1, 4, 4), Font24x32, theme, 1, "s");
+    let mut progress = ProgressBar::new(row(left_area, 3, 4, 4), theme, 100);
+    let gauge_center = Point::new(
+        (right_area.top_left.x + right_area.bottom_right.x) / 2,
+        right_area.bottom_right.y - 8,
+    );
+    let mut gauge = Gauge::new(gauge_center, 64, theme, 0, 100);
+    let mut pause =
+        Button::new(column(bottom, 0, 2, 8), Font12x16, theme, "Pause");
+    let mut reset =
+        Button::new(column(bottom, 1, 2, 8), Font12x16, theme, "Reset");
+    let mut focus = Focus::new(2);
+
+    display.clear(theme.background).unwrap();
+    let mut tenths = 0i32;
+    let mut paused = false;
+    let mut was_pressed = false;
+    let mut was_moved = false;
+    loop {
+        // スイッチの変化 (押された瞬間) でフォーカスを移動し、ボタンを押す
+        let moved = left.is_low().unwrap() || right.is_low().unwrap();
+        if moved && !was_moved {
+            if left.is_low().unwrap() {
+                focus.previous();
+            } else {
+                focus.next();
+            }
+        }
+        was_moved = moved;
+        let pressed = press.is_low().unwrap();
+        if pressed && !was_pressed {
+            match focus.index() {
+                0 => paused = !paused,
+                _ => tenths = 0,
+            }
+        }
+        was_pressed = pressed;
+
+        if !paused {
+            tenths += 1;
+        }
+        status.set_status(if paused { "paused" } else { "running" });
+        counter.set_value(tenths);
+        progress.set_value((tenths % 100) as u32);
+        // 三角波でメーターを振る
+        let phase = tenths % 200;
+        gauge.set_value(if phase < 100 { phase } else { 200 - phase });
+        pause.set_focused(focus.is_focused(0));
+        pause.set_pressed(paused);
+        reset.set_focused(focus.is_focused(1));
+
+        // 値が変わったウィジェットだけが描画される
+        status.draw(&mut display).unwrap();
+        caption.draw(&mut display).unwrap();
+        counter.draw(&mut display).unwrap();
+        progress.draw(&mut display).unwrap();
+        gauge.draw(&mut display).unwrap();
+        pause.draw(&mut display).unwrap();
+        reset.draw(&mut display).unwrap();
+
+        delay.delay_ms(100u16);
+    }
+}
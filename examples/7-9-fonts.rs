@@ -0,0 +1,117 @@
+//! 7-9 プロポーショナルフォントのサンプルコードです。
+//! アンチエイリアスしたDejaVu Sansで、折り返した文章を左・中央・右揃えで表示します。
+//! 5方向スイッチの左右で揃え方を切り替えます。
+//!
+//! フォントは tools/fontconv で変換したものです。
+//! ```sh
+//! $ cargo run --target x86_64-unknown-linux-gnu -- DejaVuSans.ttf sans16.bin --size 16 --bpp 4
+//! ```
+//!
+//! ### 実行方法
+//! ```sh
+//! $ cargo hf2 --example 7-9-fonts --release
+//! ```
+
+#![no_std]
+#![no_main]
+
+use embedded_graphics as eg;
+use panic_halt as _;
+use wio_terminal as wio;
+
+use eg::pixelcolor::Rgb565;
+use eg::prelude::*;
+use wio::hal::clock::GenericClockController;
+use wio::hal::delay::Delay;
+use wio::pac::{CorePeripherals, Peripherals};
+use wio::prelude::*;
+use wio::{entry, Pins};
+use wio_examples::font::{self, Font, TextStyle};
+use wio_examples::ui::layout::{self, inset, split_top, Alignment};
+use wio_examples::ui::{fill, Theme};
+
+// 16ピクセルのDejaVu Sans (ASCII、4ビットのアンチエイリアス)
+const SANS16: &[u8] = include_bytes!("assets/sans16.bin");
+const MESSAGE: &str = "Proportional fonts make text on a small screen \
+                       easier to read. Kerning pairs such as AV and To are \
+                       tightened, and 4-bit anti-aliasing smooths the edges.";
+
+#[entry]
+fn main() -> ! {
+    let mut peripherals = Peripherals::take().unwrap();
+    let core = CorePeripherals::take().unwrap();
+    let mut clocks = GenericClockController::with_external_32kosc(
+        peripherals.GCLK,
+        &mut peripherals.MCLK,
+        &mut peripherals.OSC32KCTRL,
+        &mut peripherals.OSCCTRL,
+        &mut peripherals.NVMCTRL,
+    );
+    let mut delay = Delay::new(core.SYST, &mut clocks);
+    let mut sets = Pins::new(peripherals.PORT).split();
+
+    // ディスプレイドライバを初期化する
+    let (mut display, _backlight) = sets
+        .display
+        .init(
+            &mut clocks,
+            peripherals.SERCOM7,
+            &mut peripherals.MCLK,
+            &mut sets.port,
+            58.mhz(),
+            &mut delay,
+        )
+        .unwrap();
+
+    // 5方向スイッチ (押されるとLowになる)
+    let left = sets.buttons.switch_y.into_floating_input(&mut sets.port);
+    let right = sets.buttons.switch_z.into_floating_input(&mut sets.port);
+
+    let theme = Theme::DARK;
+    let sans = Font::parse(SANS16).unwrap();
+    let style = TextStyle::new(theme.foreground, theme.background);
+    let (title, body) = split_top(inset(layout::screen(), 8), 24);
+
+    display.clear(theme.background).unwrap();
+    font::draw_text(
+        &mut display,
+        &sans,
+        "Wio Terminal",
+        Point::new(title.top_left.x, title.top_left.y + sans.ascent()),
+        &TextStyle::new(theme.accent, theme.background),
+    )
+    .unwrap();
+
+    let alignments = [Alignment::Left, Alignment::Center, Alignment::Right];
+    let mut index = 0;
+    let mut changed = true;
+    let mut was_moved = false;
+    loop {
+        // スイッチの変化 (押された瞬間) で揃え方を切り替える
+        let moved = left.is_low().unwrap() || right.is_low().unwrap();
+        if moved && !was_moved {
+            index = if left.is_low().unwrap() {
+                (index + alignments.len() - 1) % alignments.len()
+            } else {
+                (index + 1) % alignments.len()
+            };
+            changed = true;
+        }
+        was_moved = moved;
+
+        if changed {
+            fill(&mut display, body, theme.background).unwrap();
+            font::draw_wrapped(
+                &mut display,
+                &sans,
+                MESSAGE,
+                body,
+                alignments[index],
+                &style,
+            )
+            .unwrap();
+            changed = false;
+        }
+        delay.delay_ms(20u16);
+    }
+}
@@ -17,21 +17,23 @@ use core::fmt::Write;
 use core::ops::DerefMut;
 use cortex_m::interrupt::{self as cortex_interrupt, Mutex};
 use eg::{
-    egrectangle, egtext, fonts::Font24x32, pixelcolor::Rgb565,
-    prelude::*, primitive_style, text_style,
+    egtext, fonts::Font24x32, pixelcolor::Rgb565, prelude::*, text_style,
 };
 use embedded_graphics as eg;
 use heapless::consts::*;
 use heapless::String;
-use wio::hal::gpio::{Pb26, Pb27, PfC};
+use wio::hal::gpio::*;
+use wio::hal::hal::spi;
 use wio::hal::pwm::Channel;
-use wio::hal::sercom::{Sercom2Pad0, Sercom2Pad1, UART2};
+use wio::hal::sercom::*;
 use wio::hal::time::Hertz;
 use wio::hal::{clock::GenericClockController, timer::TimerCounter};
 use wio::hal::{delay::Delay, pwm::Tcc0Pwm};
 use wio::pac::{interrupt, CorePeripherals, Peripherals, TC3};
 use wio::prelude::*;
 use wio::{entry, Pins, Sets};
+use wio_examples::framebuffer::StripRenderer;
+use wio_examples::lcd::{Lcd, MemoryAccess};
 
 struct Ctx {
     timer_counter: u32,
@@ -66,18 +68,27 @@ fn beep<P: Into<Hertz>>(
 const SCREEN_WIDTH: i32 = 320; // 画面幅
 const SCREEN_HEIGHT: i32 = 240; // 画面高さ
 
+// LCDが接続されているSPI (MISO: PB18, MOSI: PB19, SCK: PB20)
+type Spi = SPIMaster7<
+    Sercom7Pad2<Pb18<PfD>>,
+    Sercom7Pad3<Pb19<PfD>>,
+    Sercom7Pad1<Pb20<PfD>>,
+>;
+
 // 描画処理を各ステートで呼び出せるようにしておく
+// StripRendererのフレームに描画するので、前の表示を消す必要はない
+// (前のフレームから変わった部分だけがLCDに転送される)
 fn draw<T>(display: &mut T) -> Result<(), T::Error>
 where
     T: embedded_graphics::DrawTarget<Rgb565>,
 {
-    // TODO: カウント表示エリアをクリアする
     const FONT_WIDTH: i32 = 24;
     const FONT_HEIGHT: i32 = 32;
 
     // TODO: 現在のタイムスタンプを取得する
 
     // TODO: タイムスタンプを描画する
+    //       描画の例は`wio_examples::screens::stopwatch()`にある
 
 
     Ok(())
@@ -146,27 +157,38 @@ fn main() -> ! {
 
     unsafe { CTX = Some(Ctx { timer_counter: 0, tc3 }); }
 
-    // LCDの初期化
-    let (mut display, _backlight) = sets
-        .display
-        .init(
-            &mut clocks,
-            peripherals.SERCOM7,
-            &mut peripherals.MCLK,
-            &mut sets.port,
-            60.mhz(),
-            &mut delay,
-        )
-        .unwrap();
+    // LCDが接続されているSERCOM7をSPIとして初期化する
+    let gclk0 = clocks.gclk0();
+    let spi: Spi = SPIMaster7::new(
+        &clocks.sercom7_core(&gclk0).unwrap(),
+        48.mhz(),
+        spi::MODE_0,
+        peripherals.SERCOM7,
+        &mut peripherals.MCLK,
+        (
+            sets.display.miso.into_pad(&mut sets.port),
+            sets.display.mosi.into_pad(&mut sets.port),
+            sets.display.sck.into_pad(&mut sets.port),
+        ),
+    );
+    let dc = sets.display.dc.into_push_pull_output(&mut sets.port);
+    let cs = sets.display.cs.into_push_pull_output(&mut sets.port);
+    let rst = sets.display.reset.into_push_pull_output(&mut sets.port);
+    let mut backlight =
+        sets.display.backlight.into_push_pull_output(&mut sets.port);
+    backlight.set_high().unwrap();
 
-    // LCDのクリア（全体を黒で塗りつぶす）
-    egrectangle!(
-        top_left = (0, 0),
-        bottom_right = (SCREEN_WIDTH - 1, SCREEN_HEIGHT - 1),
-        style = primitive_style!(fill_color = Rgb565::BLACK)
-    )
-    .draw(&mut display)
-    .unwrap();
+    // LCDの初期化
+    let mut lcd = Lcd::new(spi, dc, cs, rst).unwrap();
+    lcd.init(MemoryAccess::landscape(), &mut delay).unwrap();
+
+    // 前のフレームとの差分だけを転送するレンダラ
+    // 最初のフレームで画面全体が背景色 (黒) になる
+    // 描画は`renderer.render(&mut lcd, |frame| draw(frame)).unwrap()`のように行う
+    let mut renderer = StripRenderer::new(Size::new(
+        SCREEN_WIDTH as u32,
+        SCREEN_HEIGHT as u32,
+    ));
 
     // ボタンのGPIOを初期化
     let button_start =
@@ -0,0 +1,393 @@
+//! 8-1 ストップウォッチをつくる の発展版のサンプルコードです。
+//! `8-1-stop_watch` の演習を完成させ、ラップタイムやカウントダウンを加えたものです。
+//!
+//! - ボタン3 (左): スタート (一時停止中なら再開)
+//! - ボタン2 (中央): ストップ (一時停止)
+//! - ボタン1 (右): 停止中にクリア
+//! - 5方向スイッチの押し込み: 計測中はラップを記録、停止中はモードの切り替え
+//!
+//! ストップウォッチのモードでは、5方向スイッチの上下でラップの一覧をスクロールします。
+//! 最も速いラップは緑、最も遅いラップは赤で表示します。
+//! 停止したときの計測結果はフラッシュに保存し、次に電源を入れたときに読み込みます。
+//!
+//! カウントダウンのモードでは、5方向スイッチの左右で分・秒の桁を選び、上下で時間を設定します。
+//! 0になるとブザーでアラームを鳴らし、いずれかのボタンを押すと止まります。
+//!
+//! 時間はTC3のオーバーフローの回数とカウンタの値から、32.768[kHz]の1カウント単位で計り、
+//! 1/100秒まで表示します。
+//! `TIMING` を `Timing::Overflows` にすると、カウンタを読まずにオーバーフローの回数だけで、
+//! 62.5[ms]単位で計ります。
+//!
+//! 状態の遷移は `wio_examples::stopwatch::Stopwatch` にまとめてあり、
+//! このサンプルはボタンとタイマの値を渡して、結果をLCDとブザーに出力します。
+//! 画面は `StripRenderer` で毎回全体を描画し、前のフレームから変わった部分だけをLCDに転送します。
+//! 本体を縦や逆さまに持つと、加速度センサで向きを検出して画面を回転します
+//! (`wio_examples::rotation::AutoRotate`)。
+//!
+//! ### 実行方法
+//! ```sh
+//! $ cargo hf2 --example 8-1-stop_watch_laps --features app --release
+//! ```
+
+#![no_std]
+#![no_main]
+
+use cortex_m::peripheral::NVIC;
+use wio_terminal as wio;
+
+use accelerometer::Accelerometer;
+use core::cell::RefCell;
+use core::fmt::Write;
+use core::ops::DerefMut;
+use core::ptr::addr_of_mut;
+use cortex_m::interrupt::{self as cortex_interrupt, Mutex};
+use wio::hal::gpio::*;
+use wio::hal::hal::spi;
+use wio::hal::pwm::Channel;
+use wio::hal::sercom::*;
+use wio::hal::time::Hertz;
+use wio::hal::{clock::GenericClockController, timer::TimerCounter};
+use wio::hal::{delay::Delay, pwm::Tcc0Pwm};
+use wio::pac::{interrupt, CorePeripherals, Peripherals, TC3};
+use wio::prelude::*;
+use wio::{entry, Pins, Sets};
+use wio_examples::framebuffer::StripRenderer;
+use wio_examples::input::{Key, Keys};
+use wio_examples::laps::{Session, RECORD_SIZE};
+use wio_examples::lcd::Lcd;
+use wio_examples::nvm::{Nvm, STORAGE_ADDRESS};
+use wio_examples::rotation::{AutoRotate, AutoRotateConfig, Tilt};
+use wio_examples::screens;
+use wio_examples::stopwatch::{Mode, Stopwatch, Timing, ALARM_HZ, TC3_CC0};
+
+struct Ctx {
+    timer_counter: u32, // 起動してからのTC3のオーバーフロー回数
+    tc3: TimerCounter<TC3>,
+}
+static mut CTX: Option<Ctx> = None;
+
+// デバッグ用UART
+type Uart = UART2<Sercom2Pad1<Pb27<PfC>>, Sercom2Pad0<Pb26<PfC>>, (), ()>;
+static UART: Mutex<RefCell<Option<Uart>>> = Mutex::new(RefCell::new(None));
+
+// LCDが接続されているSPI (MISO: PB18, MOSI: PB19, SCK: PB20)
+type Spi = SPIMaster7<
+    Sercom7Pad2<Pb18<PfD>>,
+    Sercom7Pad3<Pb19<PfD>>,
+    Sercom7Pad1<Pb20<PfD>>,
+>;
+
+// ブザーを鳴らすヘルパー関数
+fn beep<P: Into<Hertz>>(
+    buzzer_pwm: &mut Tcc0Pwm,
+    delay: &mut Delay,
+    frequency: P,
+    duration_ms: u16,
+) {
+    buzzer_pwm.set_period(frequency.into());
+    buzzer_pwm.enable(Channel::_4);
+    delay.delay_ms(duration_ms);
+    buzzer_pwm.disable(Channel::_4);
+}
+
+// 本体の向きを調べる間隔 [ms] (uptime_ms()は62.5[ms]単位で進む)
+const ROTATE_INTERVAL_MS: u32 = 50;
+
+// 時間の計り方
+const TIMING: Timing = Timing::SubTick;
+
+// TC3の周期をちょうど62.5[ms]にする
+// TimerCounter::start()はCC0を周期のカウント数 (2048) にするが、
+// MFRQモードの周期はCC0 + 1なので、CC0を2047に設定し直す
+fn set_tc3_period() {
+    let tc3 = unsafe { &*TC3::ptr() }.count16();
+    tc3.cc[0].write(|w| unsafe { w.cc().bits(TC3_CC0 as u16) });
+    while tc3.syncbusy.read().cc0().bit_is_set() {}
+}
+
+// TC3のカウンタの現在値を読み取る
+// TimerCounterはレジスタを公開していないので、PACのレジスタを直接読む
+fn read_tc3_count() -> (u32, bool) {
+    let tc3 = unsafe { &*TC3::ptr() }.count16();
+    // READSYNCコマンドでCOUNTレジスタに最新の値を読み込む
+    tc3.ctrlbset.write(|w| w.cmd().readsync());
+    while tc3.ctrlbset.read().cmd().bits() != 0 {}
+    let count = tc3.count.read().bits() as u32;
+    // まだ割り込みで数えていないオーバーフローがあるか
+    let pending = tc3.intflag.read().ovf().bit_is_set();
+    (count, pending)
+}
+
+// 現在のタイムスタンプ [tick] を取得する (1[tick] = 1/32768[s])
+// オーバーフローの回数とカウンタの値は、割り込みを止めてまとめて読む
+fn now_ticks() -> u32 {
+    cortex_interrupt::free(|_| unsafe {
+        let overflows = (*addr_of_mut!(CTX)).as_ref().unwrap().timer_counter;
+        let (count, pending) = match TIMING {
+            Timing::Overflows => (0, false),
+            Timing::SubTick => read_tc3_count(),
+        };
+        TIMING.timestamp(overflows, count, pending)
+    })
+}
+
+// 起動してからの時間 [ms] (ボタンのチャタリング除去に使う)
+fn uptime_ms() -> u32 {
+    let count = cortex_interrupt::free(|_| unsafe {
+        (*addr_of_mut!(CTX)).as_ref().unwrap().timer_counter
+    });
+    count.wrapping_mul(125) / 2
+}
+
+// フラッシュに保存した前回の計測結果を読み込む
+fn load_session(nvm: &Nvm) -> Option<Session> {
+    let mut record = [0u8; RECORD_SIZE];
+    nvm.read(STORAGE_ADDRESS, &mut record).ok()?;
+    Session::decode(&record)
+}
+
+// 計測結果をフラッシュに保存する
+fn save_session(nvm: &mut Nvm, session: &Session) {
+    nvm.erase_block(STORAGE_ADDRESS).unwrap();
+    nvm.write(STORAGE_ADDRESS, &session.encode()).unwrap();
+}
+
+#[entry]
+fn main() -> ! {
+    let mut peripherals = Peripherals::take().unwrap();
+    let core = CorePeripherals::take().unwrap();
+    let mut clocks = GenericClockController::with_external_32kosc(
+        peripherals.GCLK,
+        &mut peripherals.MCLK,
+        &mut peripherals.OSC32KCTRL,
+        &mut peripherals.OSCCTRL,
+        &mut peripherals.NVMCTRL,
+    );
+
+    let mut sets: Sets = Pins::new(peripherals.PORT).split();
+    let mut delay = Delay::new(core.SYST, &mut clocks);
+
+    // パニックハンドラ用にUARTを初期化する（ストップウォッチの機能では不使用）
+    let serial = sets.uart.init(
+        &mut clocks,
+        Hertz(115200u32),
+        peripherals.SERCOM2,
+        &mut peripherals.MCLK,
+        &mut sets.port,
+    );
+    cortex_interrupt::free(|cs| UART.borrow(cs).replace(Some(serial)));
+
+    // ブザーの初期化（TCC0を使ったPWM信号生成）
+    let mut buzzer = sets.buzzer.init(
+        &mut clocks,
+        peripherals.TCC0,
+        &mut peripherals.MCLK,
+        &mut sets.port,
+    );
+    // デューティ比を0.5に音色変えたい場合は比率を変えるとよい
+    let max_duty = buzzer.get_max_duty();
+    buzzer.set_duty(Channel::_4, max_duty / 2);
+    buzzer.disable(Channel::_4);
+
+    // 時間を計測するためにタイマを初期化する
+    // 正確に時間を計測するために、XOSC32K（外部32.768[kHz]水晶発振器）
+    // を基準にしたクロックを使う必要がある
+    // 以下の設定でGCLK6はXOSC32Kを基準とした32.768[kHz]のクロックとなる
+    let gclk6 = clocks
+        .configure_gclk_divider_and_source(
+            wio::pac::gclk::pchctrl::GEN_A::GCLK6,
+            1,
+            wio::pac::gclk::genctrl::SRC_A::XOSC32K,
+            false,
+        )
+        .unwrap();
+    // GCLK6を使うTC2/TC3向けのクロック設定を構築
+    let timer_clock = clocks.tc2_tc3(&gclk6).unwrap();
+    // TC3を初期化
+    let mut tc3 = wio::hal::timer::TimerCounter::tc3_(
+        &timer_clock,
+        peripherals.TC3,
+        &mut peripherals.MCLK,
+    );
+    // 62.5[ms] = 1/16[s]周期のカウンタとしてTC3の動作を開始
+    tc3.start(62500.us());
+    set_tc3_period();
+
+    // 割り込みハンドラがCTXを使うので、CTXを設定してから割り込みを有効にする
+    unsafe {
+        CTX = Some(Ctx { timer_counter: 0, tc3 });
+        (*addr_of_mut!(CTX)).as_mut().unwrap().tc3.enable_interrupt();
+        NVIC::unmask(interrupt::TC3);
+    }
+
+    // 計測結果を保存するフラッシュ
+    let mut nvm = Nvm::new(peripherals.NVMCTRL);
+
+    // LCDが接続されているSERCOM7をSPIとして初期化する
+    let gclk0 = clocks.gclk0();
+    let spi: Spi = SPIMaster7::new(
+        &clocks.sercom7_core(&gclk0).unwrap(),
+        48.mhz(),
+        spi::MODE_0,
+        peripherals.SERCOM7,
+        &mut peripherals.MCLK,
+        (
+            sets.display.miso.into_pad(&mut sets.port),
+            sets.display.mosi.into_pad(&mut sets.port),
+            sets.display.sck.into_pad(&mut sets.port),
+        ),
+    );
+    let dc = sets.display.dc.into_push_pull_output(&mut sets.port);
+    let cs = sets.display.cs.into_push_pull_output(&mut sets.port);
+    let rst = sets.display.reset.into_push_pull_output(&mut sets.port);
+    let mut backlight =
+        sets.display.backlight.into_push_pull_output(&mut sets.port);
+    backlight.set_high().unwrap();
+
+    // 加速度センサで本体の向きを調べて、画面を自動で回転する
+    let mut accel = sets.accelerometer.init(
+        &mut clocks,
+        peripherals.SERCOM4,
+        &mut peripherals.MCLK,
+        &mut sets.port,
+    );
+    let mut rotate = AutoRotate::new(AutoRotateConfig::default(), Tilt::TopUp);
+    let mut rotated_ms = 0;
+
+    // LCDの初期化
+    let mut lcd = Lcd::new(spi, dc, cs, rst).unwrap();
+    lcd.init(rotate.tilt().memory_access(), &mut delay).unwrap();
+
+    // 前のフレームとの差分だけを転送するレンダラ
+    // 最初のフレームで画面全体が背景色 (黒) になる
+    let mut renderer = StripRenderer::new(rotate.tilt().size());
+
+    // ボタンのGPIOを初期化
+    let button_start =
+        sets.buttons.button3.into_floating_input(&mut sets.port);
+    let button_stop =
+        sets.buttons.button2.into_floating_input(&mut sets.port);
+    let button_clear =
+        sets.buttons.button1.into_floating_input(&mut sets.port);
+
+    // 5方向スイッチのGPIOを初期化
+    let switch_up =
+        sets.buttons.switch_x.into_floating_input(&mut sets.port);
+    let switch_down =
+        sets.buttons.switch_b.into_floating_input(&mut sets.port);
+    let switch_press =
+        sets.buttons.switch_u.into_floating_input(&mut sets.port);
+
+    // 5方向スイッチの左右はカウントダウンの時間の設定に使う
+    let switch_left =
+        sets.buttons.switch_y.into_floating_input(&mut sets.port);
+    let switch_right =
+        sets.buttons.switch_z.into_floating_input(&mut sets.port);
+
+    let mut keys = Keys::new();
+    let mut stopwatch = Stopwatch::new();
+    // 前回の計測結果があれば、停止した状態から再開する
+    if let Some(session) = load_session(&nvm) {
+        stopwatch.restore(session);
+    }
+    let mut buzzer_on = false;
+    // 画面を描き直すか
+    let mut redraw = true;
+    let mut shown_ms = None;
+
+    loop {
+        // ボタンはすべて押されるとLowになる
+        let event = keys.poll(
+            |key| match key {
+                Key::Button1 => button_clear.is_low().unwrap(),
+                Key::Button2 => button_stop.is_low().unwrap(),
+                Key::Button3 => button_start.is_low().unwrap(),
+                Key::Up => switch_up.is_low().unwrap(),
+                Key::Down => switch_down.is_low().unwrap(),
+                Key::Left => switch_left.is_low().unwrap(),
+                Key::Right => switch_right.is_low().unwrap(),
+                Key::Press => switch_press.is_low().unwrap(),
+            },
+            uptime_ms(),
+        );
+
+        // 状態の遷移はモデルに任せて、その結果を出力する
+        let now = now_ticks();
+        let output = stopwatch.update(event, now);
+        if let Some(b) = output.beep {
+            beep(&mut buzzer, &mut delay, b.frequency_hz.hz(), b.duration_ms);
+        }
+        if output.save {
+            save_session(&mut nvm, &stopwatch.session(now));
+        }
+        // アラームはパターンに合わせてブザーを鳴らしたり止めたりする
+        let on = stopwatch.buzzer_on(now);
+        if on != buzzer_on {
+            if on {
+                buzzer.set_period(ALARM_HZ.hz());
+                buzzer.enable(Channel::_4);
+            } else {
+                buzzer.disable(Channel::_4);
+            }
+            buzzer_on = on;
+        }
+
+        // 向きが変わったら、LCDの向きと画面の大きさを変えて画面全体を描き直す
+        let ms = uptime_ms();
+        if ms.wrapping_sub(rotated_ms) >= ROTATE_INTERVAL_MS {
+            rotated_ms = ms;
+            if let Some(tilt) = rotate.update(accel.accel_norm().unwrap()) {
+                lcd.set_memory_access(tilt.memory_access()).unwrap();
+                renderer.set_size(tilt.size());
+                redraw = true;
+            }
+        }
+
+        // 表示 (1/100秒) か画面の内容が変わったら、画面全体を1フレームで描画する
+        // 変わっていない部分は転送されないので、消してから描き直す必要はない
+        let time_ms = stopwatch.display_ms(now);
+        let time_changed = shown_ms != Some(time_ms / 10);
+        if !time_changed && !redraw && !output.redraw && !output.laps_changed {
+            continue;
+        }
+        renderer
+            .render(&mut lcd, |frame| {
+                screens::stopwatch_title(frame, stopwatch.title())?;
+                screens::stopwatch(frame, time_ms)?;
+                screens::time_cursor(frame, stopwatch.cursor())?;
+                if stopwatch.mode() == Mode::Stopwatch {
+                    screens::laps(frame, stopwatch.laps(), stopwatch.scroll())?;
+                }
+                Ok(())
+            })
+            .unwrap();
+        shown_ms = Some(time_ms / 10);
+        redraw = false;
+    }
+}
+
+/// TC3の割り込みハンドラ (62.5[ms]周期で呼ばれる)
+#[interrupt]
+fn TC3() {
+    unsafe {
+        let ctx = (*addr_of_mut!(CTX)).as_mut().unwrap();
+        // 割り込みフラグをクリアする (タイマは周期的に動き続ける)
+        ctx.tc3.wait().unwrap();
+        ctx.timer_counter = ctx.timer_counter.wrapping_add(1);
+    }
+}
+
+use core::panic::PanicInfo;
+#[inline(never)]
+#[panic_handler]
+fn panic(info: &PanicInfo) -> ! {
+    cortex_interrupt::free(|cs| {
+        if let Some(ref mut serial) =
+            UART.borrow(cs).borrow_mut().deref_mut()
+        {
+            writeln!(serial, "panic: {}", info).ok();
+        }
+    });
+
+    loop {}
+}
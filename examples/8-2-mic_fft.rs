@@ -1,5 +1,7 @@
 //! 8-2 マイク音声の信号処理をする
 //! マイクから入力した音声をフーリエ変換してパワースペクトラムを表示します
+//! FFTの点数 (64〜1024) と窓関数は `FFT_POINTS` と `WINDOW` で選べます
+//! 画面はRAM上のバッファに帯ごとに描き、DMAでLCDに転送します
 //!
 //! ### 実行方法
 //! ```sh
@@ -13,20 +15,27 @@ use panic_halt as _;
 use wio_terminal as wio;
 
 use core::fmt::Write;
+use core::ptr::addr_of_mut;
 use cortex_m::peripheral::NVIC;
 use heapless::consts::*;
 use heapless::Vec;
-use micromath::F32Ext;
 use wio::entry;
 use wio::hal::adc::{FreeRunning, InterruptAdc};
 use wio::hal::clock::GenericClockController;
 use wio::hal::delay::Delay;
+use wio::hal::gpio::*;
+use wio::hal::hal::spi;
+use wio::hal::sercom::*;
 use wio::hal::time::Hertz;
 use wio::pac::{interrupt, CorePeripherals, Peripherals, ADC1};
 use wio::prelude::*;
 use wio::Pins;
+use wio_examples::dmac::DmaController;
+use wio_examples::lcd::{Lcd, MemoryAccess, HEIGHT, WIDTH};
+use wio_examples::lcd_dma::{to_lcd_order, LcdDma};
+use wio_examples::screens::BAR_WIDTH;
+use wio_examples::spectrum::{Spectrum, Window};
 
-use eg::{egrectangle, pixelcolor::Rgb565, primitive_style};
 use eg::{pixelcolor::Rgb888, prelude::*};
 use embedded_graphics as eg;
 
@@ -43,14 +52,68 @@ struct Ctx {
 static mut CTX: Option<Ctx> = None;
 
 const AVERAGING_FACTOR: u32 = 4; // 平均化フィルタのサンプル点数
-const FFT_POINTS: usize = 256; // FFTをするサンプル点数
+const FFT_POINTS: usize = 256; // FFTをするサンプル点数 (64〜1024の2のべき乗)
+const WINDOW: Window = Window::Hann; // FFTの窓関数
 const ADC_SAMPLING_RATE: f32 = 83333.0; // ADCのサンプリングレート
 #[allow(dead_code)]
 // 平均化フィルタ後のサンプリングレート
 const SAMPLING_RATE: f32 = ADC_SAMPLING_RATE / AVERAGING_FACTOR as f32;
 const AMPLITUDE: f32 = 4096.0; // サンプル値の最大振幅
 
-type SamplingBuffer = heapless::Vec<f32, U256>; //サンプリングバッファの型
+// FFT_POINTS個のサンプルを貯めるサンプリングバッファ
+struct SamplingBuffer {
+    samples: [f32; FFT_POINTS],
+    len: usize,
+}
+
+impl SamplingBuffer {
+    const fn new() -> SamplingBuffer {
+        SamplingBuffer {
+            samples: [0.0; FFT_POINTS],
+            len: 0,
+        }
+    }
+
+    // いっぱいのときはサンプルを追加せずにErrで返す
+    fn push(&mut self, sample: f32) -> Result<(), f32> {
+        if self.is_full() {
+            return Err(sample);
+        }
+        self.samples[self.len] = sample;
+        self.len += 1;
+        Ok(())
+    }
+
+    fn is_full(&self) -> bool {
+        self.len == FFT_POINTS
+    }
+
+    // 貯めたサンプル (いっぱいならFFT_POINTS個)
+    fn samples(&self) -> &[f32] {
+        &self.samples[..self.len]
+    }
+}
+
+// 1回にDMAで転送する帯の高さ
+const BAND_HEIGHT: u16 = 40;
+const BAND_PIXELS: usize = WIDTH as usize * BAND_HEIGHT as usize;
+
+type Spi = SPIMaster7<
+    Sercom7Pad2<Pb18<PfD>>,
+    Sercom7Pad3<Pb19<PfD>>,
+    Sercom7Pad1<Pb20<PfD>>,
+>;
+type Display = LcdDma<
+    Spi,
+    Pc6<Output<PushPull>>,
+    Pb21<Output<PushPull>>,
+    Pc7<Output<PushPull>>,
+>;
+// 空いている描画用のバッファ
+type Bands = heapless::Vec<&'static mut [u16], U2>;
+
+// 描画用と転送用のバッファ2面分
+static mut BUFFERS: [[u16; BAND_PIXELS]; 2] = [[0; BAND_PIXELS]; 2];
 
 // f32::max,f32::minが
 // プラットフォームのライブラリとしてfmaxf,fminfがあることを前提としているが、
@@ -102,17 +165,34 @@ fn main() -> ! {
     );
 
     // 画面を初期化する
-    let (mut display, _backlight) = sets
-        .display
-        .init(
-            &mut clocks,
-            peripherals.SERCOM7,
-            &mut peripherals.MCLK,
-            &mut sets.port,
-            60.mhz(),
-            &mut delay,
-        )
-        .unwrap();
+    // LCDが接続されているSERCOM7をSPIとして初期化し、DMAチャネル0で転送する
+    let gclk0 = clocks.gclk0();
+    let spi: Spi = SPIMaster7::new(
+        &clocks.sercom7_core(&gclk0).unwrap(),
+        48.mhz(),
+        spi::MODE_0,
+        peripherals.SERCOM7,
+        &mut peripherals.MCLK,
+        (
+            sets.display.miso.into_pad(&mut sets.port),
+            sets.display.mosi.into_pad(&mut sets.port),
+            sets.display.sck.into_pad(&mut sets.port),
+        ),
+    );
+    let dc = sets.display.dc.into_push_pull_output(&mut sets.port);
+    let cs = sets.display.cs.into_push_pull_output(&mut sets.port);
+    let rst = sets.display.reset.into_push_pull_output(&mut sets.port);
+    let mut backlight =
+        sets.display.backlight.into_push_pull_output(&mut sets.port);
+    backlight.set_high().unwrap();
+    let mut lcd = Lcd::new(spi, dc, cs, rst).unwrap();
+    lcd.init(MemoryAccess::landscape(), &mut delay).unwrap();
+    let dmac = DmaController::new(peripherals.DMAC, &mut peripherals.MCLK);
+    let mut display = LcdDma::new(lcd, dmac.split().0);
+    let mut bands = Bands::new();
+    for buffer in unsafe { (*addr_of_mut!(BUFFERS)).iter_mut() } {
+        bands.push(buffer).ok();
+    }
 
     // TODO: 共有リソースを初期化する
 
@@ -126,43 +206,61 @@ fn main() -> ! {
     let button_stop =
         sets.buttons.button2.into_floating_input(&mut sets.port);
 
-    // FFTの窓関数としてHann窓を使うので係数を計算しておく
-    // 振幅の正規化用に最大振幅で割っておく
-    let mut hann_factor = [0f32; FFT_POINTS];
-    for i in 0..FFT_POINTS {
-        use core::f32::consts::PI;
-        hann_factor[i] = 0.5f32
-            * (1f32 - (PI * 2.0f32 * i as f32 / FFT_POINTS as f32).cos())
-            / AMPLITUDE;
-    }
-    let hann_factor = hann_factor;
+    // 窓関数の係数を計算しておく
+    // サンプル値はAMPLITUDEで割って-1〜1に正規化してから渡す
+    let mut spectrum = Spectrum::new(FFT_POINTS, WINDOW);
 
     // 画面のスペクトラム表示領域の内容を消す
-    const SCREEN_WIDTH: i32 = 320;
-    const SCREEN_HEIGHT: i32 = 240;
-    fn clear_screen<T: embedded_graphics::DrawTarget<Rgb565>>(
-        display: &mut T,
-    ) -> Result<(), T::Error> {
-        egrectangle!(
-            top_left = (0, 0),
-            bottom_right = (SCREEN_WIDTH - 1, SCREEN_HEIGHT - 1),
-            style = primitive_style!(fill_color = Rgb565::BLACK)
-        )
-        .draw(display)
-    };
-    clear_screen(&mut display).unwrap();
-
-    const BAR_WIDTH: i32 = 2;
+    draw_spectrum(&mut display, &mut bands, &[]);
+
     const REAL_POINTS: usize = FFT_POINTS / 2;
     const NUMBER_OF_BARS: usize = REAL_POINTS;
     const DRAW_AREA_WIDTH: i32 =
         BAR_WIDTH * (NUMBER_OF_BARS as i32 + 1);
-    let mut prev_bar_position = [0u8; NUMBER_OF_BARS as usize];
+    // 各周波数の棒の高さ [px]
+    let mut bar_heights = [0u8; NUMBER_OF_BARS];
     let mut stop_req = false;
     let mut stop_ack = false;
     loop {
         // TODO: `processing_buffer`が埋まっていれば、FFTを実行しスペクトラムを描画する
+        //       `spectrum.magnitudes_db()`で各周波数の振幅をdBで求められる
         //       停止ボタンが押された場合は、棒グラフを表示する
+        //       棒グラフは`draw_spectrum()`でDMAを使って画面全体を描き直せる
+    }
+}
+
+// 各周波数の棒グラフ (高さ [px]) を帯ごとにバッファへ描き、DMAでLCDに転送する
+// 毎回画面全体を描き直すので、前回の表示を消す必要はない
+// 1面を転送している間に、もう1面に次の帯を描く
+fn draw_spectrum(display: &mut Display, bands: &mut Bands, heights: &[u8]) {
+    for band in 0..HEIGHT / BAND_HEIGHT {
+        let y0 = band * BAND_HEIGHT;
+        // 空いているバッファがなければ、転送が終わるのを待って返してもらう
+        let buffer = match bands.pop() {
+            Some(buffer) => buffer,
+            None => display.wait().unwrap(),
+        };
+        render_bars(buffer, y0, heights);
+        if let Some(done) = display.wait() {
+            bands.push(done).ok();
+        }
+        if let Err((e, _)) =
+            display.start_blit(0, y0, WIDTH - 1, y0 + BAND_HEIGHT - 1, buffer)
+        {
+            panic!("{:?}", e);
+        }
+    }
+}
+
+// 上端がtopの帯に、画面の下端から伸びる棒を描く
+fn render_bars(buffer: &mut [u16], top: u16, heights: &[u8]) {
+    let green = to_lcd_order(0x07E0);
+    for (i, pixel) in buffer.iter_mut().enumerate() {
+        let x = i % WIDTH as usize;
+        let y = top + (i / WIDTH as usize) as u16;
+        let height =
+            heights.get(x / BAR_WIDTH as usize).copied().unwrap_or(0);
+        *pixel = if y + height as u16 >= HEIGHT { green } else { 0 };
     }
 }
 
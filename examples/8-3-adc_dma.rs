@@ -0,0 +1,120 @@
+//! 8-3 DMAを使ったADCサンプリングのサンプルコードです。
+//! マイクと光センサの値をDMAでダブルバッファに取り込み、
+//! バッファが埋まるたびに各入力の平均値をシリアルターミナルに出力します。
+//!
+//! ### 実行方法
+//! ```sh
+//! $ cargo hf2 --example 8-3-adc_dma --release
+//! ```
+
+#![no_std]
+#![no_main]
+
+use panic_halt as _;
+use wio_terminal as wio;
+
+use core::fmt::Write;
+use core::ptr::addr_of_mut;
+use cortex_m::peripheral::NVIC;
+use wio::hal::clock::GenericClockController;
+use wio::hal::time::Hertz;
+use wio::pac::{interrupt, Peripherals};
+use wio::prelude::*;
+use wio::{entry, Pins};
+use wio_examples::adc_dma::{samples_of, AdcDma, AdcInput};
+use wio_examples::dmac::DmaController;
+
+const INPUTS: [AdcInput; 2] = [AdcInput::Microphone, AdcInput::LightSensor];
+const SAMPLES_PER_INPUT: usize = 1024;
+const BUFFER_LEN: usize = SAMPLES_PER_INPUT * INPUTS.len();
+
+// DMAの転送先バッファ2面分
+static mut BUFFER_A: [u16; BUFFER_LEN] = [0; BUFFER_LEN];
+static mut BUFFER_B: [u16; BUFFER_LEN] = [0; BUFFER_LEN];
+
+// main() 関数とDMAの割り込みハンドラで共有するリソース
+static mut ADC_DMA: Option<AdcDma> = None;
+
+#[entry]
+fn main() -> ! {
+    let mut peripherals = Peripherals::take().unwrap();
+    let mut clocks = GenericClockController::with_external_32kosc(
+        peripherals.GCLK,
+        &mut peripherals.MCLK,
+        &mut peripherals.OSC32KCTRL,
+        &mut peripherals.OSCCTRL,
+        &mut peripherals.NVMCTRL,
+    );
+
+    let mut sets = Pins::new(peripherals.PORT).split();
+    let mut serial = sets.uart.init(
+        &mut clocks,
+        Hertz(115200u32),
+        peripherals.SERCOM2,
+        &mut peripherals.MCLK,
+        &mut sets.port,
+    );
+
+    // マイクと光センサのピンをアナログ入力にする
+    let _mic = sets.microphone.mic.into_function_b(&mut sets.port);
+    let _light = sets.light_sensor.pd1.into_function_b(&mut sets.port);
+
+    // DMAコントローラを初期化し、チャネル0を変換結果、チャネル1を入力切り替えに使う
+    let dmac = DmaController::new(peripherals.DMAC, &mut peripherals.MCLK);
+    let channels = dmac.split();
+    let mut adc_dma = AdcDma::new(
+        peripherals.ADC1,
+        peripherals.TC4,
+        &mut peripherals.EVSYS,
+        channels.0,
+        channels.1,
+        &INPUTS,
+        16.khz(),
+        unsafe { [&mut *addr_of_mut!(BUFFER_A), &mut *addr_of_mut!(BUFFER_B)] },
+        &mut clocks,
+        &mut peripherals.MCLK,
+    );
+    adc_dma.start();
+    unsafe {
+        ADC_DMA = Some(adc_dma);
+        NVIC::unmask(interrupt::DMAC_0);
+    }
+
+    writeln!(&mut serial, "start").unwrap();
+    loop {
+        // 割り込みハンドラと同時にアクセスしないように、割り込みを禁止して取り出す
+        let averages = cortex_m::interrupt::free(|_| {
+            let adc_dma = unsafe { (*addr_of_mut!(ADC_DMA)).as_mut().unwrap() };
+            let overruns = adc_dma.overruns();
+            adc_dma.take_ready().map(|buffer| {
+                let mut averages = [0u32; INPUTS.len()];
+                for (i, average) in averages.iter_mut().enumerate() {
+                    let sum: u32 = samples_of(buffer, INPUTS.len(), i)
+                        .map(|v| v as u32)
+                        .sum();
+                    *average = sum / SAMPLES_PER_INPUT as u32;
+                }
+                (averages, overruns)
+            })
+        });
+
+        if let Some((averages, overruns)) = averages {
+            writeln!(
+                &mut serial,
+                "mic: {}, light: {}, overruns: {}",
+                averages[0], averages[1], overruns
+            )
+            .unwrap();
+        }
+    }
+}
+
+// 変換結果を転送するDMAチャネル0の割り込みハンドラ (バッファ1面ごとに呼ばれる)
+#[interrupt]
+fn DMAC_0() {
+    unsafe {
+        if let Some(adc_dma) = (*addr_of_mut!(ADC_DMA)).as_mut() {
+            adc_dma.on_interrupt();
+        }
+    }
+}
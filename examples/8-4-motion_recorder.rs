@@ -0,0 +1,251 @@
+//! 8-4 モーションデータレコーダのサンプルコードです。
+//! 加速度センサの値を一定周期でサンプリングし、X/Y/Z軸の波形をLCDに左から右へ掃引して表示します。
+//! ボタン3 (一番左のボタン) で表示を一時停止し、ボタン2 (中央のボタン) で再開します。
+//! ボタン1 (一番右のボタン) を押すたびに、サンプリング周期を切り替えます。
+//!
+//! ### 実行方法
+//! ```sh
+//! $ cargo hf2 --example 8-4-motion_recorder --release
+//! ```
+
+#![no_std]
+#![no_main]
+
+use panic_halt as _;
+use wio_terminal as wio;
+
+use core::fmt::Write;
+use core::ptr::addr_of_mut;
+use cortex_m::peripheral::NVIC;
+use eg::{
+    egrectangle, egtext, fonts::Font8x16, pixelcolor::Rgb565, prelude::*,
+    primitive_style, text_style,
+};
+use embedded_graphics as eg;
+use heapless::consts::*;
+use heapless::{String, Vec};
+use wio::hal::clock::GenericClockController;
+use wio::hal::delay::Delay;
+use wio::hal::gpio::*;
+use wio::hal::sercom::*;
+use wio::hal::timer::TimerCounter;
+use wio::pac::{interrupt, CorePeripherals, Peripherals, TC3};
+use wio::prelude::*;
+use wio::{entry, Pins, Sets};
+use wio_examples::lis3dh::{Config, DataRate, Lis3dh, Mode, Range, ADDRESS};
+use wio_examples::plot::Plot;
+
+// 選べるサンプリング周期 [ms] (加速度センサの出力レートは100[Hz])
+const SAMPLING_PERIODS_MS: [u32; 4] = [10, 20, 50, 100];
+const SCREEN_WIDTH: i32 = 320;
+const SCREEN_HEIGHT: i32 = 240;
+// 画面上部のステータス表示の高さ
+const STATUS_HEIGHT: i32 = 20;
+
+type I2c = I2CMaster4<Sercom4Pad0<Pa13<PfD>>, Sercom4Pad1<Pa12<PfD>>>;
+type SamplingBuffer = Vec<[f32; 3], U8>; // サンプリングバッファの型
+
+// main() 関数とTC3の割り込みハンドラで共有するリソース
+struct Ctx {
+    accel: Lis3dh<I2c>,
+    tc3: TimerCounter<TC3>,
+    buffers: [SamplingBuffer; 2], // サンプリング結果のバッファ2面分
+    // 現在サンプリング結果の格納先のバッファへの参照
+    sampling_buffer: Option<&'static mut SamplingBuffer>,
+    // 現在描画処理中のバッファへの参照
+    processing_buffer: Option<&'static mut SamplingBuffer>,
+}
+
+static mut CTX: Option<Ctx> = None;
+
+#[entry]
+fn main() -> ! {
+    let mut peripherals = Peripherals::take().unwrap();
+    let core = CorePeripherals::take().unwrap();
+    let mut clocks = GenericClockController::with_external_32kosc(
+        peripherals.GCLK,
+        &mut peripherals.MCLK,
+        &mut peripherals.OSC32KCTRL,
+        &mut peripherals.OSCCTRL,
+        &mut peripherals.NVMCTRL,
+    );
+
+    let mut sets: Sets = Pins::new(peripherals.PORT).split();
+    let mut delay = Delay::new(core.SYST, &mut clocks);
+
+    // 加速度センサが接続されているSERCOM4をI2Cとして初期化する
+    let gclk0 = clocks.gclk0();
+    let i2c: I2c = I2CMaster4::new(
+        &clocks.sercom4_core(&gclk0).unwrap(),
+        400.khz(),
+        peripherals.SERCOM4,
+        &mut peripherals.MCLK,
+        sets.accelerometer.sda.into_pad(&mut sets.port),
+        sets.accelerometer.scl.into_pad(&mut sets.port),
+    );
+    let mut accel = Lis3dh::new(i2c, ADDRESS).unwrap();
+    accel
+        .configure(Config {
+            range: Range::G4,
+            data_rate: DataRate::Hz100,
+            mode: Mode::HighResolution,
+            high_pass: None,
+        })
+        .unwrap();
+
+    // サンプリング周期を作るためにTC3を初期化する
+    let timer_clock = clocks.tc2_tc3(&gclk0).unwrap();
+    let mut tc3 = TimerCounter::tc3_(
+        &timer_clock,
+        peripherals.TC3,
+        &mut peripherals.MCLK,
+    );
+    let mut period_index = 1;
+    tc3.start(SAMPLING_PERIODS_MS[period_index].ms());
+    tc3.enable_interrupt();
+
+    // LCDの初期化
+    let (mut display, _backlight) = sets
+        .display
+        .init(
+            &mut clocks,
+            peripherals.SERCOM7,
+            &mut peripherals.MCLK,
+            &mut sets.port,
+            60.mhz(),
+            &mut delay,
+        )
+        .unwrap();
+    egrectangle!(
+        top_left = (0, 0),
+        bottom_right = (SCREEN_WIDTH - 1, SCREEN_HEIGHT - 1),
+        style = primitive_style!(fill_color = Rgb565::BLACK)
+    )
+    .draw(&mut display)
+    .unwrap();
+
+    // 共有リソースを初期化する
+    unsafe {
+        CTX = Some(Ctx {
+            accel,
+            tc3,
+            buffers: [Vec::new(), Vec::new()],
+            sampling_buffer: None,
+            processing_buffer: None,
+        });
+        let ctx = (*addr_of_mut!(CTX)).as_mut().unwrap();
+        let (first, rest) = ctx.buffers.split_first_mut().unwrap();
+        ctx.sampling_buffer = Some(first);
+        ctx.processing_buffer = Some(&mut rest[0]);
+        NVIC::unmask(interrupt::TC3);
+    }
+
+    let button_freeze =
+        sets.buttons.button3.into_floating_input(&mut sets.port);
+    let button_resume =
+        sets.buttons.button2.into_floating_input(&mut sets.port);
+    let button_period =
+        sets.buttons.button1.into_floating_input(&mut sets.port);
+
+    let mut plot = Plot::new(
+        Point::new(0, STATUS_HEIGHT),
+        Size::new(SCREEN_WIDTH as u32, (SCREEN_HEIGHT - STATUS_HEIGHT) as u32),
+    );
+    let mut frozen = false;
+    let mut was_pressed = false;
+    let mut period_ms = SAMPLING_PERIODS_MS[period_index];
+    draw_status(&mut display, frozen, period_ms).unwrap();
+    loop {
+        if !frozen && button_freeze.is_low().unwrap() {
+            frozen = true;
+            draw_status(&mut display, frozen, period_ms).unwrap();
+        }
+        if frozen && button_resume.is_low().unwrap() {
+            frozen = false;
+            draw_status(&mut display, frozen, period_ms).unwrap();
+        }
+        // ボタン1が押された瞬間に、次のサンプリング周期でタイマを設定し直す
+        let pressed = button_period.is_low().unwrap();
+        if pressed && !was_pressed {
+            period_index = (period_index + 1) % SAMPLING_PERIODS_MS.len();
+            period_ms = SAMPLING_PERIODS_MS[period_index];
+            cortex_m::interrupt::free(|_| {
+                let ctx = unsafe { (*addr_of_mut!(CTX)).as_mut().unwrap() };
+                ctx.tc3.start(period_ms.ms());
+                ctx.tc3.enable_interrupt();
+            });
+            // 時間軸が変わるので、それまでの波形は消す
+            plot.clear();
+            draw_status(&mut display, frozen, period_ms).unwrap();
+        }
+        was_pressed = pressed;
+
+        // 割り込みハンドラがバッファを入れ替えるまでは、processing_bufferは空のまま
+        let ctx = unsafe { (*addr_of_mut!(CTX)).as_mut().unwrap() };
+        let processing_buffer = ctx.processing_buffer.as_mut().unwrap();
+        if processing_buffer.is_empty() {
+            continue;
+        }
+        if !frozen {
+            for values in processing_buffer.iter() {
+                plot.push(*values);
+            }
+            plot.draw(&mut display).unwrap();
+        }
+        // 処理が終わったことを割り込みハンドラに知らせるため、バッファを空にする
+        // heapless 0.5のVec::clear()は範囲外を参照するので、空のVecで置き換える
+        **processing_buffer = SamplingBuffer::new();
+    }
+}
+
+// 画面上部に動作状態を表示する
+fn draw_status<T>(
+    display: &mut T,
+    frozen: bool,
+    period_ms: u32,
+) -> Result<(), T::Error>
+where
+    T: embedded_graphics::DrawTarget<Rgb565>,
+{
+    egrectangle!(
+        top_left = (0, 0),
+        bottom_right = (SCREEN_WIDTH - 1, STATUS_HEIGHT - 1),
+        style = primitive_style!(fill_color = Rgb565::BLACK)
+    )
+    .draw(display)?;
+
+    let mut text = String::<U32>::new();
+    let state = if frozen { "FREEZE" } else { "REC" };
+    write!(text, "{} {}ms  X:R Y:G Z:C", state, period_ms).unwrap();
+    egtext!(
+        text = text.as_str(),
+        top_left = (2, 2),
+        style = text_style!(font = Font8x16, text_color = Rgb565::WHITE)
+    )
+    .draw(display)
+}
+
+/// TC3の割り込みハンドラ (サンプリング周期で呼ばれる)
+#[interrupt]
+fn TC3() {
+    let ctx = unsafe { (*addr_of_mut!(CTX)).as_mut().unwrap() };
+    ctx.tc3.wait().ok();
+
+    let accel = match ctx.accel.read_g() {
+        Ok(accel) => accel,
+        Err(_) => return,
+    };
+    let sampling_buffer = ctx.sampling_buffer.as_mut().unwrap();
+    sampling_buffer.push([accel.x, accel.y, accel.z]).ok();
+
+    // バッファが埋まっていて、main()関数での描画が終わっていれば入れ替える
+    // 描画が間に合っていない場合は、新しいサンプルを捨てる
+    if sampling_buffer.len() == sampling_buffer.capacity() {
+        let processing_buffer = ctx.processing_buffer.as_mut().unwrap();
+        if processing_buffer.is_empty() {
+            core::mem::swap(processing_buffer, sampling_buffer);
+        } else {
+            **sampling_buffer = SamplingBuffer::new();
+        }
+    }
+}
@@ -0,0 +1,103 @@
+//! 8-5 傾きの推定と歩数計のサンプルコードです。
+//! 50[Hz]で加速度センサを読み出し、ピッチ・ロール角と歩数を
+//! 1秒ごとにシリアルターミナルに出力します。
+//!
+//! ### 実行方法
+//! ```sh
+//! $ cargo hf2 --example 8-5-pedometer --features app --release
+//! ```
+
+#![no_std]
+#![no_main]
+
+use panic_halt as _;
+use wio_terminal as wio;
+
+use core::fmt::Write;
+use wio::entry;
+use wio::hal::clock::GenericClockController;
+use wio::hal::delay::Delay;
+use wio::hal::gpio::*;
+use wio::hal::sercom::*;
+use wio::pac::{CorePeripherals, Peripherals};
+use wio::prelude::*;
+use wio_examples::lis3dh::{Config, DataRate, Lis3dh, Mode, Range, ADDRESS};
+use wio_examples::motion::{AttitudeEstimator, StepCounter, StepCounterConfig};
+
+const SAMPLE_RATE: u32 = 50; // サンプリング周波数 [Hz]
+
+#[entry]
+fn main() -> ! {
+    let mut peripherals = Peripherals::take().unwrap();
+    let core = CorePeripherals::take().unwrap();
+    let mut clocks = GenericClockController::with_external_32kosc(
+        peripherals.GCLK,
+        &mut peripherals.MCLK,
+        &mut peripherals.OSC32KCTRL,
+        &mut peripherals.OSCCTRL,
+        &mut peripherals.NVMCTRL,
+    );
+
+    let mut sets = wio::Pins::new(peripherals.PORT).split();
+    let mut delay = Delay::new(core.SYST, &mut clocks);
+
+    // UARTドライバオブジェクトを初期化する
+    let mut serial = sets.uart.init(
+        &mut clocks,
+        115200.hz(),
+        peripherals.SERCOM2,
+        &mut peripherals.MCLK,
+        &mut sets.port,
+    );
+
+    // 加速度センサが接続されているSERCOM4をI2Cとして初期化する
+    let gclk0 = clocks.gclk0();
+    let i2c: I2CMaster4<Sercom4Pad0<Pa13<PfD>>, Sercom4Pad1<Pa12<PfD>>> =
+        I2CMaster4::new(
+            &clocks.sercom4_core(&gclk0).unwrap(),
+            400.khz(),
+            peripherals.SERCOM4,
+            &mut peripherals.MCLK,
+            sets.accelerometer.sda.into_pad(&mut sets.port),
+            sets.accelerometer.scl.into_pad(&mut sets.port),
+        );
+    let mut accel = Lis3dh::new(i2c, ADDRESS).unwrap();
+    accel
+        .configure(Config {
+            range: Range::G4,
+            data_rate: DataRate::Hz100,
+            mode: Mode::Normal,
+            high_pass: None,
+        })
+        .unwrap();
+
+    let mut estimator = AttitudeEstimator::new(0.1, 0.3);
+    let mut step_counter = StepCounter::new(StepCounterConfig {
+        sample_rate: SAMPLE_RATE as f32,
+        ..StepCounterConfig::default()
+    });
+
+    let mut count = 0;
+    loop {
+        let value = accel.read_g().unwrap();
+        let attitude = estimator.update(value);
+        step_counter.update(value);
+
+        count += 1;
+        if count == SAMPLE_RATE {
+            count = 0;
+            if let Some(attitude) = attitude {
+                writeln!(
+                    &mut serial,
+                    "pitch: {:.1}, roll: {:.1}, steps: {}",
+                    attitude.pitch_degrees(),
+                    attitude.roll_degrees(),
+                    step_counter.steps()
+                )
+                .unwrap();
+            }
+        }
+        // 読み出しと計算の時間は無視できるほど短いので、待ち時間で周期を作る
+        delay.delay_ms((1000 / SAMPLE_RATE) as u16);
+    }
+}
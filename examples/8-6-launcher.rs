@@ -0,0 +1,500 @@
+//! 8-6 ランチャーのサンプルコードです。
+//! LED、ブザー、光センサ、加速度センサ、ストップウォッチ、FFTのデモを1つのファームウェアにまとめ、
+//! LCDのメニューから選んで実行します。
+//! 5方向スイッチの上下でデモを選び、押し込むと起動します。長押しでメニューに戻ります。
+//!
+//! ### 実行方法
+//! ```sh
+//! $ cargo hf2 --example 8-6-launcher --features app --release
+//! ```
+
+#![no_std]
+#![no_main]
+
+use panic_halt as _;
+use wio_terminal as wio;
+
+use core::ptr::addr_of_mut;
+use cortex_m::peripheral::{DWT, NVIC};
+use eg::fonts::{Font12x16, Font24x32};
+use embedded_graphics as eg;
+use wio::hal::clock::GenericClockController;
+use wio::hal::delay::Delay;
+use wio::hal::gpio::*;
+use wio::hal::pwm::{Channel, Tcc0Pwm};
+use wio::hal::sercom::*;
+use wio::pac::{interrupt, CorePeripherals, Peripherals};
+use wio::prelude::*;
+use wio::{entry, Pins, Sets, LCD};
+use wio_examples::adc_dma::{samples_of, AdcDma, AdcInput};
+use wio_examples::dmac::DmaController;
+use wio_examples::input::{Event, Key, Keys};
+use wio_examples::launcher::{self, App, Context, Launcher};
+use wio_examples::lis3dh::{Lis3dh, ADDRESS};
+use wio_examples::screens;
+use wio_examples::spectrum::{Spectrum, Window};
+use wio_examples::ui::layout::{self, row};
+use wio_examples::ui::{Label, NumericReadout, ProgressBar, Theme, Widget};
+use wio_examples::{motion, Led};
+
+type I2c = I2CMaster4<Sercom4Pad0<Pa13<PfD>>, Sercom4Pad1<Pa12<PfD>>>;
+
+// メインクロック (120[MHz]) でのDWTのサイクル数
+const CYCLES_PER_MS: u32 = 120_000;
+const THEME: Theme = Theme::DARK;
+
+// マイクと光センサを16[kHz]でサンプリングする
+const INPUTS: [AdcInput; 2] = [AdcInput::Microphone, AdcInput::LightSensor];
+const SAMPLES_PER_INPUT: usize = 256;
+const BUFFER_LEN: usize = SAMPLES_PER_INPUT * INPUTS.len();
+static mut BUFFER_A: [u16; BUFFER_LEN] = [0; BUFFER_LEN];
+static mut BUFFER_B: [u16; BUFFER_LEN] = [0; BUFFER_LEN];
+static mut ADC_DMA: Option<AdcDma> = None;
+
+// サンプリングが終わったバッファがあれば、fで処理します
+fn with_samples<F: FnOnce(&[u16])>(f: F) -> bool {
+    cortex_m::interrupt::free(|_| {
+        let adc_dma = unsafe { (*addr_of_mut!(ADC_DMA)).as_mut().unwrap() };
+        adc_dma.take_ready().map(f).is_some()
+    })
+}
+
+// アプリが使うハードウェア
+struct Board {
+    display: LCD,
+    delay: Delay,
+    led: Led,
+    buzzer: Tcc0Pwm,
+    accel: Lis3dh<I2c>,
+    now_ms: u32,
+}
+
+impl Context for Board {
+    type Display = LCD;
+
+    fn display(&mut self) -> &mut LCD {
+        &mut self.display
+    }
+}
+
+// 画面中央に大きな文字で表示するラベル
+fn big_label() -> Label<Font24x32> {
+    Label::new(layout::screen(), Font24x32, THEME)
+        .with_alignment(layout::Alignment::Center)
+}
+
+struct LedApp {
+    on: bool,
+    label: Label<Font24x32>,
+}
+
+impl App<Board> for LedApp {
+    fn name(&self) -> &'static str {
+        "LED"
+    }
+
+    fn init(&mut self, _ctx: &mut Board) {
+        self.label.invalidate();
+    }
+
+    // 押し込むたびにユーザーLEDを点灯・消灯する
+    fn run(&mut self, ctx: &mut Board, event: Option<Event>) {
+        if event == Some(Event::Released(Key::Press)) {
+            self.on = !self.on;
+            if self.on {
+                ctx.led.turn_on();
+            } else {
+                ctx.led.turn_off();
+            }
+        }
+        self.label
+            .set_text(if self.on { "LED ON" } else { "LED OFF" });
+        self.label.draw(&mut ctx.display).unwrap();
+    }
+
+    fn exit(&mut self, ctx: &mut Board) {
+        ctx.led.turn_off();
+        self.on = false;
+    }
+}
+
+struct BuzzerApp {
+    frequency: u32,
+    readout: NumericReadout<Font24x32>,
+}
+
+impl App<Board> for BuzzerApp {
+    fn name(&self) -> &'static str {
+        "Buzzer"
+    }
+
+    fn init(&mut self, _ctx: &mut Board) {
+        self.readout.invalidate();
+    }
+
+    // 上下で周波数を変え、押し込むと鳴らす
+    fn run(&mut self, ctx: &mut Board, event: Option<Event>) {
+        match event {
+            Some(Event::Pressed(Key::Up)) => {
+                self.frequency = (self.frequency + 100).min(4000)
+            }
+            Some(Event::Pressed(Key::Down)) => {
+                self.frequency = (self.frequency - 100).max(200)
+            }
+            Some(Event::Released(Key::Press)) => {
+                ctx.buzzer.set_period(self.frequency.hz());
+                ctx.buzzer.enable(Channel::_4);
+                ctx.delay.delay_ms(200u16);
+                ctx.buzzer.disable(Channel::_4);
+            }
+            _ => {}
+        }
+        self.readout.set_value(self.frequency as i32);
+        self.readout.draw(&mut ctx.display).unwrap();
+    }
+
+    fn exit(&mut self, ctx: &mut Board) {
+        ctx.buzzer.disable(Channel::_4);
+    }
+}
+
+struct LightApp {
+    readout: NumericReadout<Font24x32>,
+    bar: ProgressBar,
+}
+
+impl App<Board> for LightApp {
+    fn name(&self) -> &'static str {
+        "Light sensor"
+    }
+
+    fn init(&mut self, _ctx: &mut Board) {
+        self.readout.invalidate();
+        self.bar.invalidate();
+    }
+
+    // 光センサの値の平均を数値とバーで表示する
+    fn run(&mut self, ctx: &mut Board, _event: Option<Event>) {
+        let mut average = None;
+        with_samples(|buffer| {
+            let sum: u32 =
+                samples_of(buffer, INPUTS.len(), 1).map(|v| v as u32).sum();
+            average = Some(sum / SAMPLES_PER_INPUT as u32);
+        });
+        if let Some(average) = average {
+            self.readout.set_value(average as i32);
+            self.bar.set_value(average);
+        }
+        self.readout.draw(&mut ctx.display).unwrap();
+        self.bar.draw(&mut ctx.display).unwrap();
+    }
+
+    fn exit(&mut self, _ctx: &mut Board) {}
+}
+
+struct AccelApp {
+    axes: [NumericReadout<Font24x32>; 3],
+    pitch: NumericReadout<Font12x16>,
+}
+
+impl App<Board> for AccelApp {
+    fn name(&self) -> &'static str {
+        "Accelerometer"
+    }
+
+    fn init(&mut self, _ctx: &mut Board) {
+        for axis in self.axes.iter_mut() {
+            axis.invalidate();
+        }
+        self.pitch.invalidate();
+    }
+
+    // X/Y/Z軸の加速度 [g] と傾き [度] を表示する
+    fn run(&mut self, ctx: &mut Board, _event: Option<Event>) {
+        if let Ok(accel) = ctx.accel.read_g() {
+            let values = [accel.x, accel.y, accel.z];
+            for (axis, value) in self.axes.iter_mut().zip(values.iter()) {
+                axis.set_value((value * 1000.0) as i32);
+            }
+            let attitude = motion::attitude(accel);
+            self.pitch.set_value(attitude.pitch_degrees() as i32);
+        }
+        for axis in self.axes.iter_mut() {
+            axis.draw(&mut ctx.display).unwrap();
+        }
+        self.pitch.draw(&mut ctx.display).unwrap();
+    }
+
+    fn exit(&mut self, _ctx: &mut Board) {}
+}
+
+struct StopwatchApp {
+    running: bool,
+    elapsed_ms: u32,
+    started_ms: u32,
+    shown: Option<u32>,
+}
+
+impl App<Board> for StopwatchApp {
+    fn name(&self) -> &'static str {
+        "Stopwatch"
+    }
+
+    fn init(&mut self, _ctx: &mut Board) {
+        self.shown = None;
+    }
+
+    // 押し込みで開始・停止、ボタン1でクリアする
+    fn run(&mut self, ctx: &mut Board, event: Option<Event>) {
+        match event {
+            Some(Event::Released(Key::Press)) if self.running => {
+                self.elapsed_ms += ctx.now_ms.wrapping_sub(self.started_ms);
+                self.running = false;
+            }
+            Some(Event::Released(Key::Press)) => {
+                self.started_ms = ctx.now_ms;
+                self.running = true;
+            }
+            Some(Event::Pressed(Key::Button1)) if !self.running => {
+                self.elapsed_ms = 0;
+            }
+            _ => {}
+        }
+        let mut elapsed = self.elapsed_ms;
+        if self.running {
+            elapsed += ctx.now_ms.wrapping_sub(self.started_ms);
+        }
+        // 1/100秒の表示が変わったときだけ描画する
+        if self.shown != Some(elapsed / 10) {
+            screens::stopwatch(&mut ctx.display, elapsed).unwrap();
+            self.shown = Some(elapsed / 10);
+        }
+    }
+
+    fn exit(&mut self, _ctx: &mut Board) {
+        self.running = false;
+    }
+}
+
+struct FftApp {
+    spectrum: Spectrum,
+    previous: [u8; SAMPLES_PER_INPUT / 2],
+}
+
+impl App<Board> for FftApp {
+    fn name(&self) -> &'static str {
+        "FFT"
+    }
+
+    fn init(&mut self, _ctx: &mut Board) {
+        self.previous = [0; SAMPLES_PER_INPUT / 2];
+    }
+
+    // マイクの音声のパワースペクトラムを表示する
+    fn run(&mut self, ctx: &mut Board, _event: Option<Event>) {
+        let mut samples = [0f32; SAMPLES_PER_INPUT];
+        let ready = with_samples(|buffer| {
+            for (sample, value) in samples
+                .iter_mut()
+                .zip(samples_of(buffer, INPUTS.len(), 0))
+            {
+                *sample = (value as f32 - 2048.0) / 2048.0;
+            }
+        });
+        if !ready {
+            return;
+        }
+        let mut db = [0f32; SAMPLES_PER_INPUT / 2];
+        self.spectrum.magnitudes_db(&samples, &mut db);
+        let mut heights = [0u8; SAMPLES_PER_INPUT / 2];
+        for (height, &db) in heights.iter_mut().zip(db.iter()) {
+            // 振幅を対数 (-80〜0[dB]) で表示する
+            // f32::max/minはfmaxf/fminfが必要なので使わない
+            let h = (db + 80.0) * 3.0;
+            *height = if h < 0.0 {
+                0
+            } else if h > 239.0 {
+                239
+            } else {
+                h as u8
+            };
+        }
+        screens::spectrum(&mut ctx.display, &heights, &mut self.previous)
+            .unwrap();
+    }
+
+    fn exit(&mut self, _ctx: &mut Board) {}
+}
+
+#[entry]
+fn main() -> ! {
+    let mut peripherals = Peripherals::take().unwrap();
+    let mut core = CorePeripherals::take().unwrap();
+    let mut clocks = GenericClockController::with_external_32kosc(
+        peripherals.GCLK,
+        &mut peripherals.MCLK,
+        &mut peripherals.OSC32KCTRL,
+        &mut peripherals.OSCCTRL,
+        &mut peripherals.NVMCTRL,
+    );
+    let mut sets: Sets = Pins::new(peripherals.PORT).split();
+    let mut delay = Delay::new(core.SYST, &mut clocks);
+    core.DCB.enable_trace();
+    core.DWT.enable_cycle_counter();
+
+    let (display, _backlight) = sets
+        .display
+        .init(
+            &mut clocks,
+            peripherals.SERCOM7,
+            &mut peripherals.MCLK,
+            &mut sets.port,
+            58.mhz(),
+            &mut delay,
+        )
+        .unwrap();
+
+    let mut buzzer = sets.buzzer.init(
+        &mut clocks,
+        peripherals.TCC0,
+        &mut peripherals.MCLK,
+        &mut sets.port,
+    );
+    let max_duty = buzzer.get_max_duty();
+    buzzer.set_duty(Channel::_4, max_duty / 2);
+    buzzer.disable(Channel::_4);
+
+    let gclk0 = clocks.gclk0();
+    let i2c: I2c = I2CMaster4::new(
+        &clocks.sercom4_core(&gclk0).unwrap(),
+        400.khz(),
+        peripherals.SERCOM4,
+        &mut peripherals.MCLK,
+        sets.accelerometer.sda.into_pad(&mut sets.port),
+        sets.accelerometer.scl.into_pad(&mut sets.port),
+    );
+    let accel = Lis3dh::new(i2c, ADDRESS).unwrap();
+
+    // マイクと光センサのピンをアナログ入力にして、DMAでサンプリングする
+    let _mic = sets.microphone.mic.into_function_b(&mut sets.port);
+    let _light = sets.light_sensor.pd1.into_function_b(&mut sets.port);
+    let dmac = DmaController::new(peripherals.DMAC, &mut peripherals.MCLK);
+    let channels = dmac.split();
+    let mut adc_dma = AdcDma::new(
+        peripherals.ADC1,
+        peripherals.TC4,
+        &mut peripherals.EVSYS,
+        channels.0,
+        channels.1,
+        &INPUTS,
+        16.khz(),
+        unsafe { [&mut *addr_of_mut!(BUFFER_A), &mut *addr_of_mut!(BUFFER_B)] },
+        &mut clocks,
+        &mut peripherals.MCLK,
+    );
+    adc_dma.start();
+    unsafe {
+        ADC_DMA = Some(adc_dma);
+        NVIC::unmask(interrupt::DMAC_0);
+    }
+
+    // ボタンと5方向スイッチ (押されるとLowになる)
+    let button1 = sets.buttons.button1.into_floating_input(&mut sets.port);
+    let up = sets.buttons.switch_x.into_floating_input(&mut sets.port);
+    let down = sets.buttons.switch_b.into_floating_input(&mut sets.port);
+    let press = sets.buttons.switch_u.into_floating_input(&mut sets.port);
+
+    let mut board = Board {
+        display,
+        delay,
+        led: Led::new(sets.user_led, &mut sets.port),
+        buzzer,
+        accel,
+        now_ms: 0,
+    };
+
+    // デモを登録する
+    let (top, bottom) =
+        layout::split_top(layout::inset(layout::screen(), 16), 120);
+    let mut led_app = LedApp {
+        on: false,
+        label: big_label(),
+    };
+    let mut buzzer_app = BuzzerApp {
+        frequency: 1000,
+        readout: NumericReadout::new(top, Font24x32, THEME, 0, "Hz"),
+    };
+    let mut light_app = LightApp {
+        readout: NumericReadout::new(top, Font24x32, THEME, 0, ""),
+        bar: ProgressBar::new(row(bottom, 0, 2, 8), THEME, 4095),
+    };
+    let mut accel_app = AccelApp {
+        axes: [
+            NumericReadout::new(row(top, 0, 3, 4), Font24x32, THEME, 3, "g"),
+            NumericReadout::new(row(top, 1, 3, 4), Font24x32, THEME, 3, "g"),
+            NumericReadout::new(row(top, 2, 3, 4), Font24x32, THEME, 3, "g"),
+        ],
+        pitch: NumericReadout::new(
+            row(bottom, 0, 2, 8),
+            Font12x16,
+            THEME,
+            0,
+            "deg",
+        ),
+    };
+    let mut stopwatch_app = StopwatchApp {
+        running: false,
+        elapsed_ms: 0,
+        started_ms: 0,
+        shown: None,
+    };
+    let mut fft_app = FftApp {
+        spectrum: Spectrum::new(SAMPLES_PER_INPUT, Window::Hann),
+        previous: [0; SAMPLES_PER_INPUT / 2],
+    };
+    let mut apps: [&mut dyn App<Board>; 6] = [
+        &mut led_app,
+        &mut buzzer_app,
+        &mut light_app,
+        &mut accel_app,
+        &mut stopwatch_app,
+        &mut fft_app,
+    ];
+    let names = launcher::names(&apps);
+    let mut launcher = Launcher::new(&names, &mut apps, THEME);
+
+    let mut keys = Keys::new();
+    let mut last_cycles = DWT::cycle_count();
+    let mut cycles = 0;
+    loop {
+        // DWTのサイクルカウンタから経過時間 [ms] を数える
+        let now = DWT::cycle_count();
+        cycles += now.wrapping_sub(last_cycles);
+        last_cycles = now;
+        board.now_ms = board.now_ms.wrapping_add(cycles / CYCLES_PER_MS);
+        cycles %= CYCLES_PER_MS;
+
+        let event = keys.poll(
+            |key| match key {
+                Key::Button1 => button1.is_low().unwrap(),
+                Key::Up => up.is_low().unwrap(),
+                Key::Down => down.is_low().unwrap(),
+                Key::Press => press.is_low().unwrap(),
+                _ => false,
+            },
+            board.now_ms,
+        );
+        launcher.update(&mut board, event).unwrap();
+        board.delay.delay_ms(5u16);
+    }
+}
+
+// 変換結果を転送するDMAチャネル0の割り込みハンドラ (バッファ1面ごとに呼ばれる)
+#[interrupt]
+fn DMAC_0() {
+    unsafe {
+        if let Some(adc_dma) = (*addr_of_mut!(ADC_DMA)).as_mut() {
+            adc_dma.on_interrupt();
+        }
+    }
+}
@@ -0,0 +1,11 @@
+/* Wio Terminal (ATSAMD51P19A) のメモリ配置 */
+/* wio_terminal クレートの memory.x の代わりに使われます */
+MEMORY
+{
+  /* 先頭の16KBはブートローダーが使います */
+  /* 最後のブロック (8KB) は src/nvm.rs のデータの保存に使うので、プログラムを置かないようにします */
+  /* ORIGIN + LENGTH が nvm::STORAGE_ADDRESS (0x7E000) になります */
+  FLASH (rx) : ORIGIN = 0x00000000 + 16K, LENGTH = 512K - 16K - 8K
+  RAM (xrw)  : ORIGIN = 0x20000000, LENGTH = 192K
+}
+_stack_start = ORIGIN(RAM) + LENGTH(RAM);
@@ -0,0 +1,268 @@
+use crate::dmac::{BeatSize, Channel, Descriptor, Trigger};
+use core::ptr::addr_of_mut;
+use wio::hal::clock::GenericClockController;
+use wio::hal::time::{Hertz, Nanoseconds};
+use wio::hal::timer::TimerCounter;
+use wio::pac::gclk::{genctrl::SRC_A, pchctrl::GEN_A};
+use wio::pac::{ADC1, EVSYS, MCLK, TC4};
+use wio::prelude::*;
+use wio_terminal as wio;
+
+// 同時にサンプリングできる入力の最大数です
+pub const MAX_INPUTS: usize = 4;
+
+// ADC1に接続されているWio Terminalのアナログ入力
+// 使用する前に、対応するピンをファンクションBに切り替えておく必要があります
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum AdcInput {
+    Microphone,  // PC30 (AIN12)
+    LightSensor, // PD01 (AIN15)
+    GroveA0,     // PB08 (AIN0) 右側のGroveポート
+    GroveA1,     // PB09 (AIN1) 右側のGroveポート
+}
+
+impl AdcInput {
+    fn muxpos(self) -> u8 {
+        match self {
+            AdcInput::Microphone => 12,
+            AdcInput::LightSensor => 15,
+            AdcInput::GroveA0 => 0,
+            AdcInput::GroveA1 => 1,
+        }
+    }
+
+    // INPUTCTRLレジスタの値 (シングルエンド、負側入力はGND)
+    fn inputctrl(self) -> u32 {
+        const MUXNEG_GND: u32 = 0x18;
+        self.muxpos() as u32 | (MUXNEG_GND << 8)
+    }
+}
+
+// イベントシステムのジェネレータ/ユーザ番号 (データシート 31.7.4, 31.7.7)
+const EVGEN_TC4_OVF: u8 = 0x55;
+const EVUSER_ADC1_START: usize = 0x39;
+const EVSYS_CHANNEL: usize = 0;
+
+// DMAシーケンスでINPUTCTRLに書き込む値の並び
+static mut SEQUENCE: [u32; MAX_INPUTS] = [0; MAX_INPUTS];
+// 2面目のバッファへの転送ディスクリプタ (1面目はチャネルのベースディスクリプタを使う)
+static mut SECOND_DESCRIPTOR: Descriptor = Descriptor::new();
+
+// DMAを使ってADCの結果をダブルバッファに取り込むサービスです
+// TC4のオーバーフローイベントで変換を開始するので、サンプリングレートはTC4の周期で決まります
+// 複数の入力を指定した場合、バッファには入力の順にサンプルが交互に格納されます
+pub struct AdcDma {
+    adc: ADC1,
+    timer: TimerCounter<TC4>,
+    result_channel: Channel,
+    sequence_channel: Channel,
+    buffers: [&'static mut [u16]; 2],
+    num_inputs: usize,
+    sample_rate: Hertz,
+    // 次に転送が完了するバッファ
+    filling: usize,
+    // 転送が完了して、まだ取り出されていないバッファ
+    ready: Option<usize>,
+    // 取り出される前に上書きされたバッファの数
+    overruns: u32,
+}
+
+impl AdcDma {
+    // 1つのバッファには入力数の倍数のサンプル数を割り当てる必要があります
+    #[allow(clippy::too_many_arguments)]
+    pub fn new<S: Into<Hertz>>(
+        adc: ADC1,
+        tc4: TC4,
+        evsys: &mut EVSYS,
+        mut result_channel: Channel,
+        mut sequence_channel: Channel,
+        inputs: &[AdcInput],
+        sample_rate: S,
+        buffers: [&'static mut [u16]; 2],
+        clocks: &mut GenericClockController,
+        mclk: &mut MCLK,
+    ) -> AdcDma {
+        let num_inputs = inputs.len();
+        assert!(num_inputs > 0 && num_inputs <= MAX_INPUTS);
+        assert!(buffers[0].len() == buffers[1].len());
+        assert!(buffers[0].len().is_multiple_of(num_inputs));
+        assert!(buffers[0].len() <= u16::MAX as usize);
+        let sample_rate = sample_rate.into();
+
+        mclk.apbdmask.modify(|_, w| w.adc1_().set_bit());
+        mclk.apbbmask.modify(|_, w| w.evsys_().set_bit());
+
+        // 48[MHz]のGCLK11をADCのクロックとして、4分周した12[MHz]で変換する
+        let gclk11 = clocks
+            .configure_gclk_divider_and_source(
+                GEN_A::GCLK11,
+                1,
+                SRC_A::DFLL,
+                false,
+            )
+            .unwrap();
+        clocks.adc1(&gclk11).unwrap();
+        let gclk0 = clocks.gclk0();
+        let timer_clock = clocks.tc4_tc5(&gclk0).unwrap();
+
+        adc.ctrla.modify(|_, w| w.swrst().set_bit());
+        while adc.syncbusy.read().swrst().bit_is_set() {}
+        adc.ctrla.modify(|_, w| w.prescaler().div4());
+        adc.ctrlb.modify(|_, w| w.ressel()._12bit());
+        adc.refctrl.modify(|_, w| w.refsel().intvcc1());
+        adc.sampctrl.modify(|_, w| unsafe { w.samplen().bits(5) });
+        adc.inputctrl
+            .write(|w| unsafe { w.bits(inputs[0].inputctrl() as u16) });
+        // 変換はイベントで開始する
+        adc.evctrl.modify(|_, w| w.startei().set_bit());
+        if num_inputs > 1 {
+            // 変換が終わるたびに、DMAで次の入力をINPUTCTRLに書き込む
+            adc.dseqctrl.modify(|_, w| w.inputctrl().set_bit());
+        }
+        while adc.syncbusy.read().bits() != 0 {}
+
+        // 変換結果を2面のバッファに交互に転送する
+        let count = buffers[0].len() as u16;
+        let result = &adc.result as *const _ as *const u8;
+        let first = result_channel.descriptor();
+        let second = unsafe { &mut *addr_of_mut!(SECOND_DESCRIPTOR) };
+        first.set(
+            BeatSize::HalfWord,
+            count,
+            result,
+            false,
+            buffers[0].as_mut_ptr() as *mut u8,
+            true,
+        );
+        second.set(
+            BeatSize::HalfWord,
+            count,
+            result,
+            false,
+            buffers[1].as_mut_ptr() as *mut u8,
+            true,
+        );
+        first.link(second);
+        second.link(first);
+        result_channel.configure(Trigger::Adc1ResultReady, 1);
+
+        if num_inputs > 1 {
+            // 最初の入力はINPUTCTRLに設定済みなので、2番目の入力から順に書き込む
+            let sequence = unsafe { &mut *addr_of_mut!(SEQUENCE) };
+            for i in 0..num_inputs {
+                sequence[i] = inputs[(i + 1) % num_inputs].inputctrl();
+            }
+            let descriptor = sequence_channel.descriptor();
+            descriptor.set(
+                BeatSize::Word,
+                num_inputs as u16,
+                sequence.as_ptr() as *const u8,
+                true,
+                &adc.dseqdata as *const _ as *mut u8,
+                false,
+            );
+            descriptor.repeat();
+            sequence_channel.configure(Trigger::Adc1Sequence, 0);
+        }
+
+        // TC4のオーバーフローをADCの変換開始イベントとして接続する
+        evsys.channel[EVSYS_CHANNEL].channel.write(|w| unsafe {
+            w.evgen().bits(EVGEN_TC4_OVF).path().asynchronous()
+        });
+        evsys.user[EVUSER_ADC1_START]
+            .write(|w| unsafe { w.channel().bits(EVSYS_CHANNEL as u8 + 1) });
+
+        let timer = TimerCounter::tc4_(&timer_clock, tc4, mclk);
+
+        AdcDma {
+            adc,
+            timer,
+            result_channel,
+            sequence_channel,
+            buffers,
+            num_inputs,
+            sample_rate,
+            filling: 0,
+            ready: None,
+            overruns: 0,
+        }
+    }
+
+    // サンプリングを開始します
+    pub fn start(&mut self) {
+        self.filling = 0;
+        self.ready = None;
+        if self.num_inputs > 1 {
+            self.sequence_channel.enable();
+        }
+        self.result_channel.enable();
+
+        self.adc.ctrla.modify(|_, w| w.enable().set_bit());
+        while self.adc.syncbusy.read().enable().bit_is_set() {}
+
+        let period = Nanoseconds(1_000_000_000 / self.sample_rate.0);
+        self.timer.start(period);
+        // TimerCounterはイベント出力を設定しないので、レジスタを直接操作する
+        // EVCTRLはTCが停止しているときしか書き込めない
+        let tc = unsafe { &*TC4::ptr() }.count16();
+        tc.ctrla.modify(|_, w| w.enable().clear_bit());
+        while tc.syncbusy.read().enable().bit_is_set() {}
+        tc.evctrl.modify(|_, w| w.ovfeo().set_bit());
+        tc.ctrla.modify(|_, w| w.enable().set_bit());
+        while tc.syncbusy.read().enable().bit_is_set() {}
+    }
+
+    // サンプリングを停止します
+    pub fn stop(&mut self) {
+        let tc = unsafe { &*TC4::ptr() }.count16();
+        tc.ctrla.modify(|_, w| w.enable().clear_bit());
+        while tc.syncbusy.read().enable().bit_is_set() {}
+
+        self.adc.ctrla.modify(|_, w| w.enable().clear_bit());
+        while self.adc.syncbusy.read().enable().bit_is_set() {}
+        self.result_channel.disable();
+        self.sequence_channel.disable();
+    }
+
+    // 結果転送用DMAチャネルの割り込みハンドラから呼び出します
+    // バッファが1面埋まった場合はtrueを返します
+    pub fn on_interrupt(&mut self) -> bool {
+        if !self.result_channel.take_complete() {
+            return false;
+        }
+        if self.ready.is_some() {
+            self.overruns += 1;
+        }
+        self.ready = Some(self.filling);
+        self.filling ^= 1;
+        true
+    }
+
+    // 埋まったバッファを取り出します
+    // 次のバッファが埋まるまでに処理を終える必要があります
+    pub fn take_ready(&mut self) -> Option<&[u16]> {
+        let index = self.ready.take()?;
+        Some(&self.buffers[index][..])
+    }
+
+    pub fn sample_rate(&self) -> Hertz {
+        self.sample_rate
+    }
+
+    pub fn num_inputs(&self) -> usize {
+        self.num_inputs
+    }
+
+    pub fn overruns(&self) -> u32 {
+        self.overruns
+    }
+}
+
+// 交互に格納されたバッファから、指定した入力のサンプルだけを取り出します
+pub fn samples_of(
+    buffer: &[u16],
+    num_inputs: usize,
+    input_index: usize,
+) -> impl Iterator<Item = u16> + '_ {
+    buffer.iter().skip(input_index).step_by(num_inputs).cloned()
+}
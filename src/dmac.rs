@@ -0,0 +1,260 @@
+use core::ptr::{addr_of, addr_of_mut};
+use core::sync::atomic::{compiler_fence, Ordering};
+use wio::pac::{DMAC, MCLK};
+use wio_terminal as wio;
+
+// 専用の割り込みベクタ(DMAC_0〜DMAC_3)を持つチャネルだけを扱います
+pub const NUM_CHANNELS: usize = 4;
+
+// DMAの転送要求を発生させるペリフェラル (データシート 22.6.3.3 TRIGSRC)
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Trigger {
+    Software,
+    Sercom7Tx,
+    Adc1ResultReady,
+    Adc1Sequence,
+}
+
+impl Trigger {
+    fn bits(self) -> u8 {
+        match self {
+            Trigger::Software => 0x00,
+            Trigger::Sercom7Tx => 0x13,
+            Trigger::Adc1ResultReady => 0x46,
+            Trigger::Adc1Sequence => 0x47,
+        }
+    }
+}
+
+// 1回の転送単位 (ビート) のサイズ
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum BeatSize {
+    Byte = 0,
+    HalfWord = 1,
+    Word = 2,
+}
+
+impl BeatSize {
+    fn bytes(self) -> u32 {
+        1 << (self as u32)
+    }
+}
+
+// 転送ディスクリプタ。メモリ上のレイアウトはDMACが直接読み書きするので変えてはいけない
+#[repr(C, align(16))]
+#[derive(Clone, Copy)]
+pub struct Descriptor {
+    btctrl: u16,
+    btcnt: u16,
+    srcaddr: u32,
+    dstaddr: u32,
+    descaddr: u32,
+}
+
+const BTCTRL_VALID: u16 = 1 << 0;
+const BTCTRL_BLOCKACT_INT: u16 = 1 << 3;
+const BTCTRL_SRCINC: u16 = 1 << 10;
+const BTCTRL_DSTINC: u16 = 1 << 11;
+
+impl Descriptor {
+    pub const fn new() -> Descriptor {
+        Descriptor {
+            btctrl: 0,
+            btcnt: 0,
+            srcaddr: 0,
+            dstaddr: 0,
+            descaddr: 0,
+        }
+    }
+
+    // 転送元・転送先を設定します
+    // アドレスをインクリメントする側は、ブロック末尾のアドレスを設定する必要があります
+    pub fn set(
+        &mut self,
+        beat: BeatSize,
+        count: u16,
+        src: *const u8,
+        src_inc: bool,
+        dst: *mut u8,
+        dst_inc: bool,
+    ) {
+        let length = count as u32 * beat.bytes();
+        let mut btctrl =
+            BTCTRL_VALID | BTCTRL_BLOCKACT_INT | ((beat as u16) << 8);
+        let mut srcaddr = src as u32;
+        let mut dstaddr = dst as u32;
+        if src_inc {
+            btctrl |= BTCTRL_SRCINC;
+            srcaddr += length;
+        }
+        if dst_inc {
+            btctrl |= BTCTRL_DSTINC;
+            dstaddr += length;
+        }
+        self.btctrl = btctrl;
+        self.btcnt = count;
+        self.srcaddr = srcaddr;
+        self.dstaddr = dstaddr;
+        self.descaddr = 0;
+    }
+
+    // このブロックの転送が終わった後に続けて転送するディスクリプタをつなげます
+    pub fn link(&mut self, next: &Descriptor) {
+        self.descaddr = next as *const Descriptor as u32;
+    }
+
+    // このブロックの転送が終わった後に、同じブロックを繰り返し転送します
+    pub fn repeat(&mut self) {
+        self.descaddr = self as *const Descriptor as u32;
+    }
+}
+
+impl Default for Descriptor {
+    fn default() -> Descriptor {
+        Descriptor::new()
+    }
+}
+
+// DMACが参照するディスクリプタの先頭と、転送中の状態を書き戻す領域
+static mut BASE_DESCRIPTORS: [Descriptor; NUM_CHANNELS] =
+    [Descriptor::new(); NUM_CHANNELS];
+static mut WRITEBACK_DESCRIPTORS: [Descriptor; NUM_CHANNELS] =
+    [Descriptor::new(); NUM_CHANNELS];
+
+// DMACドライバです。初期化後に各チャネルへ分割して使います
+pub struct DmaController {
+    dmac: DMAC,
+}
+
+pub struct Channels(pub Channel, pub Channel, pub Channel, pub Channel);
+
+impl DmaController {
+    pub fn new(dmac: DMAC, mclk: &mut MCLK) -> DmaController {
+        mclk.ahbmask.modify(|_, w| w.dmac_().set_bit());
+
+        dmac.ctrl.modify(|_, w| w.dmaenable().clear_bit());
+        dmac.ctrl.modify(|_, w| w.swrst().set_bit());
+        while dmac.ctrl.read().swrst().bit_is_set() {}
+
+        unsafe {
+            dmac.baseaddr.write(|w| {
+                w.baseaddr().bits(addr_of!(BASE_DESCRIPTORS) as u32)
+            });
+            dmac.wrbaddr.write(|w| {
+                w.wrbaddr().bits(addr_of!(WRITEBACK_DESCRIPTORS) as u32)
+            });
+        }
+        dmac.ctrl.modify(|_, w| {
+            w.dmaenable()
+                .set_bit()
+                .lvlen0()
+                .set_bit()
+                .lvlen1()
+                .set_bit()
+                .lvlen2()
+                .set_bit()
+                .lvlen3()
+                .set_bit()
+        });
+
+        DmaController { dmac }
+    }
+
+    // チャネルごとのハンドルに分割します
+    // DMACは1つしかないので、ベースディスクリプタはここで1回だけ各チャネルに渡します
+    pub fn split(self) -> Channels {
+        let [d0, d1, d2, d3] = unsafe { &mut *addr_of_mut!(BASE_DESCRIPTORS) };
+        Channels(
+            Channel { id: 0, descriptor: d0 },
+            Channel { id: 1, descriptor: d1 },
+            Channel { id: 2, descriptor: d2 },
+            Channel { id: 3, descriptor: d3 },
+        )
+    }
+}
+
+// DMACの1チャネルです。割り込みハンドラからも操作できるように、レジスタは都度参照します
+pub struct Channel {
+    id: usize,
+    descriptor: &'static mut Descriptor,
+}
+
+impl Channel {
+    fn dmac(&self) -> &wio::pac::dmac::RegisterBlock {
+        unsafe { &*DMAC::ptr() }
+    }
+
+    pub fn id(&self) -> usize {
+        self.id
+    }
+
+    // 最初に転送するディスクリプタへの参照です
+    pub fn descriptor(&mut self) -> &mut Descriptor {
+        self.descriptor
+    }
+
+    // トリガと転送完了割り込みを設定します
+    pub fn configure(&mut self, trigger: Trigger, priority: u8) {
+        let ch = &self.dmac().channel[self.id];
+        ch.chctrla.modify(|_, w| w.enable().clear_bit());
+        while ch.chctrla.read().enable().bit_is_set() {}
+        ch.chctrla.modify(|_, w| w.swrst().set_bit());
+        while ch.chctrla.read().swrst().bit_is_set() {}
+
+        ch.chctrla.modify(|_, w| unsafe {
+            // トリガ1回につき1ビート転送する
+            w.trigsrc().bits(trigger.bits()).trigact().burst()
+        });
+        ch.chprilvl.write(|w| w.prilvl().bits(priority & 0x3));
+        ch.chintenset
+            .write(|w| w.tcmpl().set_bit().terr().set_bit());
+    }
+
+    // 転送を開始します。ディスクリプタは事前に設定しておく必要があります
+    pub fn enable(&mut self) {
+        compiler_fence(Ordering::Release);
+        let ch = &self.dmac().channel[self.id];
+        ch.chctrla.modify(|_, w| w.enable().set_bit());
+    }
+
+    pub fn disable(&mut self) {
+        let ch = &self.dmac().channel[self.id];
+        ch.chctrla.modify(|_, w| w.enable().clear_bit());
+        while ch.chctrla.read().enable().bit_is_set() {}
+        compiler_fence(Ordering::Acquire);
+    }
+
+    // ソフトウェアトリガで転送を開始します
+    pub fn trigger(&mut self) {
+        let bits = 1 << self.id;
+        self.dmac()
+            .swtrigctrl
+            .modify(|r, w| unsafe { w.bits(r.bits() | bits) });
+    }
+
+    // 最後のディスクリプタの転送が終わると、ハードウェアが無効にします
+    pub fn is_enabled(&self) -> bool {
+        let ch = &self.dmac().channel[self.id];
+        ch.chctrla.read().enable().bit_is_set()
+    }
+
+    pub fn is_busy(&self) -> bool {
+        self.dmac().busych.read().bits() & (1 << self.id) != 0
+    }
+
+    // 転送完了フラグを確認してクリアします。割り込みハンドラから呼び出します
+    pub fn take_complete(&mut self) -> bool {
+        let ch = &self.dmac().channel[self.id];
+        let flags = ch.chintflag.read();
+        if flags.terr().bit_is_set() {
+            ch.chintflag.write(|w| w.terr().set_bit());
+        }
+        if flags.tcmpl().bit_is_set() {
+            ch.chintflag.write(|w| w.tcmpl().set_bit());
+            compiler_fence(Ordering::Acquire);
+            true
+        } else {
+            false
+        }
+    }
+}
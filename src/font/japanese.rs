@@ -0,0 +1,12 @@
+// ビルド時に作る日本語フォントのサブセット
+// サンプルとライブラリの文字列リテラルで使われている文字だけを含みます
+// 作り方は build.rs を参照してください
+use super::Font;
+
+pub const DATA: &[u8] =
+    include_bytes!(concat!(env!("OUT_DIR"), "/japanese.bin"));
+
+pub fn font() -> Font<'static> {
+    // ビルド時に変換したデータなので、読み込みに失敗することはない
+    Font::parse(DATA).unwrap()
+}
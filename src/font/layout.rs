@@ -0,0 +1,149 @@
+// プロポーショナルフォントの文字列の配置
+// 幅の計算、矩形の幅に収まるような折り返し、左・中央・右揃えをします
+use super::render::{draw_text, TextStyle};
+use super::Font;
+use crate::ui::layout::{self, Alignment};
+use eg::pixelcolor::Rgb565;
+use eg::prelude::*;
+use eg::primitives::Rectangle;
+use embedded_graphics as eg;
+
+// 1行の文字列を描画したときの幅 [px] (カーニングを含む)
+pub fn measure(font: &Font, text: &str) -> i32 {
+    let mut width = 0;
+    let mut previous: Option<u16> = None;
+    for c in text.chars() {
+        if let Some(glyph) = font.glyph_or_fallback(c) {
+            if let Some(previous) = previous {
+                width += font.kerning(previous, glyph.index);
+            }
+            width += glyph.advance as i32;
+            previous = Some(glyph.index);
+        }
+    }
+    width
+}
+
+// 全角の文字 (かな・漢字など) は単語の区切りがないので、どこでも改行できる
+fn is_wide(c: char) -> bool {
+    c >= '\u{2e80}'
+}
+
+// 行頭に置かない文字 (句読点、閉じ括弧、小書きのかななど)
+const NO_BREAK_BEFORE: &str =
+    "、。，．・：；？！ー…」』）】〕ぁぃぅぇぉっゃゅょァィゥェォッャュョ";
+// 行末に置かない文字 (開き括弧)
+const NO_BREAK_AFTER: &str = "「『（【〔";
+
+// previousとcの間で改行できるか (禁則処理)
+fn can_break(previous: char, c: char) -> bool {
+    (is_wide(previous) || is_wide(c))
+        && !NO_BREAK_BEFORE.contains(c)
+        && !NO_BREAK_AFTER.contains(previous)
+}
+
+// 文字列を幅max_width [px] に収まる行に分けるイテレータ
+// 空白か全角の文字の前後で改行し、句読点や括弧には禁則処理をします
+// 1単語が幅に収まらなければ文字の途中で改行します
+// 改行文字 '\n' では必ず改行します。行末と行頭の空白は取り除きます
+pub struct Lines<'a, 'f> {
+    font: &'f Font<'f>,
+    text: &'a str,
+    max_width: i32,
+    done: bool,
+}
+
+pub fn lines<'a, 'f>(
+    font: &'f Font<'f>,
+    text: &'a str,
+    max_width: i32,
+) -> Lines<'a, 'f> {
+    Lines {
+        font,
+        text,
+        max_width,
+        done: text.is_empty(),
+    }
+}
+
+impl<'a, 'f> Iterator for Lines<'a, 'f> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<&'a str> {
+        if self.done {
+            return None;
+        }
+        let text = self.text.trim_start_matches(' ');
+        let mut width = 0;
+        let mut previous: Option<(char, u16)> = None;
+        // 改行できる位置 (行の終わり, 次の行の始まり)
+        let mut last_break: Option<(usize, usize)> = None;
+        for (i, c) in text.char_indices() {
+            if c == '\n' {
+                return Some(self.split(text, i, i + 1));
+            }
+            if c == ' ' {
+                last_break = Some((i, i + 1));
+            } else if previous.is_some_and(|p| can_break(p.0, c)) {
+                last_break = Some((i, i));
+            }
+            let glyph = match self.font.glyph_or_fallback(c) {
+                Some(glyph) => glyph,
+                None => continue,
+            };
+            let mut next_width = width + glyph.advance as i32;
+            if let Some((_, index)) = previous {
+                next_width += self.font.kerning(index, glyph.index);
+            }
+            if next_width > self.max_width && c != ' ' && i > 0 {
+                let (end, next) = last_break.unwrap_or((i, i));
+                return Some(self.split(text, end, next));
+            }
+            width = next_width;
+            previous = Some((c, glyph.index));
+        }
+        self.done = true;
+        Some(text.trim_end_matches(' '))
+    }
+}
+
+impl<'a, 'f> Lines<'a, 'f> {
+    fn split(&mut self, text: &'a str, end: usize, next: usize) -> &'a str {
+        self.text = &text[next..];
+        text[..end].trim_end_matches(' ')
+    }
+}
+
+// areaの幅で折り返しながら、上から順に描画します
+// areaの下端からはみ出す行は描画しません。描画した行数を返します
+pub fn draw_wrapped<T>(
+    display: &mut T,
+    font: &Font,
+    text: &str,
+    area: Rectangle,
+    align: Alignment,
+    style: &TextStyle,
+) -> Result<usize, T::Error>
+where
+    T: DrawTarget<Rgb565>,
+{
+    let max_width = layout::width(area);
+    let mut baseline = area.top_left.y + font.ascent();
+    let mut count = 0;
+    for line in lines(font, text, max_width) {
+        if baseline + font.descent() > area.bottom_right.y + 1 {
+            break;
+        }
+        let width = measure(font, line);
+        let x = match align {
+            Alignment::Left => 0,
+            Alignment::Center => (max_width - width) / 2,
+            Alignment::Right => max_width - width,
+        };
+        let origin = Point::new(area.top_left.x + x, baseline);
+        draw_text(display, font, line, origin, style)?;
+        baseline += font.line_height();
+        count += 1;
+    }
+    Ok(count)
+}
@@ -0,0 +1,200 @@
+// プロポーショナルフォント
+// 文字ごとに幅が異なり、カーニングと2/4ビットのアンチエイリアスに対応します
+// フォントはホストのツール (tools/fontconv) でTTF/BDFから変換して作ります
+//
+// フォントのレイアウト (数値はリトルエンディアン)
+//   0..4    マジック "WFNT"
+//   4       1ピクセルのビット数 (1, 2, 4)
+//   5       予約 (0)
+//   6..8    アセント (ベースラインから上の高さ) [px]
+//   8..10   ディセント (ベースラインから下の深さ) [px]
+//   10..12  行の高さ [px]
+//   12..14  グリフの数
+//   14..16  カーニングペアの数
+//   16..    グリフ表 (文字コードの順、1つ14バイト)
+//             文字コード u32, ビットマップの位置 u32,
+//             幅 u8, 高さ u8, 左端のずれ i8, ベースラインから上端までの高さ i8,
+//             送り幅 u8, 予約 u8
+//   ..      カーニング表 (左右のグリフ番号の順、1つ6バイト)
+//             左のグリフ番号 u16, 右のグリフ番号 u16, 調整量 i8, 予約 u8
+//   ..      ビットマップ (グリフごとにバイト境界から始まり、上位ビットから詰める)
+#[cfg(feature = "japanese")]
+pub mod japanese;
+pub mod layout;
+pub mod render;
+
+pub use self::layout::{draw_wrapped, measure};
+pub use self::render::{draw_text, TextStyle};
+
+pub const MAGIC: [u8; 4] = *b"WFNT";
+pub const HEADER_SIZE: usize = 16;
+pub const GLYPH_SIZE: usize = 14;
+pub const KERNING_SIZE: usize = 6;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Error {
+    InvalidHeader,
+    UnsupportedBitsPerPixel(u8),
+    // 表やビットマップがデータの範囲をはみ出している
+    Truncated,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Font<'a> {
+    bpp: u8,
+    ascent: u16,
+    descent: u16,
+    line_height: u16,
+    glyphs: &'a [u8],
+    kerning: &'a [u8],
+    bitmaps: &'a [u8],
+}
+
+// 1文字分のグリフ
+#[derive(Clone, Copy, Debug)]
+pub struct Glyph<'a> {
+    // グリフ表での番号 (カーニングに使う)
+    pub index: u16,
+    pub width: u8,
+    pub height: u8,
+    pub x_offset: i8,
+    pub y_offset: i8,
+    pub advance: u8,
+    bpp: u8,
+    bitmap: &'a [u8],
+}
+
+impl<'a> Font<'a> {
+    pub fn parse(bytes: &'a [u8]) -> Result<Font<'a>, Error> {
+        if bytes.len() < HEADER_SIZE || bytes[..4] != MAGIC {
+            return Err(Error::InvalidHeader);
+        }
+        let bpp = bytes[4];
+        if bpp != 1 && bpp != 2 && bpp != 4 {
+            return Err(Error::UnsupportedBitsPerPixel(bpp));
+        }
+        let u16_at = |i: usize| u16::from_le_bytes([bytes[i], bytes[i + 1]]);
+        let glyph_count = u16_at(12) as usize;
+        let kerning_count = u16_at(14) as usize;
+        let kerning_start = HEADER_SIZE + glyph_count * GLYPH_SIZE;
+        let bitmap_start = kerning_start + kerning_count * KERNING_SIZE;
+        if bytes.len() < bitmap_start {
+            return Err(Error::Truncated);
+        }
+        Ok(Font {
+            bpp,
+            ascent: u16_at(6),
+            descent: u16_at(8),
+            line_height: u16_at(10),
+            glyphs: &bytes[HEADER_SIZE..kerning_start],
+            kerning: &bytes[kerning_start..bitmap_start],
+            bitmaps: &bytes[bitmap_start..],
+        })
+    }
+
+    pub fn bits_per_pixel(&self) -> u8 {
+        self.bpp
+    }
+
+    pub fn ascent(&self) -> i32 {
+        self.ascent as i32
+    }
+
+    pub fn descent(&self) -> i32 {
+        self.descent as i32
+    }
+
+    pub fn line_height(&self) -> i32 {
+        self.line_height as i32
+    }
+
+    pub fn glyph_count(&self) -> usize {
+        self.glyphs.len() / GLYPH_SIZE
+    }
+
+    fn glyph_at(&self, index: usize) -> Option<Glyph<'a>> {
+        let entry = &self.glyphs[index * GLYPH_SIZE..(index + 1) * GLYPH_SIZE];
+        let offset =
+            u32::from_le_bytes([entry[4], entry[5], entry[6], entry[7]]);
+        let (width, height) = (entry[8], entry[9]);
+        let bits = width as usize * height as usize * self.bpp as usize;
+        let start = offset as usize;
+        let bitmap = self.bitmaps.get(start..start + bits.div_ceil(8))?;
+        Some(Glyph {
+            index: index as u16,
+            width,
+            height,
+            x_offset: entry[10] as i8,
+            y_offset: entry[11] as i8,
+            advance: entry[12],
+            bpp: self.bpp,
+            bitmap,
+        })
+    }
+
+    fn codepoint_at(&self, index: usize) -> u32 {
+        let i = index * GLYPH_SIZE;
+        let g = &self.glyphs;
+        u32::from_le_bytes([g[i], g[i + 1], g[i + 2], g[i + 3]])
+    }
+
+    // 文字のグリフを二分探索で探します
+    pub fn glyph(&self, c: char) -> Option<Glyph<'a>> {
+        let code = c as u32;
+        let (mut low, mut high) = (0, self.glyph_count());
+        while low < high {
+            let middle = (low + high) / 2;
+            let value = self.codepoint_at(middle);
+            if value == code {
+                return self.glyph_at(middle);
+            } else if value < code {
+                low = middle + 1;
+            } else {
+                high = middle;
+            }
+        }
+        None
+    }
+
+    // フォントにない文字は '?' で代用します
+    pub fn glyph_or_fallback(&self, c: char) -> Option<Glyph<'a>> {
+        self.glyph(c).or_else(|| self.glyph('?'))
+    }
+
+    // leftの次にrightを並べるときの送り幅の調整量 [px]
+    pub fn kerning(&self, left: u16, right: u16) -> i32 {
+        let key = (left as u32) << 16 | right as u32;
+        let count = self.kerning.len() / KERNING_SIZE;
+        let (mut low, mut high) = (0, count);
+        while low < high {
+            let middle = (low + high) / 2;
+            let e = &self.kerning[middle * KERNING_SIZE..];
+            let value = (u16::from_le_bytes([e[0], e[1]]) as u32) << 16
+                | u16::from_le_bytes([e[2], e[3]]) as u32;
+            if value == key {
+                return e[4] as i8 as i32;
+            } else if value < key {
+                low = middle + 1;
+            } else {
+                high = middle;
+            }
+        }
+        0
+    }
+}
+
+impl<'a> Glyph<'a> {
+    // 最大の濃さ (1ビットなら1、4ビットなら15)
+    pub fn max_alpha(&self) -> u8 {
+        (1u16 << self.bpp) as u8 - 1
+    }
+
+    // (x, y) のピクセルの濃さ。0は背景、max_alpha()で文字色
+    pub fn alpha(&self, x: u8, y: u8) -> u8 {
+        let bit =
+            (y as usize * self.width as usize + x as usize) * self.bpp as usize;
+        let byte = self.bitmap[bit / 8];
+        let shift = 8 - self.bpp as usize - bit % 8;
+        (byte >> shift) & self.max_alpha()
+    }
+}
@@ -0,0 +1,83 @@
+// フォントの描画
+// LCDからは画面の色を読み出せないので、アンチエイリアスは背景色との合成で行います
+use super::{Font, Glyph};
+use crate::splash::blend;
+use eg::drawable::Pixel;
+use eg::pixelcolor::Rgb565;
+use eg::prelude::*;
+use embedded_graphics as eg;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct TextStyle {
+    pub color: Rgb565,
+    // アンチエイリアスで合成する背景の色
+    pub background: Rgb565,
+    // trueなら文字の周りも背景色で塗りつぶす (前の文字を消しながら書き換えられる)
+    pub opaque: bool,
+}
+
+impl TextStyle {
+    pub fn new(color: Rgb565, background: Rgb565) -> TextStyle {
+        TextStyle {
+            color,
+            background,
+            opaque: false,
+        }
+    }
+}
+
+// グリフを1文字描画します。originはベースラインの左端です
+pub fn draw_glyph<T>(
+    display: &mut T,
+    glyph: &Glyph,
+    origin: Point,
+    style: &TextStyle,
+) -> Result<(), T::Error>
+where
+    T: DrawTarget<Rgb565>,
+{
+    let max = glyph.max_alpha();
+    let top_left =
+        origin + Point::new(glyph.x_offset as i32, -(glyph.y_offset as i32));
+    for y in 0..glyph.height {
+        for x in 0..glyph.width {
+            let alpha = glyph.alpha(x, y);
+            if alpha == 0 && !style.opaque {
+                continue;
+            }
+            let color = blend(style.background, style.color, alpha, max);
+            let point = top_left + Point::new(x as i32, y as i32);
+            display.draw_pixel(Pixel(point, color))?;
+        }
+    }
+    Ok(())
+}
+
+// 1行の文字列を描画して、描画後の送り位置 (次の文字の原点) を返します
+// originはベースラインの左端です。改行文字は無視します
+pub fn draw_text<T>(
+    display: &mut T,
+    font: &Font,
+    text: &str,
+    origin: Point,
+    style: &TextStyle,
+) -> Result<Point, T::Error>
+where
+    T: DrawTarget<Rgb565>,
+{
+    let mut pen = origin;
+    let mut previous: Option<u16> = None;
+    for c in text.chars().filter(|&c| c != '\n') {
+        let glyph = match font.glyph_or_fallback(c) {
+            Some(glyph) => glyph,
+            None => continue,
+        };
+        if let Some(previous) = previous {
+            pen.x += font.kerning(previous, glyph.index);
+        }
+        draw_glyph(display, &glyph, pen, style)?;
+        pen.x += glyph.advance as i32;
+        previous = Some(glyph.index);
+    }
+    Ok(pen)
+}
@@ -0,0 +1,397 @@
+use crate::lcd::{self, Lcd};
+use eg::drawable::Pixel;
+use eg::geometry::{Point, Size};
+use eg::pixelcolor::raw::{RawData, RawU16};
+use eg::pixelcolor::{Rgb565, RgbColor};
+use eg::DrawTarget;
+use embedded_graphics as eg;
+use embedded_hal::blocking::spi::{Transfer, Write};
+use embedded_hal::digital::v2::OutputPin;
+
+// 扱える画面の最大サイズ (縦長の画面は幅と高さを入れ替えた240x320まで)
+pub const MAX_WIDTH: usize = 320;
+pub const MAX_HEIGHT: usize = 240;
+// 描画したピクセルを保持する単位 (タイル) の大きさ [px]
+pub const TILE_WIDTH: usize = 32;
+pub const TILE_HEIGHT: usize = 16;
+// 同時に保持できるタイルの数。1枚あたり約1[KB]使います
+pub const TILES: usize = 24;
+
+// 横長と縦長のどちらの画面も収まるように、長い辺の正方形の範囲にタイルを並べる
+const TILE_COLUMNS: usize = MAX_WIDTH / TILE_WIDTH;
+const TILE_ROWS: usize = MAX_WIDTH / TILE_HEIGHT;
+const TILE_PIXELS: usize = TILE_WIDTH * TILE_HEIGHT;
+// タイルを割り当てていない位置
+const NO_TILE: u8 = u8::MAX;
+// このフレームで一度転送して、タイルを空けた位置
+const FLUSHED: u8 = u8::MAX - 1;
+
+// 描画したピクセルの転送先
+pub trait PixelSink {
+    type Error;
+
+    // 矩形領域 (両端を含む) にRGB565のピクセルを書き込みます
+    fn write_pixels(
+        &mut self,
+        x0: u16,
+        y0: u16,
+        x1: u16,
+        y1: u16,
+        pixels: &[u16],
+    ) -> Result<(), Self::Error>;
+}
+
+impl<SPI, DC, CS, RST, SpiE, PinE> PixelSink for Lcd<SPI, DC, CS, RST>
+where
+    SPI: Transfer<u8, Error = SpiE> + Write<u8, Error = SpiE>,
+    DC: OutputPin<Error = PinE>,
+    CS: OutputPin<Error = PinE>,
+    RST: OutputPin<Error = PinE>,
+{
+    type Error = lcd::Error<SpiE, PinE>;
+
+    fn write_pixels(
+        &mut self,
+        x0: u16,
+        y0: u16,
+        x1: u16,
+        y1: u16,
+        pixels: &[u16],
+    ) -> Result<(), Self::Error> {
+        Lcd::write_pixels(self, x0, y0, x1, y1, pixels)
+    }
+}
+
+#[derive(Clone, Copy)]
+struct Tile {
+    pixels: [u16; TILE_PIXELS],
+    // 行ごとに、描画したピクセルをビットで記録する (ビットiが左からi番目)
+    written: [u32; TILE_HEIGHT],
+    // 背景色で埋めてから描画したタイルか
+    // falseなら、このフレームで一度転送した位置に後から描画したピクセルだけを持つ
+    complete: bool,
+}
+
+// 前のフレームとの差分だけをLCDに転送するレンダラです
+// 全画面のフレームバッファを持たずに、描画したピクセルは小さなタイルに溜めておきます
+// 描画処理は毎フレーム画面全体を描き、描画しなかったピクセルは背景色になります
+// フレームの最後に、各タイルの内容のハッシュを前のフレームと比べて、変わったタイルだけを転送します
+// 前の表示を消さずに描き直しても、動いた物の跡は背景色で転送されるので、表示がちらつきません
+// タイルが足りなくなった場合は、フレームの途中でも溜めたピクセルを転送します
+pub struct StripRenderer {
+    size: Size,
+    background: u16,
+    // 画面上の位置ごとに割り当てたタイルの番号
+    map: [[u8; TILE_COLUMNS]; TILE_ROWS],
+    // 画面上の位置ごとに、表示中のタイルの内容のハッシュ
+    // Noneなら内容が分からないので、次のフレームで必ず転送する
+    shown: [[Option<u32>; TILE_COLUMNS]; TILE_ROWS],
+    tiles: [Tile; TILES],
+    used: usize,
+    // 転送する矩形を連続した領域にまとめるバッファ
+    scratch: [u16; TILE_PIXELS],
+}
+
+impl StripRenderer {
+    // sizeは最大320x240 (縦長なら240x320) です。背景色は黒です
+    pub fn new(size: Size) -> StripRenderer {
+        StripRenderer {
+            size: fit(size),
+            background: RawU16::from(Rgb565::BLACK).into_inner(),
+            map: [[NO_TILE; TILE_COLUMNS]; TILE_ROWS],
+            shown: [[None; TILE_COLUMNS]; TILE_ROWS],
+            tiles: [Tile {
+                pixels: [0; TILE_PIXELS],
+                written: [0; TILE_HEIGHT],
+                complete: false,
+            }; TILES],
+            used: 0,
+            scratch: [0; TILE_PIXELS],
+        }
+    }
+
+    pub fn size(&self) -> Size {
+        self.size
+    }
+
+    // 画面の大きさを変えます (LCDを回転したときなど)
+    // 次のフレームで画面全体を転送します
+    pub fn set_size(&mut self, size: Size) {
+        self.size = fit(size);
+        self.discard();
+        self.invalidate();
+    }
+
+    // 次のフレームで画面全体を転送します (ほかの方法でLCDに描画したときなど)
+    pub fn invalidate(&mut self) {
+        self.shown = [[None; TILE_COLUMNS]; TILE_ROWS];
+    }
+
+    // sceneで1フレーム分の画面全体を描画し、前のフレームから変わった部分をsinkに転送します
+    // sceneは1回だけ呼び出されます。転送した矩形の数を返します
+    pub fn render<S, F>(
+        &mut self,
+        sink: &mut S,
+        scene: F,
+    ) -> Result<usize, S::Error>
+    where
+        S: PixelSink,
+        F: FnOnce(&mut Frame<'_, S>) -> Result<(), S::Error>,
+    {
+        let mut frame = Frame {
+            renderer: self,
+            sink,
+            flushed: 0,
+        };
+        let result = scene(&mut frame).and_then(|_| frame.finish());
+        let flushed = frame.flushed;
+        if result.is_err() {
+            // 転送できなかったピクセルは次のフレームに持ち越さず、画面全体を転送し直す
+            self.discard();
+            self.invalidate();
+        }
+        result.map(|_| flushed)
+    }
+
+    fn discard(&mut self) {
+        self.map = [[NO_TILE; TILE_COLUMNS]; TILE_ROWS];
+        self.used = 0;
+    }
+
+    // (x, y) を含むタイルを返します。割り当てていなければ割り当てます
+    fn tile(&mut self, x: usize, y: usize) -> Option<&mut Tile> {
+        let index = &mut self.map[y / TILE_HEIGHT][x / TILE_WIDTH];
+        if *index == NO_TILE || *index == FLUSHED {
+            if self.used == TILES {
+                return None;
+            }
+            let complete = *index == NO_TILE;
+            *index = self.used as u8;
+            self.used += 1;
+            let tile = &mut self.tiles[*index as usize];
+            tile.written = [0; TILE_HEIGHT];
+            tile.complete = complete;
+            if complete {
+                tile.pixels = [self.background; TILE_PIXELS];
+            }
+        }
+        Some(&mut self.tiles[*index as usize])
+    }
+
+    // 画面に収まるタイルの列と行の数
+    fn tile_counts(&self) -> (usize, usize) {
+        let columns = (self.size.width as usize).div_ceil(TILE_WIDTH);
+        let rows = (self.size.height as usize).div_ceil(TILE_HEIGHT);
+        (columns, rows)
+    }
+
+    // (column, row) のタイルのうち、画面に収まる幅と高さ
+    fn visible(&self, column: usize, row: usize) -> (usize, usize) {
+        let width =
+            (self.size.width as usize - column * TILE_WIDTH).min(TILE_WIDTH);
+        let height =
+            (self.size.height as usize - row * TILE_HEIGHT).min(TILE_HEIGHT);
+        (width, height)
+    }
+
+    // 溜めたピクセルを画面の上から順に転送し、すべてのタイルを空けます
+    // finishがtrueなら、このフレームで描画しなかった位置も背景色にします
+    fn flush<S: PixelSink>(
+        &mut self,
+        sink: &mut S,
+        finish: bool,
+    ) -> Result<usize, S::Error> {
+        let background_hash = hash(&[self.background; TILE_PIXELS]);
+        let mut flushed = 0;
+        let (columns, rows) = self.tile_counts();
+        for row in 0..rows {
+            for column in 0..columns {
+                let index = self.map[row][column];
+                let (width, height) = self.visible(column, row);
+                let (left, top) = (column * TILE_WIDTH, row * TILE_HEIGHT);
+                let shown = &mut self.shown[row][column];
+                if index == FLUSHED {
+                    continue;
+                } else if index == NO_TILE {
+                    if !finish || *shown == Some(background_hash) {
+                        continue;
+                    }
+                    // 前のフレームで描画した部分を背景色で消す
+                    let pixels = &mut self.scratch[..width * height];
+                    for pixel in pixels.iter_mut() {
+                        *pixel = self.background;
+                    }
+                    sink.write_pixels(
+                        left as u16,
+                        top as u16,
+                        (left + width - 1) as u16,
+                        (top + height - 1) as u16,
+                        pixels,
+                    )?;
+                    *shown = Some(background_hash);
+                    flushed += 1;
+                    continue;
+                }
+
+                self.map[row][column] = FLUSHED;
+                let tile = &self.tiles[index as usize];
+                if tile.complete {
+                    // 前のフレームと同じ内容なら転送しない
+                    let tile_hash = hash(&tile.pixels);
+                    if *shown == Some(tile_hash) {
+                        continue;
+                    }
+                    // 画面に収まる部分をすべて転送する
+                    let mut written = [0; TILE_HEIGHT];
+                    for mask in written[..height].iter_mut() {
+                        *mask = ((1u64 << width) - 1) as u32;
+                    }
+                    flushed += flush_tile(
+                        sink,
+                        tile,
+                        &written,
+                        &mut self.scratch,
+                        left,
+                        top,
+                    )?;
+                    *shown = Some(tile_hash);
+                } else {
+                    // 後から描画したピクセルだけを転送するので、タイル全体の内容は分からない
+                    *shown = None;
+                    flushed += flush_tile(
+                        sink,
+                        tile,
+                        &tile.written,
+                        &mut self.scratch,
+                        left,
+                        top,
+                    )?;
+                }
+            }
+        }
+        if finish {
+            self.discard();
+        }
+        self.used = 0;
+        Ok(flushed)
+    }
+}
+
+// 扱える最大サイズに収めます
+fn fit(size: Size) -> Size {
+    let (long, short) = (MAX_WIDTH as u32, MAX_HEIGHT as u32);
+    if size.width >= size.height {
+        Size::new(size.width.min(long), size.height.min(short))
+    } else {
+        Size::new(size.width.min(short), size.height.min(long))
+    }
+}
+
+// タイルの内容のハッシュ (FNV-1a)
+fn hash(pixels: &[u16; TILE_PIXELS]) -> u32 {
+    let mut hash = 0x811C_9DC5u32;
+    for &pixel in pixels.iter() {
+        hash = (hash ^ pixel as u32).wrapping_mul(0x0100_0193);
+    }
+    hash
+}
+
+// 描画したピクセル (written) が同じ並びの行をまとめ、ピクセルが連続する部分ごとに転送します
+// scratchは転送する矩形を連続した領域にまとめるのに使います
+fn flush_tile<S: PixelSink>(
+    sink: &mut S,
+    tile: &Tile,
+    written: &[u32; TILE_HEIGHT],
+    scratch: &mut [u16; TILE_PIXELS],
+    left: usize,
+    top: usize,
+) -> Result<usize, S::Error> {
+    let mut flushed = 0;
+    let mut first = 0;
+    while first < TILE_HEIGHT {
+        let mask = written[first];
+        let mut last = first + 1;
+        while last < TILE_HEIGHT && written[last] == mask {
+            last += 1;
+        }
+        let mut rest = mask;
+        while rest != 0 {
+            let start = rest.trailing_zeros() as usize;
+            let end = start + (!(rest >> start)).trailing_zeros() as usize;
+            let width = end - start;
+            for (i, row) in (first..last).enumerate() {
+                let src = &tile.pixels[row * TILE_WIDTH + start..][..width];
+                scratch[i * width..][..width].copy_from_slice(src);
+            }
+            sink.write_pixels(
+                (left + start) as u16,
+                (top + first) as u16,
+                (left + end - 1) as u16,
+                (top + last - 1) as u16,
+                &scratch[..width * (last - first)],
+            )?;
+            flushed += 1;
+            rest &= !((1u64 << end) - 1) as u32;
+        }
+        first = last;
+    }
+    Ok(flushed)
+}
+
+// StripRendererで描画中の1フレームです
+// 画面全体の座標系で描画でき、画面の外側のピクセルは捨てられます
+pub struct Frame<'a, S> {
+    renderer: &'a mut StripRenderer,
+    sink: &'a mut S,
+    flushed: usize,
+}
+
+impl<'a, S: PixelSink> Frame<'a, S> {
+    // タイルが足りなくなったときに、溜めたピクセルを転送します
+    fn flush(&mut self) -> Result<(), S::Error> {
+        self.flushed += self.renderer.flush(self.sink, false)?;
+        Ok(())
+    }
+
+    // フレームの最後に、前のフレームから変わった部分をすべて転送します
+    fn finish(&mut self) -> Result<(), S::Error> {
+        self.flushed += self.renderer.flush(self.sink, true)?;
+        Ok(())
+    }
+}
+
+impl<'a, S: PixelSink> DrawTarget<Rgb565> for Frame<'a, S> {
+    type Error = S::Error;
+
+    fn draw_pixel(&mut self, pixel: Pixel<Rgb565>) -> Result<(), S::Error> {
+        let Pixel(Point { x, y }, color) = pixel;
+        let size = self.renderer.size;
+        if x < 0 || y < 0 || x >= size.width as i32 || y >= size.height as i32 {
+            return Ok(());
+        }
+        let (x, y) = (x as usize, y as usize);
+        if self.renderer.tile(x, y).is_none() {
+            // タイルが足りなくなったら、溜めたピクセルを転送して空ける
+            self.flush()?;
+        }
+        if let Some(tile) = self.renderer.tile(x, y) {
+            let (x, y) = (x % TILE_WIDTH, y % TILE_HEIGHT);
+            tile.pixels[y * TILE_WIDTH + x] = RawU16::from(color).into_inner();
+            tile.written[y] |= 1 << x;
+        }
+        Ok(())
+    }
+
+    // 描画処理からは画面全体の大きさに見える
+    fn size(&self) -> Size {
+        self.renderer.size
+    }
+
+    // 背景色を変えて、このフレームでそれまでに描画したピクセルを捨てます
+    // 背景色は次のフレーム以降も使われます。転送はフレームの最後に変わったタイルだけを行います
+    fn clear(&mut self, color: Rgb565) -> Result<(), S::Error> {
+        self.renderer.background = RawU16::from(color).into_inner();
+        self.renderer.discard();
+        Ok(())
+    }
+}
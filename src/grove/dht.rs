@@ -0,0 +1,136 @@
+use super::Measurement;
+use embedded_hal::blocking::delay::{DelayMs, DelayUs};
+use embedded_hal::digital::v2::{InputPin, OutputPin};
+
+// センサの種類。スタート信号の長さとデータの形式が異なります
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Kind {
+    Dht11, // Grove 温湿度センサ (青)
+    Dht22, // Grove 温湿度センサ Pro (白)
+}
+
+#[derive(Debug)]
+pub enum Error<E> {
+    Pin(E),   // ピンの操作に失敗した
+    Timeout,  // センサが応答しなかった
+    Checksum, // 受信したデータのチェックサムが一致しなかった
+}
+
+impl<E> From<E> for Error<E> {
+    fn from(error: E) -> Error<E> {
+        Error::Pin(error)
+    }
+}
+
+// 信号の変化を待つ最大時間 [us]
+const TIMEOUT_US: u16 = 100;
+
+// DHT11/DHT22のドライバです
+// 1本の信号線で双方向に通信するので、pinはHighを出力したときに
+// 入力として読めるもの (オープンドレインなど) を渡します
+// 通信はマイクロ秒単位のタイミングで判定するので、
+// 読み出し中は割り込みを禁止しておくことをおすすめします
+pub struct Dht<P> {
+    pin: P,
+    kind: Kind,
+}
+
+impl<P, E> Dht<P>
+where
+    P: InputPin<Error = E> + OutputPin<Error = E>,
+{
+    pub fn new(mut pin: P, kind: Kind) -> Result<Dht<P>, Error<E>> {
+        // アイドル状態はHigh
+        pin.set_high()?;
+        Ok(Dht { pin, kind })
+    }
+
+    pub fn release(self) -> P {
+        self.pin
+    }
+
+    // 温度と湿度を読み出します
+    // センサの仕様上、前回の読み出しから1秒 (DHT22は2秒) 以上空ける必要があります
+    pub fn read<D>(&mut self, delay: &mut D) -> Result<Measurement, Error<E>>
+    where
+        D: DelayMs<u8> + DelayUs<u16>,
+    {
+        // スタート信号: Lowを一定時間出力してから解放する
+        self.pin.set_low()?;
+        match self.kind {
+            Kind::Dht11 => delay.delay_ms(20u8),
+            Kind::Dht22 => delay.delay_ms(2u8),
+        }
+        self.pin.set_high()?;
+
+        // 応答: センサが約80[us]ずつLowとHighを出力する
+        self.wait_for(false, delay)?;
+        self.wait_for(true, delay)?;
+        self.wait_for(false, delay)?;
+
+        // 各ビットは約50[us]のLowに続くHighの長さで表される
+        // (約27[us]なら0、約70[us]なら1)
+        let mut data = [0u8; 5];
+        for byte in data.iter_mut() {
+            for _ in 0..8 {
+                self.wait_for(true, delay)?;
+                delay.delay_us(35u16);
+                let bit = self.pin.is_high()?;
+                *byte = (*byte << 1) | bit as u8;
+                if bit {
+                    self.wait_for(false, delay)?;
+                }
+            }
+        }
+
+        let sum = data[..4].iter().fold(0u8, |sum, &b| sum.wrapping_add(b));
+        if sum != data[4] {
+            return Err(Error::Checksum);
+        }
+        Ok(decode(self.kind, &data))
+    }
+
+    fn wait_for<D: DelayUs<u16>>(
+        &mut self,
+        high: bool,
+        delay: &mut D,
+    ) -> Result<(), Error<E>> {
+        for _ in 0..TIMEOUT_US {
+            if self.pin.is_high()? == high {
+                return Ok(());
+            }
+            delay.delay_us(1u16);
+        }
+        Err(Error::Timeout)
+    }
+}
+
+fn decode(kind: Kind, data: &[u8; 5]) -> Measurement {
+    match kind {
+        // 整数部と小数部 (0.1単位) がそれぞれ1バイト
+        Kind::Dht11 => Measurement {
+            humidity: data[0] as f32 + data[1] as f32 / 10.0,
+            temperature: {
+                let value = data[2] as f32 + (data[3] & 0x7F) as f32 / 10.0;
+                if data[3] & 0x80 != 0 {
+                    -value
+                } else {
+                    value
+                }
+            },
+        },
+        // 0.1単位の16ビット値。温度のMSBは符号
+        Kind::Dht22 => Measurement {
+            humidity: u16::from_be_bytes([data[0], data[1]]) as f32 / 10.0,
+            temperature: {
+                let value =
+                    u16::from_be_bytes([data[2] & 0x7F, data[3]]) as f32 / 10.0;
+                if data[2] & 0x80 != 0 {
+                    -value
+                } else {
+                    value
+                }
+            },
+        },
+    }
+}
@@ -0,0 +1,15 @@
+// Groveモジュールのドライバです
+// どのドライバもembedded-halのトレイトだけに依存するので、
+// Wio TerminalのどのGroveポートに接続しても使えます
+
+pub mod dht; // 温湿度センサ (DHT11/DHT22)
+pub mod relay; // リレー
+pub mod sht3x; // 温湿度センサ (SHT31/SHT35)
+pub mod ultrasonic; // 超音波距離センサ
+
+// 温湿度センサの測定結果
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Measurement {
+    pub temperature: f32, // 温度 [℃]
+    pub humidity: f32,    // 相対湿度 [%]
+}
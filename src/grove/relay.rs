@@ -0,0 +1,46 @@
+use embedded_hal::digital::v2::OutputPin;
+
+// Grove リレーのドライバです
+// SIGがHighのときに接点が閉じます
+pub struct Relay<P> {
+    pin: P,
+    on: bool,
+}
+
+impl<P: OutputPin> Relay<P> {
+    // 接点を開いた状態で初期化します
+    pub fn new(mut pin: P) -> Result<Relay<P>, P::Error> {
+        pin.set_low()?;
+        Ok(Relay { pin, on: false })
+    }
+
+    pub fn release(self) -> P {
+        self.pin
+    }
+
+    pub fn is_on(&self) -> bool {
+        self.on
+    }
+
+    pub fn turn_on(&mut self) -> Result<(), P::Error> {
+        self.set(true)
+    }
+
+    pub fn turn_off(&mut self) -> Result<(), P::Error> {
+        self.set(false)
+    }
+
+    pub fn toggle(&mut self) -> Result<(), P::Error> {
+        self.set(!self.on)
+    }
+
+    pub fn set(&mut self, on: bool) -> Result<(), P::Error> {
+        if on {
+            self.pin.set_high()?;
+        } else {
+            self.pin.set_low()?;
+        }
+        self.on = on;
+        Ok(())
+    }
+}
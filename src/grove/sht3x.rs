@@ -0,0 +1,149 @@
+use super::Measurement;
+use embedded_hal::blocking::delay::DelayMs;
+use embedded_hal::blocking::i2c::{Read, Write};
+
+// ADDRピンがLowのときのスレーブアドレス (Grove SHT31の既定値)
+pub const ADDRESS: u8 = 0x44;
+// ADDRピンがHighのときのスレーブアドレス
+pub const ALTERNATE_ADDRESS: u8 = 0x45;
+
+// クロックストレッチなしのシングルショット測定コマンド
+const MEASURE_HIGH: u16 = 0x2400;
+const MEASURE_MEDIUM: u16 = 0x240B;
+const MEASURE_LOW: u16 = 0x2416;
+const SOFT_RESET: u16 = 0x30A2;
+const READ_STATUS: u16 = 0xF32D;
+const HEATER_ENABLE: u16 = 0x306D;
+const HEATER_DISABLE: u16 = 0x3066;
+
+// 測定の繰り返し精度。高いほど測定に時間がかかります
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Repeatability {
+    High,
+    Medium,
+    Low,
+}
+
+impl Repeatability {
+    fn command(self) -> u16 {
+        match self {
+            Repeatability::High => MEASURE_HIGH,
+            Repeatability::Medium => MEASURE_MEDIUM,
+            Repeatability::Low => MEASURE_LOW,
+        }
+    }
+
+    // 最大の測定時間 [ms] (データシート Table 4)
+    fn duration_ms(self) -> u8 {
+        match self {
+            Repeatability::High => 16,
+            Repeatability::Medium => 7,
+            Repeatability::Low => 5,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum Error<E> {
+    I2c(E), // I2Cバスのエラー
+    Crc,    // 受信したデータのCRCが一致しなかった
+}
+
+impl<E> From<E> for Error<E> {
+    fn from(error: E) -> Error<E> {
+        Error::I2c(error)
+    }
+}
+
+// Grove 温湿度センサ (SHT31/SHT35) のドライバです
+pub struct Sht3x<I2C> {
+    i2c: I2C,
+    address: u8,
+}
+
+impl<I2C, E> Sht3x<I2C>
+where
+    I2C: Write<Error = E> + Read<Error = E>,
+{
+    pub fn new(i2c: I2C, address: u8) -> Sht3x<I2C> {
+        Sht3x { i2c, address }
+    }
+
+    // I2Cドライバを返して、ドライバを破棄します
+    pub fn release(self) -> I2C {
+        self.i2c
+    }
+
+    // 温度と湿度を1回測定します。測定が終わるまでdelayで待ちます
+    pub fn measure<D: DelayMs<u8>>(
+        &mut self,
+        repeatability: Repeatability,
+        delay: &mut D,
+    ) -> Result<Measurement, Error<E>> {
+        self.command(repeatability.command())?;
+        delay.delay_ms(repeatability.duration_ms());
+        let mut data = [0u8; 6];
+        self.i2c.read(self.address, &mut data)?;
+        let temperature = checked_word(&data[0..3])?;
+        let humidity = checked_word(&data[3..6])?;
+        Ok(Measurement {
+            temperature: -45.0 + 175.0 * temperature as f32 / 65535.0,
+            humidity: 100.0 * humidity as f32 / 65535.0,
+        })
+    }
+
+    // ステータスレジスタを読み出します
+    pub fn status(&mut self) -> Result<u16, Error<E>> {
+        self.command(READ_STATUS)?;
+        let mut data = [0u8; 3];
+        self.i2c.read(self.address, &mut data)?;
+        checked_word(&data)
+    }
+
+    // 結露を飛ばすための内蔵ヒーターを切り替えます
+    pub fn set_heater(&mut self, enable: bool) -> Result<(), Error<E>> {
+        self.command(if enable {
+            HEATER_ENABLE
+        } else {
+            HEATER_DISABLE
+        })
+    }
+
+    pub fn reset<D: DelayMs<u8>>(
+        &mut self,
+        delay: &mut D,
+    ) -> Result<(), Error<E>> {
+        self.command(SOFT_RESET)?;
+        delay.delay_ms(2);
+        Ok(())
+    }
+
+    fn command(&mut self, command: u16) -> Result<(), Error<E>> {
+        self.i2c.write(self.address, &command.to_be_bytes())?;
+        Ok(())
+    }
+}
+
+// 2バイトのデータと1バイトのCRCを検査して、16ビットの値にします
+fn checked_word<E>(data: &[u8]) -> Result<u16, Error<E>> {
+    if crc8(&data[0..2]) != data[2] {
+        return Err(Error::Crc);
+    }
+    Ok(u16::from_be_bytes([data[0], data[1]]))
+}
+
+// CRC-8 (多項式0x31、初期値0xFF)
+fn crc8(data: &[u8]) -> u8 {
+    let mut crc = 0xFFu8;
+    for &byte in data {
+        crc ^= byte;
+        for _ in 0..8 {
+            crc = if crc & 0x80 != 0 {
+                (crc << 1) ^ 0x31
+            } else {
+                crc << 1
+            };
+        }
+    }
+    crc
+}
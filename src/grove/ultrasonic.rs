@@ -0,0 +1,95 @@
+use embedded_hal::blocking::delay::DelayUs;
+use embedded_hal::digital::v2::{InputPin, OutputPin};
+
+// 音速 [mm/us] (約20℃)
+const SPEED_OF_SOUND: f32 = 0.343;
+// エコーを待つ最大時間 [us]。約4[m]の往復に相当する
+const TIMEOUT_US: u32 = 25_000;
+
+#[derive(Debug)]
+pub enum Error<E> {
+    Pin(E),  // ピンの操作に失敗した
+    Timeout, // 範囲内に物体がなく、エコーが返ってこなかった
+}
+
+impl<E> From<E> for Error<E> {
+    fn from(error: E) -> Error<E> {
+        Error::Pin(error)
+    }
+}
+
+// Grove 超音波距離センサのドライバです
+// 1本のSIG線でトリガの出力とエコーの入力を行うので、pinはHighを出力したときに
+// 入力として読めるもの (オープンドレインなど) を渡します
+pub struct Ultrasonic<P> {
+    pin: P,
+}
+
+impl<P, E> Ultrasonic<P>
+where
+    P: InputPin<Error = E> + OutputPin<Error = E>,
+{
+    pub fn new(mut pin: P) -> Result<Ultrasonic<P>, Error<E>> {
+        pin.set_low()?;
+        Ok(Ultrasonic { pin })
+    }
+
+    pub fn release(self) -> P {
+        self.pin
+    }
+
+    // 距離 [mm] を測定します
+    // now_usにはマイクロ秒単位で増えていく時刻を返す関数を渡します
+    pub fn measure_mm<D, F>(
+        &mut self,
+        delay: &mut D,
+        now_us: F,
+    ) -> Result<f32, Error<E>>
+    where
+        D: DelayUs<u16>,
+        F: FnMut() -> u32,
+    {
+        let echo_us = self.measure_echo_us(delay, now_us)?;
+        // 往復の時間なので半分にする
+        Ok(echo_us as f32 * SPEED_OF_SOUND / 2.0)
+    }
+
+    // トリガを送ってから、エコーのHighパルスの長さ [us] を測定します
+    pub fn measure_echo_us<D, F>(
+        &mut self,
+        delay: &mut D,
+        mut now_us: F,
+    ) -> Result<u32, Error<E>>
+    where
+        D: DelayUs<u16>,
+        F: FnMut() -> u32,
+    {
+        // 10[us]のHighパルスでトリガする
+        self.pin.set_low()?;
+        delay.delay_us(2u16);
+        self.pin.set_high()?;
+        delay.delay_us(10u16);
+        self.pin.set_low()?;
+        // 入力として読めるように解放する
+        self.pin.set_high()?;
+
+        let start = now_us();
+        while self.pin.is_high()? {
+            if now_us().wrapping_sub(start) > TIMEOUT_US {
+                return Err(Error::Timeout);
+            }
+        }
+        while self.pin.is_low()? {
+            if now_us().wrapping_sub(start) > TIMEOUT_US {
+                return Err(Error::Timeout);
+            }
+        }
+        let rise = now_us();
+        while self.pin.is_high()? {
+            if now_us().wrapping_sub(rise) > TIMEOUT_US {
+                return Err(Error::Timeout);
+            }
+        }
+        Ok(now_us().wrapping_sub(rise))
+    }
+}
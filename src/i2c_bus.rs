@@ -0,0 +1,148 @@
+use core::cell::RefCell;
+use embedded_hal::blocking::i2c::{Read, Write, WriteRead};
+use heapless::consts::*;
+use heapless::Vec;
+
+// 7ビットアドレスの範囲
+// 0x00〜0x07と0x78〜0x7Fは予約されているが、応答するデバイスもあるので調べる
+pub const FIRST_ADDRESS: u8 = 0x00;
+pub const LAST_ADDRESS: u8 = 0x7F;
+
+// 応答したデバイスのアドレスの一覧
+pub type Addresses = Vec<u8, U128>;
+
+// すべてのアドレスに1バイトの読み出しを試み、ACKを返したアドレスを集めます
+pub fn scan<I2C: Read>(i2c: &mut I2C) -> Addresses {
+    let mut found = Addresses::new();
+    for address in FIRST_ADDRESS..=LAST_ADDRESS {
+        if probe(i2c, address) {
+            found.push(address).ok();
+        }
+    }
+    found
+}
+
+// 指定したアドレスのデバイスが応答するかどうかを調べます
+pub fn probe<I2C: Read>(i2c: &mut I2C, address: u8) -> bool {
+    let mut data = [0u8];
+    i2c.read(address, &mut data).is_ok()
+}
+
+// 共有するバスを保持し、1回の転送の間だけ占有させるための排他制御
+pub trait BusMutex {
+    type Bus;
+
+    fn create(bus: Self::Bus) -> Self;
+
+    fn lock<R, F>(&self, f: F) -> R
+    where
+        F: FnOnce(&mut Self::Bus) -> R;
+}
+
+// 割り込みハンドラから使わない場合の排他制御 (ホストでも使えます)
+impl<T> BusMutex for RefCell<T> {
+    type Bus = T;
+
+    fn create(bus: T) -> RefCell<T> {
+        RefCell::new(bus)
+    }
+
+    fn lock<R, F>(&self, f: F) -> R
+    where
+        F: FnOnce(&mut T) -> R,
+    {
+        f(&mut self.borrow_mut())
+    }
+}
+
+// 転送の間は割り込みを禁止するので、割り込みハンドラからも安全に使えます
+#[cfg(target_arch = "arm")]
+impl<T> BusMutex for cortex_m::interrupt::Mutex<RefCell<T>> {
+    type Bus = T;
+
+    fn create(bus: T) -> Self {
+        cortex_m::interrupt::Mutex::new(RefCell::new(bus))
+    }
+
+    fn lock<R, F>(&self, f: F) -> R
+    where
+        F: FnOnce(&mut T) -> R,
+    {
+        cortex_m::interrupt::free(|cs| f(&mut self.borrow(cs).borrow_mut()))
+    }
+}
+
+// 1つのI2Cバスを複数のドライバで共有するためのラッパです
+// 各ドライバにはacquire()で作ったプロキシを渡します
+pub struct SharedBus<M> {
+    bus: M,
+}
+
+// メインループからだけ使う共有バス
+pub type LocalBus<I2C> = SharedBus<RefCell<I2C>>;
+// 割り込みハンドラからも使える共有バス
+#[cfg(target_arch = "arm")]
+pub type InterruptSafeBus<I2C> =
+    SharedBus<cortex_m::interrupt::Mutex<RefCell<I2C>>>;
+
+impl<M: BusMutex> SharedBus<M> {
+    pub fn new(i2c: M::Bus) -> SharedBus<M> {
+        SharedBus {
+            bus: M::create(i2c),
+        }
+    }
+
+    pub fn acquire(&self) -> BusProxy<'_, M> {
+        BusProxy { bus: &self.bus }
+    }
+}
+
+// 共有されたI2Cバスへのハンドル
+pub struct BusProxy<'a, M> {
+    bus: &'a M,
+}
+
+impl<'a, M> Read for BusProxy<'a, M>
+where
+    M: BusMutex,
+    M::Bus: Read,
+{
+    type Error = <M::Bus as Read>::Error;
+
+    fn read(
+        &mut self,
+        address: u8,
+        buffer: &mut [u8],
+    ) -> Result<(), Self::Error> {
+        self.bus.lock(|i2c| i2c.read(address, buffer))
+    }
+}
+
+impl<'a, M> Write for BusProxy<'a, M>
+where
+    M: BusMutex,
+    M::Bus: Write,
+{
+    type Error = <M::Bus as Write>::Error;
+
+    fn write(&mut self, address: u8, bytes: &[u8]) -> Result<(), Self::Error> {
+        self.bus.lock(|i2c| i2c.write(address, bytes))
+    }
+}
+
+impl<'a, M> WriteRead for BusProxy<'a, M>
+where
+    M: BusMutex,
+    M::Bus: WriteRead,
+{
+    type Error = <M::Bus as WriteRead>::Error;
+
+    fn write_read(
+        &mut self,
+        address: u8,
+        bytes: &[u8],
+        buffer: &mut [u8],
+    ) -> Result<(), Self::Error> {
+        self.bus.lock(|i2c| i2c.write_read(address, bytes, buffer))
+    }
+}
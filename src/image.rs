@@ -0,0 +1,387 @@
+// RGB565の画像アセットの読み込み
+// アセットはホストのツール (tools/imgconv) でPNG/BMPから変換して作ります
+//
+// ヘッダ付きアセットのレイアウト (数値はリトルエンディアン)
+//   0..3  マジック "565"
+//   3     形式 (0: 無圧縮, 1: RLE, 2: QOI形式)
+//   4..6  幅 [px]
+//   6..8  高さ [px]
+//   8..   ピクセルデータ
+//
+// ピクセルはビッグエンディアンのRGB565で、左上から行の順に並びます
+// RLEは1バイトのパケットヘッダに続けてピクセルを置きます
+//   最上位ビットが1: 続く1ピクセルを (下位7ビット + 1) 回繰り返す
+//   最上位ビットが0: 続く (下位7ビット + 1) 個のピクセルをそのまま使う
+// QOI形式は、QOI (https://qoiformat.org) の命令をRGB565向けに変えたものです
+//   00iiiiii          直近の色の表 (64色) のi番目の色
+//   01rrggbb          直前の色との差分 (各成分 -2〜1)
+//   10gggggg rrrrbbbb 直前の色との差分 (G -32〜31、R/BはGの差分との差 -8〜7)
+//   11nnnnnn          直前の色をn + 1回繰り返す (nは0〜61)
+//   11111110 pppp     続く2バイトのピクセルをそのまま使う
+// 色の表の位置は (R * 3 + G * 5 + B * 7) % 64 で、直前の色と表は黒で初期化します
+use crate::framebuffer::{PixelSink, MAX_HEIGHT, MAX_WIDTH};
+use eg::drawable::Pixel;
+use eg::geometry::Point;
+use eg::image::{ImageDimensions, ImageRawBE, IntoPixelIter};
+use eg::pixelcolor::raw::{RawData, RawU16};
+use eg::pixelcolor::Rgb565;
+use embedded_graphics as eg;
+
+pub const MAGIC: [u8; 3] = *b"565";
+pub const HEADER_SIZE: usize = 8;
+// 1つのRLEパケットで表せる最大のピクセル数
+pub const MAX_PACKET_PIXELS: usize = 128;
+pub const RLE_RUN: u8 = 0x80;
+
+// QOI形式の命令
+pub const QOI_INDEX: u8 = 0x00;
+pub const QOI_DIFF: u8 = 0x40;
+pub const QOI_LUMA: u8 = 0x80;
+pub const QOI_RUN: u8 = 0xC0;
+pub const QOI_RGB565: u8 = 0xFE;
+pub const QOI_MASK: u8 = 0xC0;
+pub const QOI_INDEX_SIZE: usize = 64;
+// 1つの命令で繰り返せる最大のピクセル数
+pub const QOI_MAX_RUN: usize = 62;
+
+// QOI形式で使う直近の色の表の位置
+pub fn qoi_hash(color: u16) -> usize {
+    let (r, g, b) = split(color);
+    (r as usize * 3 + g as usize * 5 + b as usize * 7) % QOI_INDEX_SIZE
+}
+
+// RGB565の値を (R, G, B) の各成分に分けます
+pub fn split(color: u16) -> (i16, i16, i16) {
+    (
+        (color >> 11) as i16,
+        ((color >> 5) & 0x3F) as i16,
+        (color & 0x1F) as i16,
+    )
+}
+
+fn join(r: i16, g: i16, b: i16) -> u16 {
+    ((r as u16 & 0x1F) << 11) | ((g as u16 & 0x3F) << 5) | (b as u16 & 0x1F)
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Format {
+    Raw = 0,
+    Rle = 1,
+    Qoi = 2,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Error {
+    // ヘッダが足りない、またはマジックが一致しない
+    InvalidHeader,
+    UnknownFormat(u8),
+    // 無圧縮のデータの長さが幅と高さに合わない
+    SizeMismatch,
+}
+
+// ヘッダを解釈した画像アセット。データはフラッシュに置いたまま参照します
+#[derive(Clone, Copy, Debug)]
+pub struct Asset<'a> {
+    format: Format,
+    width: u16,
+    height: u16,
+    data: &'a [u8],
+}
+
+impl<'a> Asset<'a> {
+    // ヘッダ付きのアセットを読み込みます
+    pub fn parse(bytes: &'a [u8]) -> Result<Asset<'a>, Error> {
+        if bytes.len() < HEADER_SIZE || bytes[..3] != MAGIC {
+            return Err(Error::InvalidHeader);
+        }
+        let format = match bytes[3] {
+            0 => Format::Raw,
+            1 => Format::Rle,
+            2 => Format::Qoi,
+            other => return Err(Error::UnknownFormat(other)),
+        };
+        let width = u16::from_le_bytes([bytes[4], bytes[5]]);
+        let height = u16::from_le_bytes([bytes[6], bytes[7]]);
+        let asset = Asset {
+            format,
+            width,
+            height,
+            data: &bytes[HEADER_SIZE..],
+        };
+        if format == Format::Raw && asset.data.len() != asset.pixel_count() * 2
+        {
+            return Err(Error::SizeMismatch);
+        }
+        Ok(asset)
+    }
+
+    // ヘッダのない無圧縮のデータ (examples/assets/ferris.raw など) を読み込みます
+    pub fn from_raw(data: &'a [u8], width: u16) -> Result<Asset<'a>, Error> {
+        let pixels = data.len() / 2;
+        if width == 0
+            || !data.len().is_multiple_of(2)
+            || !pixels.is_multiple_of(width as usize)
+        {
+            return Err(Error::SizeMismatch);
+        }
+        Ok(Asset {
+            format: Format::Raw,
+            width,
+            height: (pixels / width as usize) as u16,
+            data,
+        })
+    }
+
+    pub fn format(&self) -> Format {
+        self.format
+    }
+
+    pub fn width(&self) -> u16 {
+        self.width
+    }
+
+    pub fn height(&self) -> u16 {
+        self.height
+    }
+
+    pub fn pixel_count(&self) -> usize {
+        self.width as usize * self.height as usize
+    }
+
+    // 無圧縮の場合は、embedded-graphicsのImageRawとしても使えます
+    pub fn as_image_raw(&self) -> Option<ImageRawBE<'a, Rgb565>> {
+        match self.format {
+            Format::Raw => Some(ImageRawBE::new(
+                self.data,
+                self.width as u32,
+                self.height as u32,
+            )),
+            Format::Rle | Format::Qoi => None,
+        }
+    }
+
+    // 左上から行の順にピクセルの色を返します
+    // 圧縮されていても順に展開するので、画像全体を展開するバッファは必要ありません
+    pub fn colors(&self) -> Colors<'a> {
+        Colors {
+            format: self.format,
+            data: self.data,
+            remaining: self.pixel_count(),
+            run: 0,
+            literal: 0,
+            color: 0,
+            index: [0; QOI_INDEX_SIZE],
+        }
+    }
+
+    // 展開しながら1行ずつsinkに転送します。画面からはみ出す部分は転送しません
+    // フレームバッファを使わずに、LCDへ直接描画できます
+    pub fn write_to<S: PixelSink>(
+        &self,
+        sink: &mut S,
+        top_left: Point,
+    ) -> Result<(), S::Error> {
+        let width = self.width as i32;
+        let x0 = top_left.x.max(0);
+        let x1 = (top_left.x + width).min(MAX_WIDTH as i32);
+        if x0 >= x1 {
+            return Ok(());
+        }
+        let mut row = [0u16; MAX_WIDTH];
+        let mut colors = self.colors();
+        for dy in 0..self.height as i32 {
+            let y = top_left.y + dy;
+            if y >= MAX_HEIGHT as i32 {
+                break;
+            }
+            let mut count = 0;
+            for dx in 0..width {
+                let raw = match colors.next() {
+                    Some(color) => RawU16::from(color).into_inner(),
+                    None => return Ok(()),
+                };
+                let x = top_left.x + dx;
+                if y >= 0 && x >= x0 && x < x1 {
+                    row[count] = raw;
+                    count += 1;
+                }
+            }
+            if count > 0 {
+                sink.write_pixels(
+                    x0 as u16,
+                    y as u16,
+                    (x1 - 1) as u16,
+                    y as u16,
+                    &row[..count],
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn read_pixel(data: &[u8]) -> Option<(u16, &[u8])> {
+    if data.len() < 2 {
+        return None;
+    }
+    Some((u16::from_be_bytes([data[0], data[1]]), &data[2..]))
+}
+
+// アセットのピクセルの色を順に展開するイテレータ
+// データが途中で切れている場合は、そこで終わります
+pub struct Colors<'a> {
+    format: Format,
+    data: &'a [u8],
+    remaining: usize,
+    // 繰り返しパケットの残りピクセル数と色
+    run: usize,
+    color: u16,
+    // そのまま使うパケットの残りピクセル数
+    literal: usize,
+    // QOI形式の直近の色の表 (直前の色はcolor、繰り返しの残りはrun)
+    index: [u16; QOI_INDEX_SIZE],
+}
+
+impl<'a> Colors<'a> {
+    fn next_qoi(&mut self) -> Option<u16> {
+        if self.run > 0 {
+            self.run -= 1;
+            return Some(self.color);
+        }
+        let (&op, mut rest) = self.data.split_first()?;
+        let (r, g, b) = split(self.color);
+        let color = if op == QOI_RGB565 {
+            let (color, after) = read_pixel(rest)?;
+            rest = after;
+            color
+        } else {
+            match op & QOI_MASK {
+                QOI_INDEX => self.index[op as usize],
+                QOI_DIFF => {
+                    let diff = |shift: u8| ((op >> shift) & 0x03) as i16 - 2;
+                    join(r + diff(4), g + diff(2), b + diff(0))
+                }
+                QOI_LUMA => {
+                    let (&next, after) = rest.split_first()?;
+                    rest = after;
+                    let dg = (op & 0x3F) as i16 - 32;
+                    let dr = (next >> 4) as i16 - 8 + dg;
+                    let db = (next & 0x0F) as i16 - 8 + dg;
+                    join(r + dr, g + dg, b + db)
+                }
+                _ => {
+                    self.run = (op & 0x3F) as usize;
+                    self.color
+                }
+            }
+        };
+        self.data = rest;
+        self.index[qoi_hash(color)] = color;
+        self.color = color;
+        Some(color)
+    }
+
+    fn next_raw(&mut self) -> Option<u16> {
+        if self.format == Format::Qoi {
+            return self.next_qoi();
+        }
+        if self.format == Format::Rle && self.run == 0 && self.literal == 0 {
+            let (&header, rest) = self.data.split_first()?;
+            let count = (header & !RLE_RUN) as usize + 1;
+            if header & RLE_RUN != 0 {
+                let (color, rest) = read_pixel(rest)?;
+                self.run = count;
+                self.color = color;
+                self.data = rest;
+            } else {
+                self.literal = count;
+                self.data = rest;
+            }
+        }
+        if self.run > 0 {
+            self.run -= 1;
+            return Some(self.color);
+        }
+        let (color, rest) = read_pixel(self.data)?;
+        self.data = rest;
+        self.literal = self.literal.saturating_sub(1);
+        Some(color)
+    }
+}
+
+impl<'a> Iterator for Colors<'a> {
+    type Item = Rgb565;
+
+    fn next(&mut self) -> Option<Rgb565> {
+        if self.remaining == 0 {
+            return None;
+        }
+        match self.next_raw() {
+            Some(raw) => {
+                self.remaining -= 1;
+                Some(Rgb565::from(RawU16::new(raw)))
+            }
+            None => {
+                self.remaining = 0;
+                None
+            }
+        }
+    }
+
+    // 無圧縮の場合は、展開せずに読み飛ばします
+    fn nth(&mut self, n: usize) -> Option<Rgb565> {
+        if self.format == Format::Raw {
+            let n = n.min(self.remaining);
+            self.data = &self.data[(n * 2).min(self.data.len())..];
+            self.remaining -= n;
+            return self.next();
+        }
+        for _ in 0..n {
+            self.next()?;
+        }
+        self.next()
+    }
+}
+
+// embedded-graphicsのImageとして描画できるようにする
+// Image::new(&asset, position).draw(&mut display)
+impl<'a> ImageDimensions for Asset<'a> {
+    fn width(&self) -> u32 {
+        self.width as u32
+    }
+
+    fn height(&self) -> u32 {
+        self.height as u32
+    }
+}
+
+impl<'a> IntoPixelIter<Rgb565> for &Asset<'a> {
+    type PixelIterator = Pixels<'a>;
+
+    fn pixel_iter(self) -> Pixels<'a> {
+        Pixels {
+            colors: self.colors(),
+            width: self.width as i32,
+            index: 0,
+        }
+    }
+}
+
+// 画像の左上を原点とした座標付きのピクセル
+pub struct Pixels<'a> {
+    colors: Colors<'a>,
+    width: i32,
+    index: i32,
+}
+
+impl<'a> Iterator for Pixels<'a> {
+    type Item = Pixel<Rgb565>;
+
+    fn next(&mut self) -> Option<Pixel<Rgb565>> {
+        let color = self.colors.next()?;
+        let point =
+            Point::new(self.index % self.width, self.index / self.width);
+        self.index += 1;
+        Some(Pixel(point, color))
+    }
+}
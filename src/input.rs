@@ -0,0 +1,143 @@
+// ボタンと5方向スイッチの入力
+// チャタリングを取り除き、押した・離した・長押しのイベントに変換します
+// ピンの状態と現在時刻を渡すだけなので、ハードウェアに依存しません
+
+// この時間 [ms] 状態が変わらなければ、入力が確定したとみなす
+pub const DEBOUNCE_MS: u32 = 20;
+// この時間 [ms] 押し続けると長押しとみなす
+pub const LONG_PRESS_MS: u32 = 800;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Key {
+    Button1, // 上面の右側のボタン
+    Button2, // 上面の中央のボタン
+    Button3, // 上面の左側のボタン
+    Up,
+    Down,
+    Left,
+    Right,
+    Press, // 5方向スイッチの押し込み
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Event {
+    Pressed(Key),
+    Released(Key),
+    // 押し続けている間に1回だけ発生する
+    LongPress(Key),
+}
+
+// 1つのキーの状態
+#[derive(Clone, Copy, Debug)]
+pub struct KeyState {
+    key: Key,
+    // 確定した状態
+    pressed: bool,
+    // 最後に読み取った状態と、その状態になった時刻
+    raw: bool,
+    changed_ms: u32,
+    // 押されたことが確定した時刻
+    pressed_ms: u32,
+    long_pressed: bool,
+}
+
+impl KeyState {
+    pub const fn new(key: Key) -> KeyState {
+        KeyState {
+            key,
+            pressed: false,
+            raw: false,
+            changed_ms: 0,
+            pressed_ms: 0,
+            long_pressed: false,
+        }
+    }
+
+    pub fn key(&self) -> Key {
+        self.key
+    }
+
+    pub fn is_pressed(&self) -> bool {
+        self.pressed
+    }
+
+    // ピンの状態 (押されていればtrue) と現在時刻 [ms] を渡して、イベントを取り出します
+    // 数ms〜数十msごとに呼び出します。時刻は一周しても構いません
+    pub fn update(&mut self, raw: bool, now_ms: u32) -> Option<Event> {
+        if raw != self.raw {
+            self.raw = raw;
+            self.changed_ms = now_ms;
+            return None;
+        }
+        if raw != self.pressed
+            && now_ms.wrapping_sub(self.changed_ms) >= DEBOUNCE_MS
+        {
+            self.pressed = raw;
+            if raw {
+                self.pressed_ms = now_ms;
+                self.long_pressed = false;
+                return Some(Event::Pressed(self.key));
+            }
+            return Some(Event::Released(self.key));
+        }
+        if self.pressed
+            && !self.long_pressed
+            && now_ms.wrapping_sub(self.pressed_ms) >= LONG_PRESS_MS
+        {
+            self.long_pressed = true;
+            return Some(Event::LongPress(self.key));
+        }
+        None
+    }
+}
+
+pub const ALL_KEYS: [Key; 8] = [
+    Key::Button1,
+    Key::Button2,
+    Key::Button3,
+    Key::Up,
+    Key::Down,
+    Key::Left,
+    Key::Right,
+    Key::Press,
+];
+
+// すべてのキーをまとめて扱います
+pub struct Keys {
+    states: [KeyState; 8],
+}
+
+impl Keys {
+    pub fn new() -> Keys {
+        let mut states = [KeyState::new(Key::Button1); 8];
+        for (state, &key) in states.iter_mut().zip(ALL_KEYS.iter()) {
+            *state = KeyState::new(key);
+        }
+        Keys { states }
+    }
+
+    pub fn is_pressed(&self, key: Key) -> bool {
+        self.states.iter().any(|s| s.key == key && s.pressed)
+    }
+
+    // is_pressedでキーごとのピンの状態を読み取り、最初に見つかったイベントを返します
+    // イベントが見つかったキーより後ろのキーは、次の呼び出しで処理します
+    pub fn poll<F>(&mut self, mut is_pressed: F, now_ms: u32) -> Option<Event>
+    where
+        F: FnMut(Key) -> bool,
+    {
+        for state in self.states.iter_mut() {
+            let raw = is_pressed(state.key);
+            if let Some(event) = state.update(raw, now_ms) {
+                return Some(event);
+            }
+        }
+        None
+    }
+}
+
+impl Default for Keys {
+    fn default() -> Keys {
+        Keys::new()
+    }
+}
@@ -0,0 +1,150 @@
+// ストップウォッチのラップタイムの記録と、フラッシュに保存するための変換
+// 時刻はすべて計測開始からの経過時間 [ms] で扱います
+use heapless::consts::*;
+use heapless::Vec;
+
+// 記録できるラップの最大数
+pub const MAX_LAPS: usize = 64;
+
+#[derive(Clone, Debug, Default)]
+pub struct Laps {
+    // ラップを記録した時点の経過時間 (スプリットタイム)
+    splits: Vec<u32, U64>,
+}
+
+impl Laps {
+    pub fn new() -> Laps {
+        Laps { splits: Vec::new() }
+    }
+
+    pub fn clear(&mut self) {
+        self.splits = Vec::new();
+    }
+
+    // 経過時間split_msでラップを記録して、ラップの番号を返します
+    // いっぱいのときは記録せずにNoneを返します
+    pub fn record(&mut self, split_ms: u32) -> Option<usize> {
+        self.splits.push(split_ms).ok()?;
+        Some(self.splits.len() - 1)
+    }
+
+    pub fn len(&self) -> usize {
+        self.splits.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.splits.is_empty()
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.splits.len() == MAX_LAPS
+    }
+
+    // index番目のラップを記録したときの経過時間
+    pub fn split(&self, index: usize) -> u32 {
+        self.splits[index]
+    }
+
+    // index番目のラップの所要時間 (前のラップからの時間)
+    pub fn lap(&self, index: usize) -> u32 {
+        let previous = if index == 0 {
+            0
+        } else {
+            self.splits[index - 1]
+        };
+        self.splits[index].saturating_sub(previous)
+    }
+
+    // 最も速いラップの番号 (2周以上記録したときだけ)
+    pub fn best(&self) -> Option<usize> {
+        self.extreme(|lap, other| lap < other)
+    }
+
+    // 最も遅いラップの番号 (2周以上記録したときだけ)
+    pub fn worst(&self) -> Option<usize> {
+        self.extreme(|lap, other| lap > other)
+    }
+
+    fn extreme<F: Fn(u32, u32) -> bool>(&self, better: F) -> Option<usize> {
+        if self.len() < 2 {
+            return None;
+        }
+        let mut found = 0;
+        for index in 1..self.len() {
+            if better(self.lap(index), self.lap(found)) {
+                found = index;
+            }
+        }
+        Some(found)
+    }
+}
+
+// フラッシュに保存する1回分の計測
+//   0..4    マジック "LAPS"
+//   4..6    ラップの数 (リトルエンディアン)
+//   6..8    予約 (0)
+//   8..12   停止したときの経過時間 [ms]
+//   12..    スプリットタイム [ms] がMAX_LAPS個 (使わない部分は0)
+//   最後の4バイト  ここまでのチェックサム
+// 消去されたフラッシュ (すべて0xFF) や壊れたデータは読み込みません
+#[derive(Clone, Debug, Default)]
+pub struct Session {
+    pub elapsed_ms: u32,
+    pub laps: Laps,
+}
+
+pub const MAGIC: [u8; 4] = *b"LAPS";
+// フラッシュに4ワード (16バイト) 単位で書き込めるように揃えた大きさ
+pub const RECORD_SIZE: usize = 12 + MAX_LAPS * 4 + 4;
+
+fn checksum(bytes: &[u8]) -> u32 {
+    // Fletcher-32と同じ考え方で、値の順序の入れ替わりも検出する
+    let (mut a, mut b) = (0u32, 0u32);
+    for &byte in bytes {
+        a = (a + byte as u32) % 65535;
+        b = (b + a) % 65535;
+    }
+    b << 16 | a
+}
+
+impl Session {
+    pub fn encode(&self) -> [u8; RECORD_SIZE] {
+        let mut record = [0u8; RECORD_SIZE];
+        record[..4].copy_from_slice(&MAGIC);
+        record[4..6].copy_from_slice(&(self.laps.len() as u16).to_le_bytes());
+        record[8..12].copy_from_slice(&self.elapsed_ms.to_le_bytes());
+        for (i, split) in self.laps.splits.iter().enumerate() {
+            let offset = 12 + i * 4;
+            record[offset..offset + 4].copy_from_slice(&split.to_le_bytes());
+        }
+        let sum = checksum(&record[..RECORD_SIZE - 4]);
+        record[RECORD_SIZE - 4..].copy_from_slice(&sum.to_le_bytes());
+        record
+    }
+
+    pub fn decode(record: &[u8]) -> Option<Session> {
+        if record.len() < RECORD_SIZE || record[..4] != MAGIC {
+            return None;
+        }
+        let word = |offset: usize| {
+            let mut bytes = [0u8; 4];
+            bytes.copy_from_slice(&record[offset..offset + 4]);
+            u32::from_le_bytes(bytes)
+        };
+        if word(RECORD_SIZE - 4) != checksum(&record[..RECORD_SIZE - 4]) {
+            return None;
+        }
+        let count = u16::from_le_bytes([record[4], record[5]]) as usize;
+        if count > MAX_LAPS {
+            return None;
+        }
+        let mut laps = Laps::new();
+        for i in 0..count {
+            laps.record(word(12 + i * 4))?;
+        }
+        Some(Session {
+            elapsed_ms: word(8),
+            laps,
+        })
+    }
+}
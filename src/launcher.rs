@@ -0,0 +1,142 @@
+// 1つのファームウェアに複数のアプリを登録して、メニューから選んで実行するランチャー
+// 5方向スイッチの上下でアプリを選び、押し込むと起動します
+// アプリの実行中に5方向スイッチを長押しすると、アプリを終了してメニューに戻ります
+use crate::input::{Event, Key};
+use crate::ui::{layout, status_bar, ListMenu, StatusBar, Theme, Widget};
+use eg::fonts::Font12x16;
+use eg::pixelcolor::Rgb565;
+use eg::prelude::*;
+use embedded_graphics as eg;
+use heapless::consts::*;
+use heapless::Vec;
+
+// 登録できるアプリの最大数
+pub const MAX_APPS: usize = 16;
+
+pub type Names = Vec<&'static str, U16>;
+
+// アプリとランチャーが共有するハードウェア (ディスプレイなど)
+pub trait Context {
+    type Display: DrawTarget<Rgb565>;
+
+    fn display(&mut self) -> &mut Self::Display;
+}
+
+pub trait App<C: Context> {
+    // メニューに表示する名前
+    fn name(&self) -> &'static str;
+
+    // アプリを起動したときに1回呼び出します。画面は消去済みです
+    fn init(&mut self, ctx: &mut C);
+
+    // 実行中に繰り返し呼び出します。入力があればeventに渡します
+    // 長押しで終了する前には Pressed(Key::Press) も届くので、
+    // 5方向スイッチの押し込みには Released で反応するとよいでしょう
+    // 起動したときの押し込みは、離すまで長押しも離したイベントも届かず、
+    // 押し続けてもアプリは終了しません
+    fn run(&mut self, ctx: &mut C, event: Option<Event>);
+
+    // メニューに戻る前に1回呼び出します。出力を止めるなどの後始末をします
+    fn exit(&mut self, ctx: &mut C);
+}
+
+// アプリの名前の一覧を作ります。Launcher::newに渡します
+pub fn names<C: Context>(apps: &[&mut dyn App<C>]) -> Names {
+    apps.iter().take(MAX_APPS).map(|app| app.name()).collect()
+}
+
+pub struct Launcher<'a, C: Context> {
+    apps: &'a mut [&'a mut dyn App<C>],
+    menu: ListMenu<'a, Font12x16>,
+    status: StatusBar,
+    theme: Theme,
+    running: Option<usize>,
+    // メニューを表示するときに画面を消去する必要があるか
+    cleared: bool,
+    // 起動に使った押し込みを離すまで、その長押しと離したイベントを無視する
+    swallow_release: bool,
+}
+
+impl<'a, C: Context> Launcher<'a, C> {
+    pub fn new(
+        names: &'a Names,
+        apps: &'a mut [&'a mut dyn App<C>],
+        theme: Theme,
+    ) -> Launcher<'a, C> {
+        assert!(names.len() == apps.len());
+        let (_, body) = layout::split_top(layout::screen(), status_bar::HEIGHT);
+        let mut status = StatusBar::new(theme);
+        status.set_title("Wio Terminal");
+        Launcher {
+            apps,
+            menu: ListMenu::new(
+                layout::inset(body, 4),
+                Font12x16,
+                theme,
+                names,
+            ),
+            status,
+            theme,
+            running: None,
+            cleared: false,
+            swallow_release: false,
+        }
+    }
+
+    // 実行中のアプリの番号
+    pub fn running(&self) -> Option<usize> {
+        self.running
+    }
+
+    // 繰り返し呼び出します。メニューの操作またはアプリの実行をします
+    pub fn update(
+        &mut self,
+        ctx: &mut C,
+        event: Option<Event>,
+    ) -> Result<(), <C::Display as DrawTarget<Rgb565>>::Error> {
+        if let Some(index) = self.running {
+            if self.swallow_release {
+                // 起動に使った押し込みは、押し続けてもアプリを終了しない
+                match event {
+                    Some(Event::LongPress(Key::Press)) => return Ok(()),
+                    Some(Event::Released(Key::Press)) => {
+                        self.swallow_release = false;
+                        return Ok(());
+                    }
+                    _ => {}
+                }
+            }
+            if event == Some(Event::LongPress(Key::Press)) {
+                self.apps[index].exit(ctx);
+                self.running = None;
+                self.cleared = false;
+            } else {
+                self.apps[index].run(ctx, event);
+                return Ok(());
+            }
+        }
+
+        if !self.cleared {
+            ctx.display().clear(self.theme.background)?;
+            self.menu.invalidate();
+            self.status.invalidate();
+            self.cleared = true;
+        }
+        match event {
+            Some(Event::Pressed(Key::Up)) => self.menu.select_previous(),
+            Some(Event::Pressed(Key::Down)) => self.menu.select_next(),
+            Some(Event::Pressed(Key::Press)) => {
+                let index = self.menu.selected();
+                ctx.display().clear(self.theme.background)?;
+                self.apps[index].init(ctx);
+                self.running = Some(index);
+                self.swallow_release = true;
+                return Ok(());
+            }
+            _ => {}
+        }
+        self.status.draw(ctx.display())?;
+        self.menu.draw(ctx.display())?;
+        Ok(())
+    }
+}
@@ -0,0 +1,491 @@
+use embedded_hal::blocking::delay::DelayMs;
+use embedded_hal::blocking::spi::{Transfer, Write};
+use embedded_hal::digital::v2::OutputPin;
+
+// LCDの解像度 (横長の向き)
+pub const WIDTH: u16 = 320;
+pub const HEIGHT: u16 = 240;
+
+// ILI9341のコマンド (データシート 8章)
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Command {
+    Nop = 0x00,
+    SoftwareReset = 0x01,
+    ReadDisplayId = 0x04,
+    ReadDisplayStatus = 0x09,
+    ReadPowerMode = 0x0A,
+    ReadMadctl = 0x0B,
+    ReadPixelFormat = 0x0C,
+    SleepIn = 0x10,
+    SleepOut = 0x11,
+    PartialModeOn = 0x12,
+    NormalModeOn = 0x13,
+    InversionOff = 0x20,
+    InversionOn = 0x21,
+    GammaSet = 0x26,
+    DisplayOff = 0x28,
+    DisplayOn = 0x29,
+    ColumnAddressSet = 0x2A,
+    PageAddressSet = 0x2B,
+    MemoryWrite = 0x2C,
+    PartialArea = 0x30,
+    VerticalScrollingDefinition = 0x33,
+    TearingEffectOff = 0x34,
+    TearingEffectOn = 0x35,
+    MemoryAccessControl = 0x36,
+    VerticalScrollingStart = 0x37,
+    IdleModeOff = 0x38,
+    IdleModeOn = 0x39,
+    PixelFormatSet = 0x3A,
+    PositiveGammaCorrection = 0xE0,
+    NegativeGammaCorrection = 0xE1,
+}
+
+// Read Display Power Mode (0x0A) の結果
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct PowerMode(pub u8);
+
+impl PowerMode {
+    pub fn booster_on(&self) -> bool {
+        self.0 & (1 << 7) != 0
+    }
+
+    pub fn idle_mode(&self) -> bool {
+        self.0 & (1 << 6) != 0
+    }
+
+    pub fn partial_mode(&self) -> bool {
+        self.0 & (1 << 5) != 0
+    }
+
+    pub fn sleep_out(&self) -> bool {
+        self.0 & (1 << 4) != 0
+    }
+
+    pub fn normal_mode(&self) -> bool {
+        self.0 & (1 << 3) != 0
+    }
+
+    pub fn display_on(&self) -> bool {
+        self.0 & (1 << 2) != 0
+    }
+}
+
+// Read Display Identification Information (0x04) の結果
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct DisplayId {
+    pub manufacturer: u8,
+    pub version: u8,
+    pub module: u8,
+}
+
+// メモリアクセス制御 (MADCTL) の設定
+// 行・列の走査方向を組み合わせて、表示の向きを決めます
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct MemoryAccess {
+    pub row_reverse: bool,        // MY
+    pub column_reverse: bool,     // MX
+    pub row_column_swap: bool,    // MV
+    pub vertical_refresh: bool,   // ML
+    pub bgr: bool,                // BGR
+    pub horizontal_refresh: bool, // MH
+}
+
+impl MemoryAccess {
+    // Wio TerminalのLCDを横長に、画面を正面から見た向きで表示する設定
+    pub fn landscape() -> MemoryAccess {
+        MemoryAccess {
+            row_column_swap: true,
+            bgr: true,
+            ..MemoryAccess::default()
+        }
+    }
+
+    pub fn bits(self) -> u8 {
+        (self.row_reverse as u8) << 7
+            | (self.column_reverse as u8) << 6
+            | (self.row_column_swap as u8) << 5
+            | (self.vertical_refresh as u8) << 4
+            | (self.bgr as u8) << 3
+            | (self.horizontal_refresh as u8) << 2
+    }
+
+    pub fn from_bits(bits: u8) -> MemoryAccess {
+        MemoryAccess {
+            row_reverse: bits & (1 << 7) != 0,
+            column_reverse: bits & (1 << 6) != 0,
+            row_column_swap: bits & (1 << 5) != 0,
+            vertical_refresh: bits & (1 << 4) != 0,
+            bgr: bits & (1 << 3) != 0,
+            horizontal_refresh: bits & (1 << 2) != 0,
+        }
+    }
+}
+
+// 1ピクセルあたりのビット数 (COLMOD)
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum PixelFormat {
+    Bits16 = 0x55, // RGB565
+    Bits18 = 0x66, // RGB666
+}
+
+// 定義済みのガンマカーブ (Gamma Set)
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum GammaCurve {
+    G2_2 = 0x01,
+    G1_8 = 0x02,
+    G2_5 = 0x04,
+    G1_0 = 0x08,
+}
+
+#[derive(Debug)]
+pub enum Error<SpiE, PinE> {
+    Spi(SpiE),   // SPIの転送に失敗した
+    Pin(PinE),   // DC/CS/RSTピンの操作に失敗した
+    InvalidArea, // 画面の範囲外が指定された
+}
+
+// ILI9341をコマンド単位で操作するドライバです
+// DCがLowのときはコマンド、Highのときはパラメータやピクセルデータとして送られます
+pub struct Lcd<SPI, DC, CS, RST> {
+    spi: SPI,
+    dc: DC,
+    cs: CS,
+    rst: RST,
+}
+
+impl<SPI, DC, CS, RST, SpiE, PinE> Lcd<SPI, DC, CS, RST>
+where
+    SPI: Transfer<u8, Error = SpiE> + Write<u8, Error = SpiE>,
+    DC: OutputPin<Error = PinE>,
+    CS: OutputPin<Error = PinE>,
+    RST: OutputPin<Error = PinE>,
+{
+    pub fn new(
+        spi: SPI,
+        mut dc: DC,
+        mut cs: CS,
+        mut rst: RST,
+    ) -> Result<Lcd<SPI, DC, CS, RST>, Error<SpiE, PinE>> {
+        cs.set_high().map_err(Error::Pin)?;
+        dc.set_high().map_err(Error::Pin)?;
+        rst.set_high().map_err(Error::Pin)?;
+        Ok(Lcd { spi, dc, cs, rst })
+    }
+
+    // SPIドライバとピンを返して、ドライバを破棄します
+    pub fn release(self) -> (SPI, DC, CS, RST) {
+        (self.spi, self.dc, self.cs, self.rst)
+    }
+
+    // RSTピンでハードウェアリセットします
+    pub fn hard_reset<D: DelayMs<u8>>(
+        &mut self,
+        delay: &mut D,
+    ) -> Result<(), Error<SpiE, PinE>> {
+        self.rst.set_low().map_err(Error::Pin)?;
+        delay.delay_ms(1);
+        self.rst.set_high().map_err(Error::Pin)?;
+        // リセット解除後、コマンドを受け付けるまで120[ms]待つ
+        delay.delay_ms(120);
+        Ok(())
+    }
+
+    // リセットしてから、RGB565・指定した向きで表示を始めます
+    pub fn init<D: DelayMs<u8>>(
+        &mut self,
+        memory_access: MemoryAccess,
+        delay: &mut D,
+    ) -> Result<(), Error<SpiE, PinE>> {
+        self.hard_reset(delay)?;
+        self.command(Command::SoftwareReset, &[])?;
+        delay.delay_ms(5);
+        self.set_memory_access(memory_access)?;
+        self.set_pixel_format(PixelFormat::Bits16)?;
+        self.sleep_out(delay)?;
+        self.set_display_on(true)
+    }
+
+    pub fn read_display_id(&mut self) -> Result<DisplayId, Error<SpiE, PinE>> {
+        let mut data = [0u8; 3];
+        self.read(Command::ReadDisplayId, &mut data)?;
+        Ok(DisplayId {
+            manufacturer: data[0],
+            version: data[1],
+            module: data[2],
+        })
+    }
+
+    pub fn read_power_mode(&mut self) -> Result<PowerMode, Error<SpiE, PinE>> {
+        let mut data = [0u8];
+        self.read(Command::ReadPowerMode, &mut data)?;
+        Ok(PowerMode(data[0]))
+    }
+
+    pub fn read_memory_access(
+        &mut self,
+    ) -> Result<MemoryAccess, Error<SpiE, PinE>> {
+        let mut data = [0u8];
+        self.read(Command::ReadMadctl, &mut data)?;
+        Ok(MemoryAccess::from_bits(data[0]))
+    }
+
+    pub fn read_pixel_format(&mut self) -> Result<u8, Error<SpiE, PinE>> {
+        let mut data = [0u8];
+        self.read(Command::ReadPixelFormat, &mut data)?;
+        Ok(data[0])
+    }
+
+    pub fn set_memory_access(
+        &mut self,
+        memory_access: MemoryAccess,
+    ) -> Result<(), Error<SpiE, PinE>> {
+        self.command(Command::MemoryAccessControl, &[memory_access.bits()])
+    }
+
+    pub fn set_pixel_format(
+        &mut self,
+        format: PixelFormat,
+    ) -> Result<(), Error<SpiE, PinE>> {
+        self.command(Command::PixelFormatSet, &[format as u8])
+    }
+
+    pub fn set_gamma_curve(
+        &mut self,
+        curve: GammaCurve,
+    ) -> Result<(), Error<SpiE, PinE>> {
+        self.command(Command::GammaSet, &[curve as u8])
+    }
+
+    // ガンマ補正テーブルを直接書き込みます (正・負それぞれ15バイト)
+    pub fn set_gamma_correction(
+        &mut self,
+        positive: &[u8; 15],
+        negative: &[u8; 15],
+    ) -> Result<(), Error<SpiE, PinE>> {
+        self.command(Command::PositiveGammaCorrection, positive)?;
+        self.command(Command::NegativeGammaCorrection, negative)
+    }
+
+    // スリープを解除します。解除後は120[ms]待つ必要があります
+    pub fn sleep_out<D: DelayMs<u8>>(
+        &mut self,
+        delay: &mut D,
+    ) -> Result<(), Error<SpiE, PinE>> {
+        self.command(Command::SleepOut, &[])?;
+        delay.delay_ms(120);
+        Ok(())
+    }
+
+    pub fn sleep_in<D: DelayMs<u8>>(
+        &mut self,
+        delay: &mut D,
+    ) -> Result<(), Error<SpiE, PinE>> {
+        self.command(Command::SleepIn, &[])?;
+        delay.delay_ms(5);
+        Ok(())
+    }
+
+    pub fn set_display_on(
+        &mut self,
+        on: bool,
+    ) -> Result<(), Error<SpiE, PinE>> {
+        let command = if on {
+            Command::DisplayOn
+        } else {
+            Command::DisplayOff
+        };
+        self.command(command, &[])
+    }
+
+    pub fn set_inversion(&mut self, on: bool) -> Result<(), Error<SpiE, PinE>> {
+        let command = if on {
+            Command::InversionOn
+        } else {
+            Command::InversionOff
+        };
+        self.command(command, &[])
+    }
+
+    pub fn set_idle_mode(&mut self, on: bool) -> Result<(), Error<SpiE, PinE>> {
+        let command = if on {
+            Command::IdleModeOn
+        } else {
+            Command::IdleModeOff
+        };
+        self.command(command, &[])
+    }
+
+    // start_rowからend_rowまでの行だけを表示する部分表示モードにします
+    pub fn enter_partial_mode(
+        &mut self,
+        start_row: u16,
+        end_row: u16,
+    ) -> Result<(), Error<SpiE, PinE>> {
+        if start_row > end_row {
+            return Err(Error::InvalidArea);
+        }
+        let (s, e) = (start_row.to_be_bytes(), end_row.to_be_bytes());
+        self.command(Command::PartialArea, &[s[0], s[1], e[0], e[1]])?;
+        self.command(Command::PartialModeOn, &[])
+    }
+
+    pub fn enter_normal_mode(&mut self) -> Result<(), Error<SpiE, PinE>> {
+        self.command(Command::NormalModeOn, &[])
+    }
+
+    // 垂直スクロールの領域を設定します
+    // 3つの合計はフレームメモリの行数 (320) と等しくなければなりません
+    pub fn set_scroll_area(
+        &mut self,
+        top_fixed: u16,
+        scroll_height: u16,
+        bottom_fixed: u16,
+    ) -> Result<(), Error<SpiE, PinE>> {
+        let total = top_fixed
+            .checked_add(scroll_height)
+            .and_then(|height| height.checked_add(bottom_fixed));
+        if total != Some(WIDTH) {
+            return Err(Error::InvalidArea);
+        }
+        let (t, s, b) = (
+            top_fixed.to_be_bytes(),
+            scroll_height.to_be_bytes(),
+            bottom_fixed.to_be_bytes(),
+        );
+        self.command(
+            Command::VerticalScrollingDefinition,
+            &[t[0], t[1], s[0], s[1], b[0], b[1]],
+        )
+    }
+
+    // スクロール領域の先頭に表示するフレームメモリの行を設定します
+    pub fn set_scroll_start(
+        &mut self,
+        line: u16,
+    ) -> Result<(), Error<SpiE, PinE>> {
+        let l = line.to_be_bytes();
+        self.command(Command::VerticalScrollingStart, &l)
+    }
+
+    // 書き込み先の矩形領域を設定します (両端を含む)
+    pub fn set_window(
+        &mut self,
+        x0: u16,
+        y0: u16,
+        x1: u16,
+        y1: u16,
+    ) -> Result<(), Error<SpiE, PinE>> {
+        if x0 > x1 || y0 > y1 {
+            return Err(Error::InvalidArea);
+        }
+        let (a, b) = (x0.to_be_bytes(), x1.to_be_bytes());
+        self.command(Command::ColumnAddressSet, &[a[0], a[1], b[0], b[1]])?;
+        let (a, b) = (y0.to_be_bytes(), y1.to_be_bytes());
+        self.command(Command::PageAddressSet, &[a[0], a[1], b[0], b[1]])
+    }
+
+    // 矩形領域にRGB565のピクセルを書き込みます
+    pub fn write_pixels(
+        &mut self,
+        x0: u16,
+        y0: u16,
+        x1: u16,
+        y1: u16,
+        pixels: &[u16],
+    ) -> Result<(), Error<SpiE, PinE>> {
+        self.set_window(x0, y0, x1, y1)?;
+        self.start_memory_write()?;
+        // 送信に失敗してもCSは解放する
+        let result = self.write_pixel_data(pixels);
+        let end = self.end();
+        result.and(end)
+    }
+
+    fn write_pixel_data(
+        &mut self,
+        pixels: &[u16],
+    ) -> Result<(), Error<SpiE, PinE>> {
+        // ILI9341はビッグエンディアンで受け取る
+        let mut chunk = [0u8; 64];
+        for pixels in pixels.chunks(chunk.len() / 2) {
+            for (bytes, pixel) in chunk.chunks_mut(2).zip(pixels) {
+                bytes.copy_from_slice(&pixel.to_be_bytes());
+            }
+            self.spi
+                .write(&chunk[..pixels.len() * 2])
+                .map_err(Error::Spi)?;
+        }
+        Ok(())
+    }
+
+    // Memory Writeコマンドを送って、CSをLowのままにします
+    // 続くピクセルデータはDMAなどで送り、最後にend()を呼びます
+    pub fn start_memory_write(&mut self) -> Result<(), Error<SpiE, PinE>> {
+        self.cs.set_low().map_err(Error::Pin)?;
+        if let Err(error) = self.write_command(Command::MemoryWrite) {
+            self.end().ok();
+            return Err(error);
+        }
+        Ok(())
+    }
+
+    pub fn end(&mut self) -> Result<(), Error<SpiE, PinE>> {
+        self.cs.set_high().map_err(Error::Pin)
+    }
+
+    // コマンドとパラメータを送ります
+    pub fn command(
+        &mut self,
+        command: Command,
+        params: &[u8],
+    ) -> Result<(), Error<SpiE, PinE>> {
+        self.transaction(|lcd| {
+            lcd.write_command(command)?;
+            if !params.is_empty() {
+                lcd.spi.write(params).map_err(Error::Spi)?;
+            }
+            Ok(())
+        })
+    }
+
+    // 読み出しコマンドを送って、結果をbufferに受け取ります
+    // 最初の1バイトはダミーなので読み捨てます
+    pub fn read(
+        &mut self,
+        command: Command,
+        buffer: &mut [u8],
+    ) -> Result<(), Error<SpiE, PinE>> {
+        self.transaction(|lcd| {
+            lcd.write_command(command)?;
+            let mut dummy = [0u8];
+            lcd.spi.transfer(&mut dummy).map_err(Error::Spi)?;
+            for byte in buffer.iter_mut() {
+                *byte = 0;
+            }
+            lcd.spi.transfer(buffer).map_err(Error::Spi)?;
+            Ok(())
+        })
+    }
+
+    // CSをLowにしてfを実行し、失敗した場合もCSをHighに戻します
+    fn transaction<F>(&mut self, f: F) -> Result<(), Error<SpiE, PinE>>
+    where
+        F: FnOnce(&mut Self) -> Result<(), Error<SpiE, PinE>>,
+    {
+        self.cs.set_low().map_err(Error::Pin)?;
+        let result = f(self);
+        let end = self.end();
+        result.and(end)
+    }
+
+    // DCをLowにしてコマンドを送り、続くデータのためにHighに戻します
+    fn write_command(
+        &mut self,
+        command: Command,
+    ) -> Result<(), Error<SpiE, PinE>> {
+        self.dc.set_low().map_err(Error::Pin)?;
+        self.spi.write(&[command as u8]).map_err(Error::Spi)?;
+        self.dc.set_high().map_err(Error::Pin)
+    }
+}
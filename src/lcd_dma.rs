@@ -0,0 +1,181 @@
+use crate::dmac::{BeatSize, Channel, Descriptor, Trigger};
+use crate::lcd::{Error, Lcd};
+use core::ptr::addr_of_mut;
+use embedded_hal::blocking::spi::{Transfer, Write};
+use embedded_hal::digital::v2::OutputPin;
+use wio::pac::SERCOM7;
+use wio_terminal as wio;
+
+// 1つのディスクリプタで転送できる最大のバイト数
+const MAX_BLOCK_BYTES: usize = 0xFFFF;
+// 1回の転送でつなげるディスクリプタの数。320x240の全画面 (153600バイト) を送れる
+const MAX_BLOCKS: usize = 4;
+
+// 2つ目以降のブロックの転送ディスクリプタ (1つ目はチャネルのベースディスクリプタを使う)
+static mut LINKED_DESCRIPTORS: [Descriptor; MAX_BLOCKS - 1] =
+    [Descriptor::new(); MAX_BLOCKS - 1];
+
+// RAM上のピクセルデータを、DMAでSERCOM7 (LCDのSPI) に転送するサービスです
+// 転送はバックグラウンドで進み、完了はDMAの割り込みハンドラでon_interrupt()を
+// 呼び出して検出します
+// ILI9341はビッグエンディアンでピクセルを受け取るので、バッファにはto_lcd_order()で
+// 上位と下位のバイトを入れ替えた値を格納しておきます
+// 転送中のバッファはLcdDmaが預かり、転送が終わった後で呼び出し元に返します
+pub struct LcdDma<SPI, DC, CS, RST> {
+    lcd: Lcd<SPI, DC, CS, RST>,
+    channel: Channel,
+    busy: bool,
+    // 転送中または転送済みで、まだ返していないバッファ
+    buffer: Option<&'static mut [u16]>,
+}
+
+// 転送を開始できなかったときは、エラーと一緒にバッファを返します
+pub type BlitError<E> = (nb::Error<E>, &'static mut [u16]);
+
+// RGB565の値を、DMAでそのまま送れるバイト順に変換します
+pub fn to_lcd_order(pixel: u16) -> u16 {
+    pixel.to_be()
+}
+
+impl<SPI, DC, CS, RST, SpiE, PinE> LcdDma<SPI, DC, CS, RST>
+where
+    SPI: Transfer<u8, Error = SpiE> + Write<u8, Error = SpiE>,
+    DC: OutputPin<Error = PinE>,
+    CS: OutputPin<Error = PinE>,
+    RST: OutputPin<Error = PinE>,
+{
+    // lcdは初期化済みで、SERCOM7のSPIを使っている必要があります
+    pub fn new(
+        lcd: Lcd<SPI, DC, CS, RST>,
+        mut channel: Channel,
+    ) -> LcdDma<SPI, DC, CS, RST> {
+        channel.configure(Trigger::Sercom7Tx, 1);
+        LcdDma {
+            lcd,
+            channel,
+            busy: false,
+            buffer: None,
+        }
+    }
+
+    pub fn release(self) -> (Lcd<SPI, DC, CS, RST>, Channel) {
+        (self.lcd, self.channel)
+    }
+
+    // 転送中でなければ、コマンドを送るためにLCDドライバを借ります
+    pub fn lcd(&mut self) -> Option<&mut Lcd<SPI, DC, CS, RST>> {
+        if self.is_busy() {
+            None
+        } else {
+            Some(&mut self.lcd)
+        }
+    }
+
+    pub fn is_busy(&self) -> bool {
+        self.busy
+    }
+
+    // 転送が終わっていれば、転送したバッファを返します
+    pub fn take_buffer(&mut self) -> Option<&'static mut [u16]> {
+        if self.is_busy() {
+            None
+        } else {
+            self.buffer.take()
+        }
+    }
+
+    // 矩形領域 (両端を含む) へのピクセルの転送を開始します
+    // pixelsは転送が終わるまで預かり、前回転送したバッファがあればそれを返します
+    pub fn start_blit(
+        &mut self,
+        x0: u16,
+        y0: u16,
+        x1: u16,
+        y1: u16,
+        pixels: &'static mut [u16],
+    ) -> Result<Option<&'static mut [u16]>, BlitError<Error<SpiE, PinE>>> {
+        if self.is_busy() {
+            return Err((nb::Error::WouldBlock, pixels));
+        }
+        let area = (x1 as usize + 1).saturating_sub(x0 as usize)
+            * (y1 as usize + 1).saturating_sub(y0 as usize);
+        let bytes = area * 2;
+        if area == 0
+            || pixels.len() < area
+            || bytes > MAX_BLOCK_BYTES * MAX_BLOCKS
+        {
+            return Err((nb::Error::Other(Error::InvalidArea), pixels));
+        }
+        if let Err(e) = self
+            .lcd
+            .set_window(x0, y0, x1, y1)
+            .and_then(|_| self.lcd.start_memory_write())
+        {
+            return Err((nb::Error::Other(e), pixels));
+        }
+
+        // 65535バイトごとにディスクリプタを分けてつなげる
+        let spi = unsafe { &*SERCOM7::ptr() }.spim();
+        let data = &spi.data as *const _ as *mut u8;
+        let src = pixels.as_ptr() as *const u8;
+        let blocks = bytes.div_ceil(MAX_BLOCK_BYTES);
+        let mut first = Some(self.channel.descriptor());
+        let mut previous: Option<&mut Descriptor> = None;
+        for block in 0..blocks {
+            let offset = block * MAX_BLOCK_BYTES;
+            let length = (bytes - offset).min(MAX_BLOCK_BYTES);
+            let descriptor = if let Some(first) = first.take() {
+                first
+            } else {
+                unsafe { &mut (*addr_of_mut!(LINKED_DESCRIPTORS))[block - 1] }
+            };
+            descriptor.set(
+                BeatSize::Byte,
+                length as u16,
+                unsafe { src.add(offset) },
+                true,
+                data,
+                false,
+            );
+            if let Some(previous) = previous {
+                previous.link(descriptor);
+            }
+            previous = Some(descriptor);
+        }
+        self.busy = true;
+        self.channel.enable();
+        Ok(self.buffer.replace(pixels))
+    }
+
+    // DMAの割り込みハンドラから呼び出します。転送がすべて終わったときにtrueを返します
+    // 割り込みを使わない場合は、is_busy()がfalseになるまで繰り返し呼び出します
+    pub fn on_interrupt(&mut self) -> bool {
+        if !self.channel.take_complete() || !self.busy {
+            return false;
+        }
+        // ブロックごとに割り込みが発生するので、最後のブロックが終わって
+        // チャネルが無効になるまでは転送中とみなす
+        if self.channel.is_enabled() {
+            return false;
+        }
+        let spi = unsafe { &*SERCOM7::ptr() }.spim();
+        // 最後のバイトがシフトアウトされるまで待ってからCSを上げる
+        while spi.intflag.read().txc().bit_is_clear() {}
+        // 送信中に受信したデータは読み捨てて、オーバーフローをクリアしておく
+        while spi.intflag.read().rxc().bit_is_set() {
+            spi.data.read();
+        }
+        spi.status.modify(|_, w| w.bufovf().set_bit());
+        self.lcd.end().ok();
+        self.busy = false;
+        true
+    }
+
+    // 転送が終わるまで待ち、転送したバッファを返します (割り込みを使わない場合)
+    pub fn wait(&mut self) -> Option<&'static mut [u16]> {
+        while self.is_busy() {
+            self.on_interrupt();
+        }
+        self.buffer.take()
+    }
+}
@@ -1,15 +1,54 @@
 #![no_std]
 #![allow(dead_code)] // 使用しないメソッドでコンパイラが警告を出さないようにします
 
+// Wio Terminalのハードウェアに依存するモジュールは、ARM向けのビルドでだけ有効にします
+// それ以外はホストでもビルドできるので、シミュレータから使えます
+#[cfg(target_arch = "arm")]
 use wio::hal::gpio::*; // GPIOの構造体やトレイトをインポートします
+#[cfg(target_arch = "arm")]
 use wio::prelude::*;
+#[cfg(target_arch = "arm")]
 use wio_terminal as wio;
 
+#[cfg(target_arch = "arm")]
+pub mod adc_dma; // DMAを使ったADCのサンプリング
+#[cfg(target_arch = "arm")]
+pub mod dmac; // DMAコントローラ
+pub mod font; // プロポーショナルフォント
+pub mod framebuffer; // 描画した部分だけを転送するフレームバッファ
+pub mod grove; // Groveモジュールのドライバ
+pub mod i2c_bus; // I2Cバスのスキャンと共有
+pub mod image; // RGB565の画像アセット
+pub mod input; // ボタンの入力イベント
+pub mod laps; // ストップウォッチのラップタイム
+pub mod launcher; // アプリを選んで実行するランチャー
+pub mod lcd; // ILI9341のコマンド
+#[cfg(target_arch = "arm")]
+pub mod lcd_dma; // DMAによるLCDへの転送
+pub mod lis3dh; // LIS3DH加速度センサ
+#[cfg(feature = "app")]
+mod math; // f32の数学関数
+#[cfg(feature = "app")]
+pub mod motion; // 傾きの推定と歩数計
+#[cfg(target_arch = "arm")]
+pub mod nvm; // 内蔵フラッシュへの保存
+pub mod plot; // 掃引する折れ線グラフ
+pub mod rotation; // 傾きによる画面の自動回転
+pub mod screens; // サンプルの画面描画
+#[cfg(feature = "app")]
+pub mod spectrum; // FFTによるスペクトラム解析
+pub mod splash; // スプラッシュ画面
+pub mod sprite; // スプライトシート
+pub mod stopwatch; // ストップウォッチのモデル
+pub mod ui; // ウィジェットツールキット
+
 // Wio TerminalのユーザーLEDドライバです
+#[cfg(target_arch = "arm")]
 pub struct Led {
     pin: Pa15<Output<PushPull>>,
 }
 
+#[cfg(target_arch = "arm")]
 impl Led {
     // デフォルトモードのPA15ピンを、出力モードに移行します
     pub fn new(pin: Pa15<Input<Floating>>, port: &mut Port) -> Led {
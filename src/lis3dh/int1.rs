@@ -0,0 +1,37 @@
+use wio::pac::{EIC, MCLK, PORT};
+use wio_terminal as wio;
+
+// Wio TerminalのLIS3DHのINT1はPC21 (EXTINT5) に接続されています
+// 割り込みハンドラは EIC_EXTINT_5 です
+pub const INT1_EXTINT: u8 = 5;
+const INT1_PIN: usize = 21;
+
+// PC21をEICのファンクション (A) に切り替えて、入力を有効にします
+// PORTはPins構造体に渡してしまうので、Pins::new() の前に呼び出します
+pub fn configure_int1_pin(port: &PORT) {
+    // PMUXの値0がファンクションA
+    port.group2.pmux[INT1_PIN / 2].modify(|_, w| unsafe { w.pmuxo().bits(0) });
+    port.group2.pincfg[INT1_PIN]
+        .modify(|_, w| w.pmuxen().set_bit().inen().set_bit());
+}
+
+// 32.768[kHz]の低消費電力クロックでEICを動かし、EXTINT5の立ち上がりエッジを検出します
+pub fn configure_eic(eic: &EIC, mclk: &mut MCLK) {
+    mclk.apbamask.modify(|_, w| w.eic_().set_bit());
+
+    eic.ctrla.modify(|_, w| w.enable().clear_bit());
+    while eic.syncbusy.read().enable().bit_is_set() {}
+    eic.ctrla.modify(|_, w| w.cksel().set_bit());
+    eic.config[0].modify(|_, w| w.sense5().rise().filten5().set_bit());
+    eic.intenset
+        .write(|w| unsafe { w.extint().bits(1 << INT1_EXTINT) });
+    eic.ctrla.modify(|_, w| w.enable().set_bit());
+    while eic.syncbusy.read().enable().bit_is_set() {}
+}
+
+// EXTINT5の割り込みフラグをクリアします。割り込みハンドラから呼び出します
+pub fn clear_int1() {
+    let eic = unsafe { &*EIC::ptr() };
+    eic.intflag
+        .write(|w| unsafe { w.extint().bits(1 << INT1_EXTINT) });
+}
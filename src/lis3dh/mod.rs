@@ -0,0 +1,817 @@
+use accelerometer::vector::{F32x3, I16x3};
+use accelerometer::{Accelerometer, ErrorKind, RawAccelerometer};
+use core::fmt::Debug;
+use embedded_hal::blocking::i2c::{Write, WriteRead};
+use heapless::consts::*;
+use heapless::Vec;
+
+#[cfg(target_arch = "arm")]
+pub mod int1; // Wio TerminalのINT1ピンの外部割り込み
+pub mod register; // レジスタマップ
+
+use self::register::*;
+
+// Wio TerminalのLIS3DHはSA0がGNDに接続されているので、スレーブアドレスは0x18です
+pub const ADDRESS: u8 = 0x18;
+// WHO_AM_Iレジスタに格納されているデバイスID
+pub const DEVICE_ID: u8 = 0x33;
+
+// フルスケール (CTRL_REG4 FS)
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Range {
+    G2 = 0b00,
+    G4 = 0b01,
+    G8 = 0b10,
+    G16 = 0b11,
+}
+
+impl Range {
+    // 割り込み・クリック検出のしきい値レジスタ1LSBあたりの加速度 [mg]
+    fn threshold_lsb(self) -> u16 {
+        match self {
+            Range::G2 => 16,
+            Range::G4 => 32,
+            Range::G8 => 62,
+            Range::G16 => 186,
+        }
+    }
+}
+
+// 出力データレート (CTRL_REG1 ODR)
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum DataRate {
+    PowerDown = 0b0000,
+    Hz1 = 0b0001,
+    Hz10 = 0b0010,
+    Hz25 = 0b0011,
+    Hz50 = 0b0100,
+    Hz100 = 0b0101,
+    Hz200 = 0b0110,
+    Hz400 = 0b0111,
+    Hz1600LowPower = 0b1000, // 低消費電力モード専用
+    Hz1344 = 0b1001,         // 低消費電力モードでは5376[Hz]になる
+}
+
+impl DataRate {
+    // 動作モードを考慮したサンプリング周波数 [Hz]
+    pub fn hz(self, mode: Mode) -> f32 {
+        match self {
+            DataRate::PowerDown => 0.0,
+            DataRate::Hz1 => 1.0,
+            DataRate::Hz10 => 10.0,
+            DataRate::Hz25 => 25.0,
+            DataRate::Hz50 => 50.0,
+            DataRate::Hz100 => 100.0,
+            DataRate::Hz200 => 200.0,
+            DataRate::Hz400 => 400.0,
+            DataRate::Hz1600LowPower => 1600.0,
+            DataRate::Hz1344 if mode == Mode::LowPower => 5376.0,
+            DataRate::Hz1344 => 1344.0,
+        }
+    }
+}
+
+// 動作モード。分解能と消費電力のトレードオフになります
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Mode {
+    LowPower,       // 8ビット
+    Normal,         // 10ビット
+    HighResolution, // 12ビット
+}
+
+impl Mode {
+    fn bits(self) -> u8 {
+        match self {
+            Mode::LowPower => 8,
+            Mode::Normal => 10,
+            Mode::HighResolution => 12,
+        }
+    }
+
+    // 1デジットあたりの加速度 [mg] (データシート Table 4)
+    fn sensitivity(self, range: Range) -> f32 {
+        let high_resolution = match range {
+            Range::G2 => 1.0,
+            Range::G4 => 2.0,
+            Range::G8 => 4.0,
+            Range::G16 => 12.0,
+        };
+        match self {
+            Mode::LowPower => high_resolution * 16.0,
+            Mode::Normal => high_resolution * 4.0,
+            Mode::HighResolution => high_resolution,
+        }
+    }
+}
+
+// ハイパスフィルタのモード (CTRL_REG2 HPM)
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum HighPassMode {
+    NormalWithReset = 0b00, // REFERENCEレジスタの読み出しでリセットする
+    Reference = 0b01,       // REFERENCEレジスタの値を基準にする
+    Normal = 0b10,
+    AutoReset = 0b11, // 割り込みイベントでリセットする
+}
+
+// ハイパスフィルタの設定
+// カットオフ周波数はデータレートに依存し、cutoffが大きいほど低くなります (CTRL_REG2 HPCF)
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct HighPassFilter {
+    pub mode: HighPassMode,
+    pub cutoff: u8,
+}
+
+// 加速度センサの設定
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Config {
+    pub range: Range,
+    pub data_rate: DataRate,
+    pub mode: Mode,
+    // Noneの場合はハイパスフィルタを通さない値を出力します
+    pub high_pass: Option<HighPassFilter>,
+}
+
+impl Default for Config {
+    fn default() -> Config {
+        Config {
+            range: Range::G2,
+            data_rate: DataRate::Hz100,
+            mode: Mode::HighResolution,
+            high_pass: None,
+        }
+    }
+}
+
+impl Config {
+    fn ctrl_reg1(&self) -> CtrlReg1 {
+        // X/Y/Z軸すべてを有効にする
+        let mut reg = CtrlReg1::default();
+        reg.set_odr(self.data_rate as u8)
+            .set_lpen(self.mode == Mode::LowPower);
+        reg
+    }
+
+    fn ctrl_reg2(&self) -> CtrlReg2 {
+        let mut reg = CtrlReg2::default();
+        if let Some(filter) = self.high_pass {
+            // FDSを立てると、出力レジスタにフィルタ後の値が格納される
+            reg.set_hpm(filter.mode as u8)
+                .set_hpcf(filter.cutoff)
+                .set_fds(true);
+        }
+        reg
+    }
+
+    fn ctrl_reg4(&self) -> CtrlReg4 {
+        // BDUを立てて、上位と下位のバイトを読み出し終わるまで出力レジスタを更新させない
+        let mut reg = CtrlReg4::default();
+        reg.set_bdu(true)
+            .set_fs(self.range as u8)
+            .set_hr(self.mode == Mode::HighResolution);
+        reg
+    }
+}
+
+// タップ (クリック) 検出の設定
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct TapConfig {
+    pub threshold_mg: u16,  // タップとみなす加速度
+    pub time_limit_ms: u16, // しきい値を超えてから戻るまでの最大時間
+    pub latency_ms: u16,    // 1回目のタップ後、2回目の検出を始めるまでの時間
+    pub window_ms: u16,     // 2回目のタップを待つ時間
+    pub double_tap: bool,   // ダブルタップも検出する
+}
+
+impl Default for TapConfig {
+    fn default() -> TapConfig {
+        TapConfig {
+            threshold_mg: 1200,
+            time_limit_ms: 50,
+            latency_ms: 100,
+            window_ms: 300,
+            double_tap: true,
+        }
+    }
+}
+
+// 自由落下検出の設定
+// すべての軸の加速度がしきい値を下回った状態が一定時間続くと、自由落下とみなします
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct FreeFallConfig {
+    pub threshold_mg: u16,
+    pub duration_ms: u16,
+}
+
+impl Default for FreeFallConfig {
+    fn default() -> FreeFallConfig {
+        FreeFallConfig {
+            threshold_mg: 350,
+            duration_ms: 30,
+        }
+    }
+}
+
+// INT1ピンに通知する検出エンジンの設定
+// Noneを指定したエンジンは無効になります
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct GestureConfig {
+    pub tap: Option<TapConfig>,
+    pub free_fall: Option<FreeFallConfig>,
+    pub orientation: bool, // 6D向き検出
+}
+
+// 本体の向き
+// Wio Terminalを画面が正面に見える横向きで持ったときをLandscapeとします
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Orientation {
+    Landscape,
+    LandscapeFlipped,
+    Portrait,
+    PortraitFlipped,
+    FaceUp,
+    FaceDown,
+}
+
+impl Orientation {
+    // 6D検出のINT_SRCレジスタの値から向きを求めます
+    fn from_6d_source(source: Int2Src) -> Option<Orientation> {
+        match source.0 & 0x3F {
+            0b00_0001 => Some(Orientation::Landscape),
+            0b00_0010 => Some(Orientation::LandscapeFlipped),
+            0b00_0100 => Some(Orientation::Portrait),
+            0b00_1000 => Some(Orientation::PortraitFlipped),
+            0b01_0000 => Some(Orientation::FaceDown),
+            0b10_0000 => Some(Orientation::FaceUp),
+            _ => None,
+        }
+    }
+}
+
+// アプリケーションに通知するイベント
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Event {
+    Tap,
+    DoubleTap,
+    FreeFall,
+    Orientation(Orientation),
+}
+
+// 1回の読み出しで発生しうるイベントの集合
+pub type Events = Vec<Event, U4>;
+
+// FIFOに格納できるサンプル数
+pub const FIFO_DEPTH: usize = 32;
+
+// FIFOの動作モード (FIFO_CTRL_REG FM)
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum FifoMode {
+    Bypass = 0b00,       // FIFOを使わない
+    Fifo = 0b01,         // いっぱいになったら格納を止める
+    Stream = 0b10,       // いっぱいになったら古いサンプルから上書きする
+    StreamToFifo = 0b11, // トリガまでStream、トリガ後はFIFOモードで動く
+}
+
+// FIFOの状態 (FIFO_SRC_REG)
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct FifoStatus {
+    pub watermark: bool, // ウォーターマーク以上のサンプルが溜まっている
+    pub overrun: bool,   // いっぱいになり、サンプルが失われた
+    pub len: usize,      // 格納されているサンプル数
+}
+
+impl FifoStatus {
+    fn from_register(reg: FifoSrcReg) -> FifoStatus {
+        let overrun = reg.ovrn_fifo();
+        let len = if reg.empty() {
+            0
+        } else if overrun {
+            // オーバーラン時のFSSは31になるが、FIFOには32サンプル格納されている
+            FIFO_DEPTH
+        } else {
+            reg.fss() as usize
+        };
+        FifoStatus {
+            watermark: reg.wtm(),
+            overrun,
+            len,
+        }
+    }
+}
+
+// タイムスタンプ付きのサンプル
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Sample {
+    pub timestamp_us: u32,
+    pub accel: F32x3,
+}
+
+// FIFOから一度に読み出したサンプル列
+// 最後のサンプルを読み出し時刻とみなし、データレートから各サンプルの時刻を逆算します
+pub struct FifoBatch {
+    data: [u8; 6 * FIFO_DEPTH],
+    len: usize,
+    shift: u8,
+    scale: f32,
+    last_timestamp_us: u32,
+    period_us: u32,
+}
+
+impl FifoBatch {
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    // 動作モードの分解能に合わせて右詰めにした値
+    pub fn raw(&self, index: usize) -> I16x3 {
+        let d = &self.data[index * 6..index * 6 + 6];
+        let value = |l: u8, h: u8| i16::from_le_bytes([l, h]) >> self.shift;
+        I16x3::new(value(d[0], d[1]), value(d[2], d[3]), value(d[4], d[5]))
+    }
+
+    pub fn sample(&self, index: usize) -> Sample {
+        let raw = self.raw(index);
+        let age = (self.len - 1 - index) as u32;
+        Sample {
+            timestamp_us: self
+                .last_timestamp_us
+                .wrapping_sub(age * self.period_us),
+            accel: F32x3::new(
+                raw.x as f32 * self.scale,
+                raw.y as f32 * self.scale,
+                raw.z as f32 * self.scale,
+            ),
+        }
+    }
+
+    // 古いサンプルから順に取り出すイテレータ
+    pub fn iter(&self) -> FifoSamples<'_> {
+        FifoSamples {
+            batch: self,
+            index: 0,
+        }
+    }
+}
+
+pub struct FifoSamples<'a> {
+    batch: &'a FifoBatch,
+    index: usize,
+}
+
+impl<'a> Iterator for FifoSamples<'a> {
+    type Item = Sample;
+
+    fn next(&mut self) -> Option<Sample> {
+        if self.index >= self.batch.len {
+            return None;
+        }
+        let sample = self.batch.sample(self.index);
+        self.index += 1;
+        Some(sample)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.batch.len - self.index;
+        (remaining, Some(remaining))
+    }
+}
+
+impl<'a> IntoIterator for &'a FifoBatch {
+    type Item = Sample;
+    type IntoIter = FifoSamples<'a>;
+
+    fn into_iter(self) -> FifoSamples<'a> {
+        self.iter()
+    }
+}
+
+#[derive(Debug)]
+pub enum Error<E> {
+    I2c(E),            // I2Cバスのエラー
+    InvalidDevice(u8), // WHO_AM_Iが期待した値ではなかった
+    InvalidConfig,     // 組み合わせられない設定が指定された
+}
+
+impl<E> From<E> for Error<E> {
+    fn from(error: E) -> Error<E> {
+        Error::I2c(error)
+    }
+}
+
+// LIS3DH加速度センサのドライバです
+pub struct Lis3dh<I2C> {
+    i2c: I2C,
+    address: u8,
+    config: Config,
+}
+
+impl<I2C, E> Lis3dh<I2C>
+where
+    I2C: Write<Error = E> + WriteRead<Error = E>,
+{
+    // デバイスIDを確認して、デフォルトの設定で初期化します
+    pub fn new(i2c: I2C, address: u8) -> Result<Lis3dh<I2C>, Error<E>> {
+        let mut lis3dh = Lis3dh {
+            i2c,
+            address,
+            config: Config::default(),
+        };
+        let id = lis3dh.device_id()?;
+        if id != DEVICE_ID {
+            return Err(Error::InvalidDevice(id));
+        }
+        lis3dh.configure(Config::default())?;
+        Ok(lis3dh)
+    }
+
+    // I2Cドライバを返して、ドライバを破棄します
+    pub fn release(self) -> I2C {
+        self.i2c
+    }
+
+    pub fn device_id(&mut self) -> Result<u8, Error<E>> {
+        self.read::<WhoAmI>().map(|reg| reg.id())
+    }
+
+    pub fn config(&self) -> Config {
+        self.config
+    }
+
+    // 設定をまとめて書き込みます
+    pub fn configure(&mut self, config: Config) -> Result<(), Error<E>> {
+        if config.data_rate == DataRate::Hz1600LowPower
+            && config.mode != Mode::LowPower
+        {
+            return Err(Error::InvalidConfig);
+        }
+        self.write(config.ctrl_reg1())?;
+        self.write(config.ctrl_reg2())?;
+        self.write(config.ctrl_reg4())?;
+        if let Some(HighPassFilter {
+            mode: HighPassMode::NormalWithReset,
+            ..
+        }) = config.high_pass
+        {
+            // REFERENCEを読み出してフィルタの状態をリセットする
+            self.read::<Reference>()?;
+        }
+        self.config = config;
+        Ok(())
+    }
+
+    pub fn set_range(&mut self, range: Range) -> Result<(), Error<E>> {
+        self.configure(Config {
+            range,
+            ..self.config
+        })
+    }
+
+    pub fn set_data_rate(
+        &mut self,
+        data_rate: DataRate,
+    ) -> Result<(), Error<E>> {
+        self.configure(Config {
+            data_rate,
+            ..self.config
+        })
+    }
+
+    pub fn set_mode(&mut self, mode: Mode) -> Result<(), Error<E>> {
+        self.configure(Config {
+            mode,
+            ..self.config
+        })
+    }
+
+    pub fn set_high_pass_filter(
+        &mut self,
+        high_pass: Option<HighPassFilter>,
+    ) -> Result<(), Error<E>> {
+        self.configure(Config {
+            high_pass,
+            ..self.config
+        })
+    }
+
+    // 出力レジスタの値を、動作モードの分解能に合わせて右詰めにして読み出します
+    pub fn read_raw(&mut self) -> Result<I16x3, Error<E>> {
+        let mut data = [0u8; 6];
+        self.i2c.write_read(
+            self.address,
+            &[OUT_X_L | AUTO_INCREMENT],
+            &mut data,
+        )?;
+        let shift = 16 - self.config.mode.bits();
+        let value = |l: u8, h: u8| i16::from_le_bytes([l, h]) >> shift;
+        Ok(I16x3::new(
+            value(data[0], data[1]),
+            value(data[2], data[3]),
+            value(data[4], data[5]),
+        ))
+    }
+
+    // 加速度を[g]単位で読み出します
+    pub fn read_g(&mut self) -> Result<F32x3, Error<E>> {
+        let raw = self.read_raw()?;
+        let scale = self.config.mode.sensitivity(self.config.range) / 1000.0;
+        Ok(F32x3::new(
+            raw.x as f32 * scale,
+            raw.y as f32 * scale,
+            raw.z as f32 * scale,
+        ))
+    }
+
+    // 新しいサンプルが揃っているかどうかなどの状態を読み出します
+    pub fn status(&mut self) -> Result<StatusReg, Error<E>> {
+        self.read()
+    }
+
+    // 補助ADCを有効にします
+    // temperatureをtrueにすると、ADC3に温度センサの値 (相対値) が入ります
+    pub fn enable_adc(&mut self, temperature: bool) -> Result<(), Error<E>> {
+        // ADCの出力もBDUで保護する必要がある
+        self.modify(|reg: &mut CtrlReg4| {
+            reg.set_bdu(true);
+        })?;
+        let mut reg = TempCfgReg::default();
+        reg.set_adc_en(true).set_temp_en(temperature);
+        self.write(reg)
+    }
+
+    // 補助ADCのチャネル (1〜3) の値を左詰めの16ビットで読み出します
+    pub fn read_adc(&mut self, channel: u8) -> Result<i16, Error<E>> {
+        if !(1..=3).contains(&channel) {
+            return Err(Error::InvalidConfig);
+        }
+        let mut data = [0u8; 2];
+        let address = OUT_ADC1_L + (channel - 1) * 2;
+        self.i2c.write_read(
+            self.address,
+            &[address | AUTO_INCREMENT],
+            &mut data,
+        )?;
+        Ok(i16::from_le_bytes(data))
+    }
+
+    // 内部メモリの校正値を再読み込みします
+    pub fn reboot(&mut self) -> Result<(), Error<E>> {
+        self.modify(|reg: &mut CtrlReg5| {
+            reg.set_boot(true);
+        })
+    }
+
+    // タップ・自由落下・向き検出を設定して、INT1ピンに通知するようにします
+    // しきい値や時間は現在のフルスケールとデータレートで換算するので、
+    // それらを変更した場合は再度呼び出す必要があります
+    pub fn enable_gestures(
+        &mut self,
+        gestures: &GestureConfig,
+    ) -> Result<(), Error<E>> {
+        let hz = self.config.data_rate.hz(self.config.mode);
+        if hz == 0.0 {
+            return Err(Error::InvalidConfig);
+        }
+        let lsb = self.config.range.threshold_lsb();
+        let threshold = |mg: u16| ((mg + lsb / 2) / lsb).min(0x7F) as u8;
+        let ticks = |ms: u16, max: u8| {
+            let ticks = ms as f32 * hz / 1000.0 + 0.5;
+            if ticks >= max as f32 {
+                max
+            } else {
+                ticks as u8
+            }
+        };
+        // FIFOの割り込み設定 (I1_WTM, I1_OVERRUN) などは残しておく
+        let mut ctrl_reg3: CtrlReg3 = self.read()?;
+        ctrl_reg3
+            .set_i1_click(gestures.tap.is_some())
+            .set_i1_ia1(gestures.free_fall.is_some())
+            .set_i1_ia2(gestures.orientation);
+        let mut ctrl_reg5: CtrlReg5 = self.read()?;
+        ctrl_reg5
+            .set_lir_int1(gestures.free_fall.is_some())
+            .set_d4d_int1(false)
+            .set_lir_int2(gestures.orientation)
+            .set_d4d_int2(false);
+
+        let mut click_cfg = ClickCfg::default();
+        if let Some(tap) = gestures.tap {
+            // 全軸のシングルクリック (とダブルクリック) を検出する
+            click_cfg.set_xs(true).set_ys(true).set_zs(true);
+            if tap.double_tap {
+                click_cfg.set_xd(true).set_yd(true).set_zd(true);
+            }
+            // LIR_Clickを立てて、CLICK_SRCを読み出すまで割り込みを保持する
+            let mut click_ths = ClickThs::default();
+            click_ths
+                .set_lir_click(true)
+                .set_ths(threshold(tap.threshold_mg));
+            self.write(click_ths)?;
+            let mut time_limit = TimeLimit::default();
+            time_limit.set_tli(ticks(tap.time_limit_ms, 0x7F));
+            self.write(time_limit)?;
+            let mut time_latency = TimeLatency::default();
+            time_latency.set_tla(ticks(tap.latency_ms, 0xFF));
+            self.write(time_latency)?;
+            let mut time_window = TimeWindow::default();
+            time_window.set_tw(ticks(tap.window_ms, 0xFF));
+            self.write(time_window)?;
+        }
+        self.write(click_cfg)?;
+
+        let mut int1_cfg = Int1Cfg::default();
+        if let Some(free_fall) = gestures.free_fall {
+            // 割り込み1: X/Y/Z軸すべてが低加速度 (AND条件)
+            let mut int1_ths = Int1Ths::default();
+            int1_ths.set_ths(threshold(free_fall.threshold_mg));
+            self.write(int1_ths)?;
+            let mut int1_duration = Int1Duration::default();
+            int1_duration.set_duration(ticks(free_fall.duration_ms, 0x7F));
+            self.write(int1_duration)?;
+            int1_cfg
+                .set_aoi(true)
+                .set_xlie(true)
+                .set_ylie(true)
+                .set_zlie(true);
+        }
+        self.write(int1_cfg)?;
+
+        // 全ビットを立てると6D位置検出になる
+        let int2_cfg = if gestures.orientation {
+            // 割り込み2: 6D位置検出。約0.5[g]以上で軸の向きを判定する
+            let mut int2_ths = Int2Ths::default();
+            int2_ths.set_ths(threshold(500));
+            self.write(int2_ths)?;
+            let mut int2_duration = Int2Duration::default();
+            int2_duration.set_duration(ticks(100, 0x7F));
+            self.write(int2_duration)?;
+            Int2Cfg(0xFF)
+        } else {
+            Int2Cfg::default()
+        };
+        self.write(int2_cfg)?;
+
+        self.write(ctrl_reg5)?;
+        self.write(ctrl_reg3)?;
+        // 設定前に保持されていた割り込みを解除しておく
+        self.read_events().map(|_| ())
+    }
+
+    // 各検出エンジンの状態を読み出してイベントに変換します
+    // 読み出すと保持されていた割り込みが解除され、INT1ピンがLowに戻ります
+    pub fn read_events(&mut self) -> Result<Events, Error<E>> {
+        let mut events = Events::new();
+        let click: ClickSrc = self.read()?;
+        if click.ia() {
+            let event = if click.dclick() {
+                Event::DoubleTap
+            } else {
+                Event::Tap
+            };
+            events.push(event).ok();
+        }
+        if self.read::<Int1Src>()?.ia() {
+            events.push(Event::FreeFall).ok();
+        }
+        let int2: Int2Src = self.read()?;
+        if int2.ia() {
+            if let Some(orientation) = Orientation::from_6d_source(int2) {
+                events.push(Event::Orientation(orientation)).ok();
+            }
+        }
+        Ok(events)
+    }
+
+    // FIFOを有効にします
+    // watermark個以上のサンプルが溜まると、INT1ピンで通知します
+    pub fn enable_fifo(
+        &mut self,
+        mode: FifoMode,
+        watermark: u8,
+    ) -> Result<(), Error<E>> {
+        if watermark as usize >= FIFO_DEPTH {
+            return Err(Error::InvalidConfig);
+        }
+        // モードを切り替える前にBypassにして、FIFOの中身を捨てる
+        self.write(FifoCtrlReg::default())?;
+        self.modify(|reg: &mut CtrlReg5| {
+            reg.set_fifo_en(true);
+        })?;
+        let mut fifo_ctrl = FifoCtrlReg::default();
+        fifo_ctrl.set_fm(mode as u8).set_fth(watermark);
+        self.write(fifo_ctrl)?;
+        self.modify(|reg: &mut CtrlReg3| {
+            reg.set_i1_wtm(true);
+        })
+    }
+
+    pub fn disable_fifo(&mut self) -> Result<(), Error<E>> {
+        self.modify(|reg: &mut CtrlReg3| {
+            reg.set_i1_wtm(false);
+        })?;
+        self.write(FifoCtrlReg::default())?;
+        self.modify(|reg: &mut CtrlReg5| {
+            reg.set_fifo_en(false);
+        })
+    }
+
+    pub fn fifo_status(&mut self) -> Result<FifoStatus, Error<E>> {
+        self.read().map(FifoStatus::from_register)
+    }
+
+    // FIFOに溜まっているサンプルを1回のバースト転送でまとめて読み出します
+    // timestamp_usには読み出した時刻を渡します
+    pub fn read_fifo(
+        &mut self,
+        timestamp_us: u32,
+    ) -> Result<FifoBatch, Error<E>> {
+        let status = self.fifo_status()?;
+        let mut batch = FifoBatch {
+            data: [0; 6 * FIFO_DEPTH],
+            len: status.len,
+            shift: 16 - self.config.mode.bits(),
+            scale: self.config.mode.sensitivity(self.config.range) / 1000.0,
+            last_timestamp_us: timestamp_us,
+            period_us: match self.config.data_rate.hz(self.config.mode) {
+                hz if hz > 0.0 => (1_000_000.0 / hz) as u32,
+                _ => 0,
+            },
+        };
+        if batch.len > 0 {
+            // FIFOの読み出しでは、OUT_Z_Hの次はOUT_X_Lに戻る
+            self.i2c.write_read(
+                self.address,
+                &[OUT_X_L | AUTO_INCREMENT],
+                &mut batch.data[..6 * batch.len],
+            )?;
+        }
+        Ok(batch)
+    }
+
+    // レジスタを型付きで読み出します
+    // 高レベルのAPIで扱っていない機能は、これらを使って直接設定できます
+    pub fn read<R: Register>(&mut self) -> Result<R, Error<E>> {
+        let mut data = [0u8];
+        self.i2c
+            .write_read(self.address, &[R::ADDRESS], &mut data)?;
+        Ok(R::from_bits(data[0]))
+    }
+
+    pub fn write<R: Writable>(&mut self, value: R) -> Result<(), Error<E>> {
+        self.i2c.write(self.address, &[R::ADDRESS, value.bits()])?;
+        Ok(())
+    }
+
+    // 読み出した値をfで書き換えてから書き戻します
+    pub fn modify<R, F>(&mut self, f: F) -> Result<(), Error<E>>
+    where
+        R: Writable,
+        F: FnOnce(&mut R),
+    {
+        let mut value = self.read()?;
+        f(&mut value);
+        self.write(value)
+    }
+}
+
+// accelerometerクレートのトレイトを実装しておくと、既存のコードからそのまま使えます
+impl<I2C, E> Accelerometer for Lis3dh<I2C>
+where
+    I2C: Write<Error = E> + WriteRead<Error = E>,
+    E: Debug,
+{
+    type Error = E;
+
+    fn accel_norm(&mut self) -> Result<F32x3, accelerometer::Error<E>> {
+        self.read_g().map_err(into_accelerometer_error)
+    }
+
+    fn sample_rate(&mut self) -> Result<f32, accelerometer::Error<E>> {
+        Ok(self.config.data_rate.hz(self.config.mode))
+    }
+}
+
+impl<I2C, E> RawAccelerometer<I16x3> for Lis3dh<I2C>
+where
+    I2C: Write<Error = E> + WriteRead<Error = E>,
+    E: Debug,
+{
+    type Error = E;
+
+    fn accel_raw(&mut self) -> Result<I16x3, accelerometer::Error<E>> {
+        self.read_raw().map_err(into_accelerometer_error)
+    }
+}
+
+fn into_accelerometer_error<E: Debug>(
+    error: Error<E>,
+) -> accelerometer::Error<E> {
+    match error {
+        Error::I2c(e) => accelerometer::Error::from(e),
+        Error::InvalidDevice(_) => accelerometer::Error::new(ErrorKind::Device),
+        Error::InvalidConfig => accelerometer::Error::new(ErrorKind::Param),
+    }
+}
@@ -0,0 +1,372 @@
+// LIS3DHのレジスタマップです
+// 各レジスタを1バイトの構造体として定義し、ビットフィールドごとのアクセサを持たせます
+
+// レジスタの型が実装するトレイト
+pub trait Register: Copy {
+    const ADDRESS: u8;
+    fn from_bits(bits: u8) -> Self;
+    fn bits(self) -> u8;
+}
+
+// 書き込み可能なレジスタが実装するトレイト
+pub trait Writable: Register {}
+
+macro_rules! register {
+    (
+        $name:ident = $address:expr, $access:ident, reset = $reset:literal {
+            $( $get:ident, $set:ident : $ty:ident @ $lsb:expr $(, $width:expr)? ; )*
+        }
+    ) => {
+        #[derive(Clone, Copy, PartialEq, Eq, Debug)]
+        pub struct $name(pub u8);
+
+        impl Register for $name {
+            const ADDRESS: u8 = $address;
+
+            fn from_bits(bits: u8) -> $name {
+                $name(bits)
+            }
+
+            fn bits(self) -> u8 {
+                self.0
+            }
+        }
+
+        impl Default for $name {
+            // リセット後の値
+            fn default() -> $name {
+                $name($reset)
+            }
+        }
+
+        access!($access, $name);
+
+        impl $name {
+            $( field!($get, $set, $ty, $lsb $(, $width)?); )*
+        }
+    };
+}
+
+macro_rules! access {
+    (rw, $name:ident) => {
+        impl Writable for $name {}
+    };
+    (ro, $name:ident) => {};
+}
+
+macro_rules! field {
+    ($get:ident, $set:ident, bool, $bit:expr) => {
+        pub fn $get(&self) -> bool {
+            self.0 & (1 << $bit) != 0
+        }
+
+        pub fn $set(&mut self, value: bool) -> &mut Self {
+            if value {
+                self.0 |= 1 << $bit;
+            } else {
+                self.0 &= !(1 << $bit);
+            }
+            self
+        }
+    };
+    ($get:ident, $set:ident, u8, $lsb:expr, $width:expr) => {
+        pub fn $get(&self) -> u8 {
+            let mask = ((1u16 << $width) - 1) as u8;
+            (self.0 >> $lsb) & mask
+        }
+
+        pub fn $set(&mut self, value: u8) -> &mut Self {
+            let mask = ((1u16 << $width) - 1) as u8;
+            self.0 = (self.0 & !(mask << $lsb)) | ((value & mask) << $lsb);
+            self
+        }
+    };
+}
+
+// 出力レジスタなど、ビットフィールドを持たないレジスタのアドレス
+pub const OUT_ADC1_L: u8 = 0x08;
+pub const OUT_X_L: u8 = 0x28;
+// レジスタアドレスのMSBを立てると、連続読み出し時にアドレスが自動でインクリメントされます
+pub const AUTO_INCREMENT: u8 = 0x80;
+
+// 補助ADCのステータス
+register! {
+    StatusRegAux = 0x07, ro, reset = 0x00 {
+        or321, set_or321: bool @ 7;
+        or3, set_or3: bool @ 6;
+        or2, set_or2: bool @ 5;
+        or1, set_or1: bool @ 4;
+        da321, set_da321: bool @ 3;
+        da3, set_da3: bool @ 2;
+        da2, set_da2: bool @ 1;
+        da1, set_da1: bool @ 0;
+    }
+}
+
+register! {
+    IntCounterReg = 0x0E, ro, reset = 0x00 {
+        count, set_count: u8 @ 0, 8;
+    }
+}
+
+register! {
+    WhoAmI = 0x0F, ro, reset = 0x33 {
+        id, set_id: u8 @ 0, 8;
+    }
+}
+
+// 下位ビットは0x10のまま書き込む必要がある
+register! {
+    CtrlReg0 = 0x1E, rw, reset = 0x10 {
+        sdo_pu_disc, set_sdo_pu_disc: bool @ 7;
+    }
+}
+
+register! {
+    TempCfgReg = 0x1F, rw, reset = 0x00 {
+        adc_en, set_adc_en: bool @ 7;
+        temp_en, set_temp_en: bool @ 6;
+    }
+}
+
+register! {
+    CtrlReg1 = 0x20, rw, reset = 0x07 {
+        odr, set_odr: u8 @ 4, 4;
+        lpen, set_lpen: bool @ 3;
+        zen, set_zen: bool @ 2;
+        yen, set_yen: bool @ 1;
+        xen, set_xen: bool @ 0;
+    }
+}
+
+register! {
+    CtrlReg2 = 0x21, rw, reset = 0x00 {
+        hpm, set_hpm: u8 @ 6, 2;
+        hpcf, set_hpcf: u8 @ 4, 2;
+        fds, set_fds: bool @ 3;
+        hpclick, set_hpclick: bool @ 2;
+        hp_ia2, set_hp_ia2: bool @ 1;
+        hp_ia1, set_hp_ia1: bool @ 0;
+    }
+}
+
+// INT1ピンへの割り込みの割り当て
+register! {
+    CtrlReg3 = 0x22, rw, reset = 0x00 {
+        i1_click, set_i1_click: bool @ 7;
+        i1_ia1, set_i1_ia1: bool @ 6;
+        i1_ia2, set_i1_ia2: bool @ 5;
+        i1_zyxda, set_i1_zyxda: bool @ 4;
+        i1_321da, set_i1_321da: bool @ 3;
+        i1_wtm, set_i1_wtm: bool @ 2;
+        i1_overrun, set_i1_overrun: bool @ 1;
+    }
+}
+
+register! {
+    CtrlReg4 = 0x23, rw, reset = 0x00 {
+        bdu, set_bdu: bool @ 7;
+        ble, set_ble: bool @ 6;
+        fs, set_fs: u8 @ 4, 2;
+        hr, set_hr: bool @ 3;
+        st, set_st: u8 @ 1, 2;
+        sim, set_sim: bool @ 0;
+    }
+}
+
+register! {
+    CtrlReg5 = 0x24, rw, reset = 0x00 {
+        boot, set_boot: bool @ 7;
+        fifo_en, set_fifo_en: bool @ 6;
+        lir_int1, set_lir_int1: bool @ 3;
+        d4d_int1, set_d4d_int1: bool @ 2;
+        lir_int2, set_lir_int2: bool @ 1;
+        d4d_int2, set_d4d_int2: bool @ 0;
+    }
+}
+
+// INT2ピンへの割り込みの割り当て
+register! {
+    CtrlReg6 = 0x25, rw, reset = 0x00 {
+        i2_click, set_i2_click: bool @ 7;
+        i2_ia1, set_i2_ia1: bool @ 6;
+        i2_ia2, set_i2_ia2: bool @ 5;
+        i2_boot, set_i2_boot: bool @ 4;
+        i2_act, set_i2_act: bool @ 3;
+        int_polarity, set_int_polarity: bool @ 1;
+    }
+}
+
+register! {
+    Reference = 0x26, rw, reset = 0x00 {
+        reference, set_reference: u8 @ 0, 8;
+    }
+}
+
+register! {
+    StatusReg = 0x27, ro, reset = 0x00 {
+        zyxor, set_zyxor: bool @ 7;
+        zor, set_zor: bool @ 6;
+        yor, set_yor: bool @ 5;
+        xor, set_xor: bool @ 4;
+        zyxda, set_zyxda: bool @ 3;
+        zda, set_zda: bool @ 2;
+        yda, set_yda: bool @ 1;
+        xda, set_xda: bool @ 0;
+    }
+}
+
+register! {
+    FifoCtrlReg = 0x2E, rw, reset = 0x00 {
+        fm, set_fm: u8 @ 6, 2;
+        tr, set_tr: bool @ 5;
+        fth, set_fth: u8 @ 0, 5;
+    }
+}
+
+register! {
+    FifoSrcReg = 0x2F, ro, reset = 0x20 {
+        wtm, set_wtm: bool @ 7;
+        ovrn_fifo, set_ovrn_fifo: bool @ 6;
+        empty, set_empty: bool @ 5;
+        fss, set_fss: u8 @ 0, 5;
+    }
+}
+
+// 割り込み1の検出条件
+// aoiとsix_dの組み合わせで、OR/AND条件と6D/4D位置検出を切り替えます
+register! {
+    Int1Cfg = 0x30, rw, reset = 0x00 {
+        aoi, set_aoi: bool @ 7;
+        six_d, set_six_d: bool @ 6;
+        zhie, set_zhie: bool @ 5;
+        zlie, set_zlie: bool @ 4;
+        yhie, set_yhie: bool @ 3;
+        ylie, set_ylie: bool @ 2;
+        xhie, set_xhie: bool @ 1;
+        xlie, set_xlie: bool @ 0;
+    }
+}
+
+register! {
+    Int1Src = 0x31, ro, reset = 0x00 {
+        ia, set_ia: bool @ 6;
+        zh, set_zh: bool @ 5;
+        zl, set_zl: bool @ 4;
+        yh, set_yh: bool @ 3;
+        yl, set_yl: bool @ 2;
+        xh, set_xh: bool @ 1;
+        xl, set_xl: bool @ 0;
+    }
+}
+
+register! {
+    Int1Ths = 0x32, rw, reset = 0x00 {
+        ths, set_ths: u8 @ 0, 7;
+    }
+}
+
+register! {
+    Int1Duration = 0x33, rw, reset = 0x00 {
+        duration, set_duration: u8 @ 0, 7;
+    }
+}
+
+register! {
+    Int2Cfg = 0x34, rw, reset = 0x00 {
+        aoi, set_aoi: bool @ 7;
+        six_d, set_six_d: bool @ 6;
+        zhie, set_zhie: bool @ 5;
+        zlie, set_zlie: bool @ 4;
+        yhie, set_yhie: bool @ 3;
+        ylie, set_ylie: bool @ 2;
+        xhie, set_xhie: bool @ 1;
+        xlie, set_xlie: bool @ 0;
+    }
+}
+
+register! {
+    Int2Src = 0x35, ro, reset = 0x00 {
+        ia, set_ia: bool @ 6;
+        zh, set_zh: bool @ 5;
+        zl, set_zl: bool @ 4;
+        yh, set_yh: bool @ 3;
+        yl, set_yl: bool @ 2;
+        xh, set_xh: bool @ 1;
+        xl, set_xl: bool @ 0;
+    }
+}
+
+register! {
+    Int2Ths = 0x36, rw, reset = 0x00 {
+        ths, set_ths: u8 @ 0, 7;
+    }
+}
+
+register! {
+    Int2Duration = 0x37, rw, reset = 0x00 {
+        duration, set_duration: u8 @ 0, 7;
+    }
+}
+
+// クリック検出の軸 (sがシングル、dがダブル)
+register! {
+    ClickCfg = 0x38, rw, reset = 0x00 {
+        zd, set_zd: bool @ 5;
+        zs, set_zs: bool @ 4;
+        yd, set_yd: bool @ 3;
+        ys, set_ys: bool @ 2;
+        xd, set_xd: bool @ 1;
+        xs, set_xs: bool @ 0;
+    }
+}
+
+register! {
+    ClickSrc = 0x39, ro, reset = 0x00 {
+        ia, set_ia: bool @ 6;
+        dclick, set_dclick: bool @ 5;
+        sclick, set_sclick: bool @ 4;
+        sign, set_sign: bool @ 3;
+        z, set_z: bool @ 2;
+        y, set_y: bool @ 1;
+        x, set_x: bool @ 0;
+    }
+}
+
+register! {
+    ClickThs = 0x3A, rw, reset = 0x00 {
+        lir_click, set_lir_click: bool @ 7;
+        ths, set_ths: u8 @ 0, 7;
+    }
+}
+
+register! {
+    TimeLimit = 0x3B, rw, reset = 0x00 {
+        tli, set_tli: u8 @ 0, 7;
+    }
+}
+
+register! {
+    TimeLatency = 0x3C, rw, reset = 0x00 {
+        tla, set_tla: u8 @ 0, 8;
+    }
+}
+
+register! {
+    TimeWindow = 0x3D, rw, reset = 0x00 {
+        tw, set_tw: u8 @ 0, 8;
+    }
+}
+
+// スリープ・ウェイクアップ (Activity) 検出のしきい値と時間
+register! {
+    ActThs = 0x3E, rw, reset = 0x00 {
+        acth, set_acth: u8 @ 0, 7;
+    }
+}
+
+register! {
+    ActDur = 0x3F, rw, reset = 0x00 {
+        actd, set_actd: u8 @ 0, 8;
+    }
+}
@@ -0,0 +1,19 @@
+// f32の数学関数
+// 実機と同じ結果になるように、ホストのテストでもstdではなくmicromathの実装を使います
+use micromath::F32Ext;
+
+pub fn sqrt(x: f32) -> f32 {
+    F32Ext::sqrt(x)
+}
+
+pub fn atan2(y: f32, x: f32) -> f32 {
+    F32Ext::atan2(y, x)
+}
+
+pub fn cos(x: f32) -> f32 {
+    F32Ext::cos(x)
+}
+
+pub fn log10(x: f32) -> f32 {
+    F32Ext::log10(x)
+}
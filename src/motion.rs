@@ -0,0 +1,279 @@
+use crate::math::{atan2, sqrt};
+use accelerometer::vector::F32x3;
+
+// 本体の傾き [rad]
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub struct Attitude {
+    pub pitch: f32, // X軸まわりの傾き
+    pub roll: f32,  // Y軸まわりの傾き
+}
+
+impl Attitude {
+    pub fn pitch_degrees(&self) -> f32 {
+        self.pitch.to_degrees()
+    }
+
+    pub fn roll_degrees(&self) -> f32 {
+        self.roll.to_degrees()
+    }
+}
+
+// 静止時の加速度 (重力の方向) から傾きを求めます
+pub fn attitude(accel: F32x3) -> Attitude {
+    let (x, y, z) = (accel.x, accel.y, accel.z);
+    Attitude {
+        pitch: atan2(-x, sqrt(y * y + z * z)),
+        roll: atan2(y, z),
+    }
+}
+
+// 加速度の大きさ [g]
+pub fn magnitude(accel: F32x3) -> f32 {
+    sqrt(accel.x * accel.x + accel.y * accel.y + accel.z * accel.z)
+}
+
+// 1次のローパスフィルタ (指数移動平均)
+// alphaが小さいほど平滑化が強くなります
+#[derive(Clone, Copy, Debug)]
+pub struct LowPassFilter {
+    alpha: f32,
+    state: Option<F32x3>,
+}
+
+impl LowPassFilter {
+    pub fn new(alpha: f32) -> LowPassFilter {
+        LowPassFilter { alpha, state: None }
+    }
+
+    // サンプリング周波数とカットオフ周波数から係数を決めます
+    pub fn with_cutoff(sample_rate: f32, cutoff: f32) -> LowPassFilter {
+        let dt = 1.0 / sample_rate;
+        let rc = 1.0 / (2.0 * core::f32::consts::PI * cutoff);
+        LowPassFilter::new(dt / (rc + dt))
+    }
+
+    pub fn reset(&mut self) {
+        self.state = None;
+    }
+
+    pub fn update(&mut self, input: F32x3) -> F32x3 {
+        let a = self.alpha;
+        let output = match self.state {
+            Some(s) => F32x3::new(
+                s.x + a * (input.x - s.x),
+                s.y + a * (input.y - s.y),
+                s.z + a * (input.z - s.z),
+            ),
+            None => input,
+        };
+        self.state = Some(output);
+        output
+    }
+}
+
+// 相補フィルタで傾きを推定します
+// 加速度の大きさが1[g]から外れているとき (振られているとき) は、
+// 新しいサンプルの重みを下げて、それまでの推定値を信頼します
+#[derive(Clone, Copy, Debug)]
+pub struct AttitudeEstimator {
+    alpha: f32,
+    // 1[g]からのずれがこの値 [g] を超えたサンプルは使わない
+    tolerance: f32,
+    attitude: Option<Attitude>,
+}
+
+impl AttitudeEstimator {
+    pub fn new(alpha: f32, tolerance: f32) -> AttitudeEstimator {
+        AttitudeEstimator {
+            alpha,
+            tolerance,
+            attitude: None,
+        }
+    }
+
+    pub fn attitude(&self) -> Option<Attitude> {
+        self.attitude
+    }
+
+    pub fn update(&mut self, accel: F32x3) -> Option<Attitude> {
+        let error = (magnitude(accel) - 1.0).abs();
+        if error > self.tolerance {
+            return self.attitude;
+        }
+        // 1[g]に近いほど新しいサンプルを重く扱う
+        let weight = self.alpha * (1.0 - error / self.tolerance);
+        let measured = attitude(accel);
+        let next = match self.attitude {
+            Some(previous) => Attitude {
+                pitch: blend_angle(previous.pitch, measured.pitch, weight),
+                roll: blend_angle(previous.roll, measured.roll, weight),
+            },
+            None => measured,
+        };
+        self.attitude = Some(next);
+        Some(next)
+    }
+}
+
+// -π〜πの境界をまたぐときも、近い方向に補間する
+fn blend_angle(previous: f32, measured: f32, weight: f32) -> f32 {
+    use core::f32::consts::PI;
+    let mut delta = measured - previous;
+    if delta > PI {
+        delta -= 2.0 * PI;
+    } else if delta < -PI {
+        delta += 2.0 * PI;
+    }
+    let mut angle = previous + weight * delta;
+    if angle > PI {
+        angle -= 2.0 * PI;
+    } else if angle < -PI {
+        angle += 2.0 * PI;
+    }
+    angle
+}
+
+// 歩数計の設定
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct StepCounterConfig {
+    pub sample_rate: f32,   // サンプリング周波数 [Hz]
+    pub window: usize,      // しきい値を更新する間隔 [サンプル]
+    pub min_amplitude: f32, // 歩行とみなす最小の振れ幅 [g]
+    pub min_interval: f32,  // 歩数として数える最短の間隔 [s]
+    pub max_interval: f32,  // これより間隔が空いたら歩行が途切れたとみなす [s]
+    // 歩行が途切れた後、この歩数だけ連続したら数え始める (振っただけの誤検出を防ぐ)
+    pub min_consecutive_steps: u32,
+}
+
+impl Default for StepCounterConfig {
+    fn default() -> StepCounterConfig {
+        StepCounterConfig {
+            sample_rate: 50.0,
+            window: 50,
+            min_amplitude: 0.15,
+            min_interval: 0.25,
+            max_interval: 2.0,
+            min_consecutive_steps: 4,
+        }
+    }
+}
+
+// 加速度の大きさの波形から歩数を数えます
+// 直前の区間と現在の区間の最大値と最小値の中間をしきい値とし (動的しきい値)、
+// 波形がしきい値を上から下へ横切ったときを1歩とします
+// 現在の区間も含めるので、歩き始めの1歩目からしきい値が追従します
+// 横切る前に、しきい値より振れ幅の1/4以上高くなっている必要があります (ヒステリシス)
+#[derive(Clone, Copy, Debug)]
+pub struct StepCounter {
+    config: StepCounterConfig,
+    filter: f32,
+    smoothed: Option<f32>,
+    // 直前の区間の最小値と最大値
+    last_min: f32,
+    last_max: f32,
+    window_min: f32,
+    window_max: f32,
+    window_count: usize,
+    previous: f32,
+    // しきい値より十分高くなった後で、まだ下へ横切っていない
+    armed: bool,
+    since_last_step: usize,
+    // 歩行が途切れてから、まだ数えていない歩数
+    pending: u32,
+    steps: u32,
+}
+
+impl StepCounter {
+    pub fn new(config: StepCounterConfig) -> StepCounter {
+        StepCounter {
+            config,
+            // 約5[Hz]のローパスフィルタで細かい振動を取り除く
+            filter: LowPassFilter::with_cutoff(config.sample_rate, 5.0).alpha,
+            smoothed: None,
+            last_min: f32::MAX,
+            last_max: f32::MIN,
+            window_min: f32::MAX,
+            window_max: f32::MIN,
+            window_count: 0,
+            previous: 1.0,
+            armed: false,
+            since_last_step: 0,
+            pending: 0,
+            steps: 0,
+        }
+    }
+
+    pub fn steps(&self) -> u32 {
+        self.steps
+    }
+
+    pub fn reset(&mut self) {
+        *self = StepCounter::new(self.config);
+    }
+
+    // 加速度 [g] を1サンプル入力します。歩数が増えたときはtrueを返します
+    pub fn update(&mut self, accel: F32x3) -> bool {
+        let value = magnitude(accel);
+        let smoothed = match self.smoothed {
+            Some(s) => s + self.filter * (value - s),
+            None => value,
+        };
+        self.smoothed = Some(smoothed);
+
+        // 区間ごとに最大値と最小値からしきい値を更新する
+        if smoothed < self.window_min {
+            self.window_min = smoothed;
+        }
+        if smoothed > self.window_max {
+            self.window_max = smoothed;
+        }
+        let low = self.window_min.min(self.last_min);
+        let high = self.window_max.max(self.last_max);
+        let threshold = (high + low) / 2.0;
+        let amplitude = high - low;
+        self.window_count += 1;
+        if self.window_count >= self.config.window {
+            self.last_min = self.window_min;
+            self.last_max = self.window_max;
+            self.window_min = f32::MAX;
+            self.window_max = f32::MIN;
+            self.window_count = 0;
+        }
+
+        self.since_last_step = self.since_last_step.saturating_add(1);
+        // 静止したときの小さな揺れでしきい値を何度も横切らないようにする
+        if smoothed > threshold + amplitude / 4.0 {
+            self.armed = true;
+        }
+        let crossed =
+            self.armed && self.previous > threshold && smoothed <= threshold;
+        self.previous = smoothed;
+        if !crossed {
+            return false;
+        }
+        self.armed = false;
+        if amplitude < self.config.min_amplitude {
+            return false;
+        }
+
+        let interval = self.since_last_step as f32 / self.config.sample_rate;
+        if interval < self.config.min_interval {
+            return false;
+        }
+        self.since_last_step = 0;
+        if interval > self.config.max_interval {
+            self.pending = 0;
+        }
+        if self.pending < self.config.min_consecutive_steps {
+            // 連続した歩数に達したら、それまでの分をまとめて数える
+            self.pending += 1;
+            if self.pending < self.config.min_consecutive_steps {
+                return false;
+            }
+            self.steps += self.pending;
+        } else {
+            self.steps += 1;
+        }
+        true
+    }
+}
@@ -0,0 +1,140 @@
+// NVMコントローラ (NVMCTRL) による内蔵フラッシュへの書き込み
+// 電源を切っても残しておきたい少量のデータを、フラッシュの最後のブロックに保存します
+// フラッシュは消去すると0xFFになり、書き込みでは1を0にすることしかできないので、
+// 書き換えるときはブロック (8KB) 単位で消去してから書き込みます
+// 消去・書き込みの回数には上限 (数万回) があるので、頻繁に書き込まないようにします
+use wio::pac::NVMCTRL;
+use wio_terminal as wio;
+
+pub const PAGE_SIZE: usize = 512;
+pub const BLOCK_SIZE: usize = 8192;
+// ATSAMD51P19Aのフラッシュの大きさ
+pub const FLASH_SIZE: usize = 512 * 1024;
+// データの保存に使う最後のブロック
+// プログラムが上書きしないように、memory.x でフラッシュの領域から除いています
+pub const STORAGE_ADDRESS: u32 = (FLASH_SIZE - BLOCK_SIZE) as u32;
+
+// コマンド (データシート 25.8.2 CTRLB)
+const CMDEX_KEY: u16 = 0xa5 << 8;
+const CMD_EB: u16 = 0x01; // ブロックの消去
+const CMD_WP: u16 = 0x03; // ページの書き込み
+const CMD_PBC: u16 = 0x15; // ページバッファのクリア
+
+// 割り込みフラグ (データシート 25.8.6 INTFLAG)
+const INTFLAG_DONE: u16 = 1 << 0;
+const INTFLAG_ADDRE: u16 = 1 << 1;
+const INTFLAG_PROGE: u16 = 1 << 2;
+const INTFLAG_LOCKE: u16 = 1 << 3;
+const INTFLAG_NVME: u16 = 1 << 6;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Error {
+    // アドレスがフラッシュの範囲外か、境界に揃っていない
+    InvalidAddress,
+    // 保護された領域への書き込み
+    Locked,
+    // コマンドの実行に失敗した
+    Failed,
+}
+
+pub struct Nvm {
+    nvmctrl: NVMCTRL,
+}
+
+impl Nvm {
+    pub fn new(nvmctrl: NVMCTRL) -> Nvm {
+        // ページバッファの内容を、コマンドを実行したときだけ書き込む (手動書き込みモード)
+        nvmctrl
+            .ctrla
+            .modify(|r, w| unsafe { w.bits(r.bits() & !(0x3 << 4)) });
+        Nvm { nvmctrl }
+    }
+
+    pub fn free(self) -> NVMCTRL {
+        self.nvmctrl
+    }
+
+    // フラッシュはメモリと同じように読み出せます
+    pub fn read(&self, address: u32, buffer: &mut [u8]) -> Result<(), Error> {
+        check_range(address, buffer.len())?;
+        for (i, byte) in buffer.iter_mut().enumerate() {
+            let pointer = (address as usize + i) as *const u8;
+            *byte = unsafe { core::ptr::read_volatile(pointer) };
+        }
+        Ok(())
+    }
+
+    // addressを含むブロックを消去します
+    pub fn erase_block(&mut self, address: u32) -> Result<(), Error> {
+        check_range(address, 0)?;
+        let block = address & !(BLOCK_SIZE as u32 - 1);
+        self.command(CMD_EB, block)
+    }
+
+    // 消去済みの領域にdataを書き込みます
+    // addressは4バイト境界に揃えます。dataの端数は0xFFで埋めて書き込みます
+    pub fn write(&mut self, address: u32, data: &[u8]) -> Result<(), Error> {
+        check_range(address, data.len())?;
+        if !address.is_multiple_of(4) {
+            return Err(Error::InvalidAddress);
+        }
+        let mut written = 0;
+        while written < data.len() {
+            // ページをまたがないように、1ページずつページバッファに詰めて書き込む
+            let start = address + written as u32;
+            let page = start & !(PAGE_SIZE as u32 - 1);
+            let length = (page as usize + PAGE_SIZE - start as usize)
+                .min(data.len() - written);
+            self.command(CMD_PBC, page)?;
+            for (i, chunk) in
+                data[written..written + length].chunks(4).enumerate()
+            {
+                let mut word = [0xff; 4];
+                word[..chunk.len()].copy_from_slice(chunk);
+                let pointer = (start as usize + i * 4) as *mut u32;
+                unsafe {
+                    core::ptr::write_volatile(pointer, u32::from_le_bytes(word))
+                };
+            }
+            self.command(CMD_WP, page)?;
+            written += length;
+        }
+        Ok(())
+    }
+
+    fn wait_ready(&self) {
+        while self.nvmctrl.status.read().bits() & 1 == 0 {}
+    }
+
+    fn command(&mut self, command: u16, address: u32) -> Result<(), Error> {
+        self.wait_ready();
+        let errors =
+            INTFLAG_ADDRE | INTFLAG_PROGE | INTFLAG_LOCKE | INTFLAG_NVME;
+        self.nvmctrl
+            .intflag
+            .write(|w| unsafe { w.bits(INTFLAG_DONE | errors) });
+        self.nvmctrl.addr.write(|w| unsafe { w.bits(address) });
+        self.nvmctrl
+            .ctrlb
+            .write(|w| unsafe { w.bits(CMDEX_KEY | command) });
+        self.wait_ready();
+        let flags = self.nvmctrl.intflag.read().bits();
+        if flags & INTFLAG_LOCKE != 0 {
+            Err(Error::Locked)
+        } else if flags & INTFLAG_ADDRE != 0 {
+            Err(Error::InvalidAddress)
+        } else if flags & errors != 0 {
+            Err(Error::Failed)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+fn check_range(address: u32, length: usize) -> Result<(), Error> {
+    if address as usize + length > FLASH_SIZE {
+        Err(Error::InvalidAddress)
+    } else {
+        Ok(())
+    }
+}
@@ -0,0 +1,323 @@
+use core::fmt::Write;
+use eg::drawable::Pixel;
+use eg::fonts::{Font, Font6x8, Text};
+use eg::pixelcolor::Rgb565;
+use eg::prelude::*;
+use eg::primitives::{Line, Rectangle};
+use eg::style::{PrimitiveStyle, TextStyle};
+use embedded_graphics as eg;
+use heapless::consts::*;
+use heapless::String;
+
+// 保持できる最大のサンプル数 (画面幅と同じ)
+pub const MAX_POINTS: usize = 320;
+// 描画する系列の数 (X/Y/Z軸)
+pub const TRACES: usize = 3;
+
+// 目盛りの表示に使う左側の余白 [px]
+const AXIS_WIDTH: i32 = 6 * 6;
+// 0の線の色 (灰色)
+const GRID_COLOR: Rgb565 = Rgb565::new(8, 16, 8);
+// 最新のサンプルの右側に空けておく列の数
+const GAP: usize = 4;
+
+// 目盛りの文字列。f32を"{:+.2}"で書式化した最長の文字列
+// (符号、整数部39桁、小数点、小数部2桁の43文字) が入る大きさにする
+type Label = String<U48>;
+
+// 左端から右端へ掃引しながらサンプルを描く折れ線グラフです
+// 前回の描画から追加されたサンプルの列だけを消して描き直すので、ちらつきません
+// 縦軸は表示中のサンプルの最小値・最大値に合わせて自動で拡大縮小し、
+// そのときだけグラフ全体を描き直します
+pub struct Plot {
+    top_left: Point,
+    size: Size,
+    colors: [Rgb565; TRACES],
+    background: Rgb565,
+    // 縦軸の範囲がこれより狭くならないようにする
+    min_span: f32,
+    // サンプルはリングバッファの位置と同じ列に描く
+    samples: [[f32; TRACES]; MAX_POINTS],
+    head: usize,
+    len: usize,
+    // まだ描いていないサンプルの数
+    pending: usize,
+    // 描画済みのグラフの縦軸の範囲。Noneなら全体を描き直す
+    scale: Option<(f32, f32)>,
+}
+
+impl Plot {
+    pub fn new(top_left: Point, size: Size) -> Plot {
+        Plot {
+            top_left,
+            size,
+            colors: [Rgb565::RED, Rgb565::GREEN, Rgb565::CYAN],
+            background: Rgb565::BLACK,
+            min_span: 0.1,
+            samples: [[0.0; TRACES]; MAX_POINTS],
+            head: 0,
+            len: 0,
+            pending: 0,
+            scale: None,
+        }
+    }
+
+    pub fn set_colors(&mut self, colors: [Rgb565; TRACES]) {
+        self.colors = colors;
+        self.invalidate();
+    }
+
+    pub fn set_min_span(&mut self, min_span: f32) {
+        self.min_span = min_span;
+        self.invalidate();
+    }
+
+    // グラフ部分の幅 [px]。1ピクセルに1サンプルを表示します
+    pub fn capacity(&self) -> usize {
+        let width = self.size.width as i32 - AXIS_WIDTH;
+        (width.max(0) as usize).min(MAX_POINTS)
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn clear(&mut self) {
+        self.head = 0;
+        self.len = 0;
+        self.pending = 0;
+        self.invalidate();
+    }
+
+    // 次のdraw()でグラフ全体を描き直します (画面を消去したときなど)
+    pub fn invalidate(&mut self) {
+        self.scale = None;
+    }
+
+    // サンプルを追加します。いっぱいの場合は最も古いサンプルを捨てます
+    pub fn push(&mut self, values: [f32; TRACES]) {
+        let capacity = self.capacity();
+        if capacity == 0 {
+            return;
+        }
+        let index = (self.head + self.len) % capacity;
+        self.samples[index] = values;
+        if self.len < capacity {
+            self.len += 1;
+        } else {
+            self.head = (self.head + 1) % capacity;
+        }
+        self.pending = (self.pending + 1).min(capacity);
+    }
+
+    // 古い順にi番目のサンプル
+    pub fn get(&self, i: usize) -> Option<[f32; TRACES]> {
+        if i >= self.len {
+            return None;
+        }
+        Some(self.samples[(self.head + i) % self.capacity()])
+    }
+
+    // 縦軸の範囲 (最小値, 最大値)
+    pub fn range(&self) -> (f32, f32) {
+        let mut min = f32::MAX;
+        let mut max = f32::MIN;
+        for i in 0..self.len {
+            for &value in self.samples[(self.head + i) % self.capacity()].iter()
+            {
+                if value < min {
+                    min = value;
+                }
+                if value > max {
+                    max = value;
+                }
+            }
+        }
+        if self.len == 0 {
+            min = -self.min_span / 2.0;
+            max = self.min_span / 2.0;
+        }
+        // 上下に1割の余白を取る
+        let mut span = max - min;
+        if span < self.min_span {
+            let center = (max + min) / 2.0;
+            min = center - self.min_span / 2.0;
+            max = center + self.min_span / 2.0;
+            span = self.min_span;
+        }
+        (min - span * 0.1, max + span * 0.1)
+    }
+
+    // 描画済みの縦軸の範囲のままでよいか
+    // はみ出したときと、範囲が半分より狭くなったときに拡大縮小する
+    fn keeps_scale(&self, (min, max): (f32, f32)) -> bool {
+        match self.scale {
+            Some((scale_min, scale_max)) => {
+                min >= scale_min
+                    && max <= scale_max
+                    && (max - min) * 2.0 >= scale_max - scale_min
+            }
+            None => false,
+        }
+    }
+
+    // 前回から追加されたサンプルを描きます。縦軸を拡大縮小したときは全体を描き直します
+    pub fn draw<D>(&mut self, display: &mut D) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Rgb565>,
+    {
+        let capacity = self.capacity();
+        if capacity == 0 {
+            return Ok(());
+        }
+        let range = self.range();
+        if !self.keeps_scale(range) {
+            self.scale = Some(range);
+            self.pending = 0;
+            return self.draw_all(display, range);
+        }
+        if self.pending == 0 {
+            return Ok(());
+        }
+        // 古いサンプルから順に、その列を消して描く
+        let newest = self.head + self.len - 1;
+        for i in (0..self.pending).rev() {
+            let slot = (newest + capacity - i) % capacity;
+            self.draw_column(display, slot, range, true)?;
+        }
+        self.pending = 0;
+        self.erase_gap(display)
+    }
+
+    fn draw_all<D>(
+        &self,
+        display: &mut D,
+        (min, max): (f32, f32),
+    ) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Rgb565>,
+    {
+        let bottom_right = self.top_left
+            + Point::new(
+                self.size.width as i32 - 1,
+                self.size.height as i32 - 1,
+            );
+        Rectangle::new(self.top_left, bottom_right)
+            .into_styled(PrimitiveStyle::with_fill(self.background))
+            .draw(display)?;
+
+        // 目盛り (最大値・最小値) を描く
+        let label_style = TextStyle::new(Font6x8, Rgb565::WHITE);
+        let mut label = Label::new();
+        write!(label, "{:+.2}", max).ok();
+        Text::new(label.as_str(), self.top_left)
+            .into_styled(label_style)
+            .draw(display)?;
+        // heapless 0.5のString::clear()は範囲外を参照するので使わない
+        label = Label::new();
+        write!(label, "{:+.2}", min).ok();
+        let font_height = Font6x8::CHARACTER_SIZE.height as i32;
+        Text::new(
+            label.as_str(),
+            Point::new(self.top_left.x, bottom_right.y - font_height + 1),
+        )
+        .into_styled(label_style)
+        .draw(display)?;
+
+        for slot in 0..self.len {
+            if !self.in_gap(slot) {
+                self.draw_column(display, slot, (min, max), false)?;
+            }
+        }
+        Ok(())
+    }
+
+    // 最新のサンプルの右側の空白 (描画位置の目印) に入っている列か
+    fn in_gap(&self, slot: usize) -> bool {
+        let capacity = self.capacity();
+        let newest = (self.head + self.len - 1) % capacity;
+        let distance = (slot + capacity - newest) % capacity;
+        distance != 0 && distance <= GAP
+    }
+
+    // 最新のサンプルの右側の列を消しておく
+    fn erase_gap<D>(&self, display: &mut D) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Rgb565>,
+    {
+        let capacity = self.capacity();
+        let newest = self.head + self.len - 1;
+        for i in 1..=GAP.min(capacity - 1) {
+            let x = self.column_x((newest + i) % capacity);
+            self.vertical_line(display, x, self.background)?;
+        }
+        Ok(())
+    }
+
+    fn column_x(&self, slot: usize) -> i32 {
+        self.top_left.x + AXIS_WIDTH + slot as i32
+    }
+
+    fn vertical_line<D>(
+        &self,
+        display: &mut D,
+        x: i32,
+        color: Rgb565,
+    ) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Rgb565>,
+    {
+        let bottom = self.top_left.y + self.size.height as i32 - 1;
+        Line::new(Point::new(x, self.top_left.y), Point::new(x, bottom))
+            .into_styled(PrimitiveStyle::with_stroke(color, 1))
+            .draw(display)
+    }
+
+    // slotのサンプルを、1つ前のサンプルからの線分として描く
+    // 左端の列と空白の直後の列は、つながっていないので点だけを描く
+    fn draw_column<D>(
+        &self,
+        display: &mut D,
+        slot: usize,
+        (min, max): (f32, f32),
+        erase: bool,
+    ) -> Result<(), D::Error>
+    where
+        D: DrawTarget<Rgb565>,
+    {
+        let height = self.size.height as i32;
+        let to_y = |value: f32| {
+            let ratio = (value - min) / (max - min);
+            self.top_left.y + height - 1 - (ratio * (height - 1) as f32) as i32
+        };
+        let x = self.column_x(slot);
+        if erase {
+            self.vertical_line(display, x, self.background)?;
+        }
+        // 範囲に含まれていれば0の線を描く
+        if min < 0.0 && max > 0.0 {
+            Pixel(Point::new(x, to_y(0.0)), GRID_COLOR).draw(display)?;
+        }
+        let connected = slot > 0 && !self.in_gap(slot - 1);
+        let current = self.samples[slot];
+        let previous = if connected {
+            self.samples[slot - 1]
+        } else {
+            current
+        };
+        let previous_x = if connected { x - 1 } else { x };
+        for trace in 0..TRACES {
+            Line::new(
+                Point::new(previous_x, to_y(previous[trace])),
+                Point::new(x, to_y(current[trace])),
+            )
+            .into_styled(PrimitiveStyle::with_stroke(self.colors[trace], 1))
+            .draw(display)?;
+        }
+        Ok(())
+    }
+}
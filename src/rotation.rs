@@ -0,0 +1,172 @@
+use crate::lcd::MemoryAccess;
+use accelerometer::vector::F32x3;
+use embedded_graphics::geometry::Size;
+use ili9341::Orientation;
+
+// 本体を傾けた方向。画面の上端がどちらを向いているかを表します
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Tilt {
+    TopUp,    // 通常の横持ち
+    BottomUp, // 横持ちで上下逆さま
+    LeftUp,   // 左端が上の縦持ち
+    RightUp,  // 右端が上の縦持ち
+}
+
+impl Tilt {
+    // 傾きに対応するLCDの向き
+    pub fn orientation(self) -> Orientation {
+        match self {
+            Tilt::TopUp => Orientation::LandscapeFlipped,
+            Tilt::BottomUp => Orientation::Landscape,
+            Tilt::LeftUp => Orientation::Portrait,
+            Tilt::RightUp => Orientation::PortraitFlipped,
+        }
+    }
+
+    // 縦持ちかどうか
+    pub fn is_portrait(self) -> bool {
+        match self {
+            Tilt::LeftUp | Tilt::RightUp => true,
+            Tilt::TopUp | Tilt::BottomUp => false,
+        }
+    }
+
+    // 傾きに対応するMADCTLの設定 (wio_examples::lcd::Lcdを使う場合)
+    // TopUpがMemoryAccess::landscape()で、BottomUpはそれを180°回転した向きです
+    pub fn memory_access(self) -> MemoryAccess {
+        let landscape = MemoryAccess::landscape();
+        match self {
+            Tilt::TopUp => landscape,
+            Tilt::BottomUp => MemoryAccess {
+                row_reverse: true,
+                column_reverse: true,
+                ..landscape
+            },
+            Tilt::LeftUp => MemoryAccess {
+                row_column_swap: false,
+                row_reverse: true,
+                ..landscape
+            },
+            Tilt::RightUp => MemoryAccess {
+                row_column_swap: false,
+                column_reverse: true,
+                ..landscape
+            },
+        }
+    }
+
+    // この向きでの画面の大きさ [px]
+    pub fn size(self) -> Size {
+        if self.is_portrait() {
+            Size::new(240, 320)
+        } else {
+            Size::new(320, 240)
+        }
+    }
+}
+
+// 自動回転の設定
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct AutoRotateConfig {
+    // 画面の面内の重力成分がこの値 [g] を下回るとき (机に置いたときなど) は回転しない
+    pub threshold: f32,
+    // 縦横を切り替えるには、もう一方の軸よりこの値 [g] 以上大きくなる必要がある
+    pub hysteresis: f32,
+    // 同じ向きがこの回数続いたら回転する
+    pub stable_samples: u8,
+}
+
+impl Default for AutoRotateConfig {
+    fn default() -> AutoRotateConfig {
+        AutoRotateConfig {
+            threshold: 0.5,
+            hysteresis: 0.2,
+            stable_samples: 5,
+        }
+    }
+}
+
+// 加速度から画面の向きを決めて、チャタリングを取り除くフィルタです
+pub struct AutoRotate {
+    config: AutoRotateConfig,
+    current: Tilt,
+    candidate: Tilt,
+    count: u8,
+}
+
+impl AutoRotate {
+    pub fn new(config: AutoRotateConfig, initial: Tilt) -> AutoRotate {
+        AutoRotate {
+            config,
+            current: initial,
+            candidate: initial,
+            count: 0,
+        }
+    }
+
+    pub fn tilt(&self) -> Tilt {
+        self.current
+    }
+
+    // 加速度 [g] を入力します
+    // 向きが変わったときだけ、新しい傾きを返します
+    pub fn update(&mut self, accel: F32x3) -> Option<Tilt> {
+        let tilt = match self.detect(accel) {
+            Some(tilt) => tilt,
+            None => {
+                self.count = 0;
+                return None;
+            }
+        };
+        if tilt == self.current {
+            self.count = 0;
+            return None;
+        }
+        if tilt != self.candidate {
+            self.candidate = tilt;
+            self.count = 0;
+        }
+        self.count += 1;
+        if self.count < self.config.stable_samples {
+            return None;
+        }
+        self.current = tilt;
+        self.count = 0;
+        Some(tilt)
+    }
+
+    // 1回分の加速度から傾きを判定します。判定できない場合はNoneを返します
+    // Wio TerminalではLIS3DHのX軸が画面の上下方向、Y軸が左右方向を向いています
+    pub fn detect(&self, accel: F32x3) -> Option<Tilt> {
+        let (x, y) = (accel.x, accel.y);
+        let (ax, ay) = (abs(x), abs(y));
+        if ax < self.config.threshold && ay < self.config.threshold {
+            return None;
+        }
+        // 現在と同じ軸の向きは、ヒステリシスなしで維持する
+        let vertical = match self.current {
+            Tilt::TopUp | Tilt::BottomUp => ax + self.config.hysteresis >= ay,
+            Tilt::LeftUp | Tilt::RightUp => ax >= ay + self.config.hysteresis,
+        };
+        let tilt = if vertical {
+            if x > 0.0 {
+                Tilt::TopUp
+            } else {
+                Tilt::BottomUp
+            }
+        } else if y > 0.0 {
+            Tilt::LeftUp
+        } else {
+            Tilt::RightUp
+        };
+        Some(tilt)
+    }
+}
+
+fn abs(value: f32) -> f32 {
+    if value < 0.0 {
+        -value
+    } else {
+        value
+    }
+}
@@ -0,0 +1,241 @@
+// サンプルの画面描画です
+// DrawTarget<Rgb565>に対して描画するので、LCDとホストのシミュレータの両方で使えます
+use crate::laps::Laps;
+use crate::stopwatch::{format_time, TimeField, LAP_ROWS};
+use core::fmt::Write;
+use eg::fonts::{Font12x16, Font24x32, Font8x16, Text};
+use eg::pixelcolor::Rgb565;
+use eg::prelude::*;
+use eg::primitives::Rectangle;
+use eg::style::{PrimitiveStyle, TextStyle, TextStyleBuilder};
+use embedded_graphics as eg;
+use heapless::consts::*;
+use heapless::String;
+
+pub const SCREEN_WIDTH: i32 = 320;
+pub const SCREEN_HEIGHT: i32 = 240;
+
+// 画面全体を黒で塗りつぶします
+pub fn clear<T>(display: &mut T) -> Result<(), T::Error>
+where
+    T: DrawTarget<Rgb565>,
+{
+    let size = display.size();
+    fill(
+        display,
+        Point::zero(),
+        Point::new(size.width as i32 - 1, size.height as i32 - 1),
+        Rgb565::BLACK,
+    )
+}
+
+// 7-4 Hello World
+pub fn hello<T>(display: &mut T) -> Result<(), T::Error>
+where
+    T: DrawTarget<Rgb565>,
+{
+    clear(display)?;
+    Text::new("Hello Wio Terminal!", Point::new(46, 112))
+        .into_styled(TextStyle::new(Font12x16, Rgb565::WHITE))
+        .draw(display)
+}
+
+// 8-1 経過時間の表示に使うフォントの大きさと文字数 ("MM:SS.ss")
+const TIME_FONT_WIDTH: i32 = 24;
+const TIME_FONT_HEIGHT: i32 = 32;
+const TIME_CHARS: i32 = 8;
+// 経過時間の上端から、モードの名前とラップの一覧の上端までの距離
+const TITLE_GAP: i32 = 44;
+const LAP_GAP: i32 = 46;
+
+// 経過時間を表示する位置 (左上)
+// ほかの表示もこの位置から配置するので、縦長の画面でも表示できます
+fn time_position(size: Size) -> Point {
+    Point::new(
+        (size.width as i32 - TIME_FONT_WIDTH * TIME_CHARS) / 2,
+        (size.height as i32 - TIME_FONT_HEIGHT) / 2,
+    )
+}
+
+// 8-1 ストップウォッチの経過時間 (分:秒.1/100秒) を画面中央に表示します
+// 文字の背景も黒で塗るので、前の表示を消さずに上書きできます
+pub fn stopwatch<T>(display: &mut T, elapsed_ms: u32) -> Result<(), T::Error>
+where
+    T: DrawTarget<Rgb565>,
+{
+    let style = TextStyleBuilder::new(Font24x32)
+        .text_color(Rgb565::WHITE)
+        .background_color(Rgb565::BLACK)
+        .build();
+    let position = time_position(display.size());
+    Text::new(format_time(elapsed_ms).as_str(), position)
+        .into_styled(style)
+        .draw(display)
+}
+
+// 8-1 経過時間の下に、選んでいる桁を示す下線を引きます。Noneなら消します
+pub fn time_cursor<T>(
+    display: &mut T,
+    field: Option<TimeField>,
+) -> Result<(), T::Error>
+where
+    T: DrawTarget<Rgb565>,
+{
+    let time = time_position(display.size());
+    let top = time.y + TIME_FONT_HEIGHT + 2;
+    fill(
+        display,
+        Point::new(time.x, top),
+        Point::new(time.x + TIME_FONT_WIDTH * TIME_CHARS - 1, top + 2),
+        Rgb565::BLACK,
+    )?;
+    let first = match field {
+        Some(TimeField::Minutes) => 0,
+        Some(TimeField::Seconds) => 3,
+        None => return Ok(()),
+    };
+    let left = time.x + TIME_FONT_WIDTH * first;
+    fill(
+        display,
+        Point::new(left, top),
+        Point::new(left + TIME_FONT_WIDTH * 2 - 1, top + 2),
+        Rgb565::YELLOW,
+    )
+}
+
+// 8-1 経過時間の上に、モードの名前を中央揃えで表示します
+pub fn stopwatch_title<T>(display: &mut T, title: &str) -> Result<(), T::Error>
+where
+    T: DrawTarget<Rgb565>,
+{
+    let size = display.size();
+    let (width, top) = (size.width as i32, time_position(size).y - TITLE_GAP);
+    fill(
+        display,
+        Point::new(0, top),
+        Point::new(width - 1, top + 15),
+        Rgb565::BLACK,
+    )?;
+    let left = (width - 12 * title.len() as i32) / 2;
+    Text::new(title, Point::new(left, top))
+        .into_styled(TextStyle::new(Font12x16, Rgb565::CYAN))
+        .draw(display)
+}
+
+const LAP_ROW_HEIGHT: i32 = 17;
+
+// 8-1 ラップタイムの一覧を、経過時間の下に新しいものから順に表示します
+// firstは一番上に表示するラップ (最新のラップから数えた位置) で、スクロールに使います
+// 最も速いラップを緑、最も遅いラップを赤で表示します
+// 各行は同じ幅で背景も塗るので、ラップのない行だけを消します
+pub fn laps<T>(
+    display: &mut T,
+    laps: &Laps,
+    first: usize,
+) -> Result<(), T::Error>
+where
+    T: DrawTarget<Rgb565>,
+{
+    let (best, worst) = (laps.best(), laps.worst());
+    let size = display.size();
+    let width = size.width as i32;
+    let lap_top = time_position(size).y + LAP_GAP;
+    for row in 0..LAP_ROWS {
+        let top = lap_top + LAP_ROW_HEIGHT * row as i32;
+        let index = match laps.len().checked_sub(first + row + 1) {
+            Some(index) => index,
+            None => {
+                fill(
+                    display,
+                    Point::new(0, top),
+                    Point::new(width - 1, top + LAP_ROW_HEIGHT - 1),
+                    Rgb565::BLACK,
+                )?;
+                continue;
+            }
+        };
+        // "番号  ラップタイム  スプリットタイム"
+        let mut text = String::<U32>::new();
+        write!(
+            text,
+            "{:2}  {}  {}",
+            index + 1,
+            format_time(laps.lap(index)),
+            format_time(laps.split(index))
+        )
+        .unwrap();
+        let color = if Some(index) == best {
+            Rgb565::GREEN
+        } else if Some(index) == worst {
+            Rgb565::RED
+        } else {
+            Rgb565::WHITE
+        };
+        let style = TextStyleBuilder::new(Font8x16)
+            .text_color(color)
+            .background_color(Rgb565::BLACK)
+            .build();
+        let left = (width - 8 * text.len() as i32) / 2;
+        Text::new(text.as_str(), Point::new(left, top))
+            .into_styled(style)
+            .draw(display)?;
+    }
+    Ok(())
+}
+
+// 8-2 スペクトラムの棒グラフの幅 [px]
+pub const BAR_WIDTH: i32 = 2;
+
+// 8-2 スペクトラムを棒グラフで描画します
+// heightsは各棒の高さ [px]、previousは前回描画した高さで、描画後に更新されます
+// 前回との差分だけを描くので、毎回画面を消去する必要はありません
+pub fn spectrum<T>(
+    display: &mut T,
+    heights: &[u8],
+    previous: &mut [u8],
+) -> Result<(), T::Error>
+where
+    T: DrawTarget<Rgb565>,
+{
+    let bottom = SCREEN_HEIGHT - 1;
+    for (i, (&height, previous)) in
+        heights.iter().zip(previous.iter_mut()).enumerate()
+    {
+        let left = BAR_WIDTH * i as i32;
+        let right = left + BAR_WIDTH - 1;
+        let (new, old) = (height as i32, *previous as i32);
+        if new > old {
+            // 伸びた部分を描く
+            fill(
+                display,
+                Point::new(left, bottom - new + 1),
+                Point::new(right, bottom - old),
+                Rgb565::GREEN,
+            )?;
+        } else if new < old {
+            // 縮んだ部分を消す
+            fill(
+                display,
+                Point::new(left, bottom - old + 1),
+                Point::new(right, bottom - new),
+                Rgb565::BLACK,
+            )?;
+        }
+        *previous = height;
+    }
+    Ok(())
+}
+
+fn fill<T>(
+    display: &mut T,
+    top_left: Point,
+    bottom_right: Point,
+    color: Rgb565,
+) -> Result<(), T::Error>
+where
+    T: DrawTarget<Rgb565>,
+{
+    Rectangle::new(top_left, bottom_right)
+        .into_styled(PrimitiveStyle::with_fill(color))
+        .draw(display)
+}
@@ -0,0 +1,153 @@
+// 8-2 音声のスペクトラム解析
+// サンプルに窓関数をかけてFFT (microfft) し、各周波数の振幅をdBで求めます
+// ハードウェアに依存しないので、ホストでテストできます (tests/spectrum.rs)
+use crate::math::{cos, log10, sqrt};
+use core::f32::consts::PI;
+
+// FFTの点数として選べる範囲 (2のべき乗)
+pub const MIN_POINTS: usize = 64;
+pub const MAX_POINTS: usize = 1024;
+// to_db()が返す最小の値 [dB] (振幅0のとき)
+pub const MIN_DB: f32 = -120.0;
+
+// FFTの前にサンプルにかける窓関数
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Window {
+    Hann,     // 周波数の分解能と漏れのバランスがよい
+    Hamming,  // Hann窓より近くの漏れが小さい
+    Blackman, // 遠くへの漏れが小さい
+    FlatTop,  // ピークの振幅を正確に測れるが、ピークの幅が広い
+}
+
+impl Window {
+    // 点数pointsの窓のi番目の係数
+    pub fn coefficient(self, i: usize, points: usize) -> f32 {
+        let x = 2.0 * PI * i as f32 / points as f32;
+        match self {
+            Window::Hann => 0.5 - 0.5 * cos(x),
+            Window::Hamming => 0.54 - 0.46 * cos(x),
+            Window::Blackman => 0.42 - 0.5 * cos(x) + 0.08 * cos(2.0 * x),
+            Window::FlatTop => {
+                0.215_578_95 - 0.416_631_58 * cos(x)
+                    + 0.277_263_16 * cos(2.0 * x)
+                    - 0.083_578_95 * cos(3.0 * x)
+                    + 0.006_947_37 * cos(4.0 * x)
+            }
+        }
+    }
+
+    // 係数の平均。窓をかけて小さくなった振幅を元に戻すのに使います
+    pub fn coherent_gain(self) -> f32 {
+        match self {
+            Window::Hann => 0.5,
+            Window::Hamming => 0.54,
+            Window::Blackman => 0.42,
+            Window::FlatTop => 0.215_578_95,
+        }
+    }
+}
+
+// FFTの点数として使えるか
+pub fn is_supported(points: usize) -> bool {
+    points.is_power_of_two() && (MIN_POINTS..=MAX_POINTS).contains(&points)
+}
+
+// 振幅をdBにします (振幅1が0[dB])
+pub fn to_db(magnitude: f32) -> f32 {
+    if magnitude > 1e-6 {
+        20.0 * log10(magnitude)
+    } else {
+        MIN_DB
+    }
+}
+
+pub struct Spectrum {
+    points: usize,
+    window: Window,
+    // 窓関数の係数
+    coefficients: [f32; MAX_POINTS],
+    // FFTの作業領域
+    buffer: [f32; MAX_POINTS],
+}
+
+impl Spectrum {
+    // pointsは64〜1024の2のべき乗です
+    pub fn new(points: usize, window: Window) -> Spectrum {
+        assert!(is_supported(points));
+        let mut spectrum = Spectrum {
+            points,
+            window,
+            coefficients: [0.0; MAX_POINTS],
+            buffer: [0.0; MAX_POINTS],
+        };
+        spectrum.set_window(window);
+        spectrum
+    }
+
+    pub fn points(&self) -> usize {
+        self.points
+    }
+
+    // 結果の周波数ビンの数 (0 Hz から サンプリング周波数/2 の手前まで)
+    pub fn bins(&self) -> usize {
+        self.points / 2
+    }
+
+    pub fn window(&self) -> Window {
+        self.window
+    }
+
+    pub fn set_window(&mut self, window: Window) {
+        self.window = window;
+        for (i, c) in self.coefficients[..self.points].iter_mut().enumerate() {
+            *c = window.coefficient(i, self.points);
+        }
+    }
+
+    // index番目の周波数ビンの中心の周波数 [Hz]
+    pub fn bin_frequency(&self, index: usize, sampling_rate: f32) -> f32 {
+        index as f32 * sampling_rate / self.points as f32
+    }
+
+    // samples (points個、-1〜1) の各周波数ビンの振幅をmagnitudesに書き込みます
+    // 振幅1の正弦波が、その周波数のビンで1になるように補正します
+    // magnitudesはbins()個まで使います
+    pub fn magnitudes(&mut self, samples: &[f32], magnitudes: &mut [f32]) {
+        let n = self.points;
+        assert!(samples.len() == n);
+        for ((b, &s), &c) in self.buffer[..n]
+            .iter_mut()
+            .zip(samples)
+            .zip(self.coefficients[..n].iter())
+        {
+            *b = s * c;
+        }
+
+        let buffer = &mut self.buffer[..n];
+        let bins: &[_] = match n {
+            64 => microfft::real::rfft_64(buffer),
+            128 => microfft::real::rfft_128(buffer),
+            256 => microfft::real::rfft_256(buffer),
+            512 => microfft::real::rfft_512(buffer),
+            _ => microfft::real::rfft_1024(buffer),
+        };
+
+        let scale = 2.0 / (n as f32 * self.window.coherent_gain());
+        for (k, (m, bin)) in magnitudes.iter_mut().zip(bins).enumerate() {
+            *m = if k == 0 {
+                // 0 Hzの成分は実部だけ (虚部にはナイキスト周波数の成分が入ることがある)
+                bin.re.abs() * scale / 2.0
+            } else {
+                sqrt(bin.re * bin.re + bin.im * bin.im) * scale
+            };
+        }
+    }
+
+    // magnitudes()の振幅をdBにしてdbに書き込みます
+    pub fn magnitudes_db(&mut self, samples: &[f32], db: &mut [f32]) {
+        self.magnitudes(samples, db);
+        for value in db.iter_mut().take(self.bins()) {
+            *value = to_db(*value);
+        }
+    }
+}
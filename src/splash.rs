@@ -0,0 +1,103 @@
+// 画像を画面中央に表示するスプラッシュ画面
+use crate::image::Asset;
+use eg::drawable::Pixel;
+use eg::image::IntoPixelIter;
+use eg::pixelcolor::Rgb565;
+use eg::prelude::*;
+use eg::primitives::Rectangle;
+use eg::style::PrimitiveStyle;
+use embedded_graphics as eg;
+use embedded_hal::blocking::delay::DelayMs;
+
+pub struct Splash<'a> {
+    image: Asset<'a>,
+    background: Rgb565,
+}
+
+impl<'a> Splash<'a> {
+    pub fn new(image: Asset<'a>, background: Rgb565) -> Splash<'a> {
+        Splash { image, background }
+    }
+
+    // 画像を中央に置いたときの左上の座標
+    pub fn position(&self, screen: Size) -> Point {
+        Point::new(
+            (screen.width as i32 - self.image.width() as i32) / 2,
+            (screen.height as i32 - self.image.height() as i32) / 2,
+        )
+    }
+
+    // 背景で塗りつぶしてから、画像を中央に描画します
+    pub fn draw<T>(&self, display: &mut T) -> Result<(), T::Error>
+    where
+        T: DrawTarget<Rgb565>,
+    {
+        self.clear(display)?;
+        self.draw_blended(display, 1, 1)
+    }
+
+    // 背景の色から画像の色へ、stepsで段階的に変化させながら描画します
+    // 1段階ごとにstep_ms [ms] 待ちます
+    pub fn fade_in<T, D>(
+        &self,
+        display: &mut T,
+        steps: u8,
+        step_ms: u16,
+        delay: &mut D,
+    ) -> Result<(), T::Error>
+    where
+        T: DrawTarget<Rgb565>,
+        D: DelayMs<u16>,
+    {
+        self.clear(display)?;
+        let steps = steps.max(1);
+        for step in 1..=steps {
+            self.draw_blended(display, step, steps)?;
+            delay.delay_ms(step_ms);
+        }
+        Ok(())
+    }
+
+    fn clear<T>(&self, display: &mut T) -> Result<(), T::Error>
+    where
+        T: DrawTarget<Rgb565>,
+    {
+        let size = display.size();
+        Rectangle::new(
+            Point::zero(),
+            Point::new(size.width as i32 - 1, size.height as i32 - 1),
+        )
+        .into_styled(PrimitiveStyle::with_fill(self.background))
+        .draw(display)
+    }
+
+    fn draw_blended<T>(
+        &self,
+        display: &mut T,
+        step: u8,
+        steps: u8,
+    ) -> Result<(), T::Error>
+    where
+        T: DrawTarget<Rgb565>,
+    {
+        let offset = self.position(display.size());
+        for Pixel(point, color) in (&self.image).pixel_iter() {
+            let color = blend(self.background, color, step, steps);
+            display.draw_pixel(Pixel(point + offset, color))?;
+        }
+        Ok(())
+    }
+}
+
+// fromからtoへstep/steps の割合で近づけた色を返します
+pub fn blend(from: Rgb565, to: Rgb565, step: u8, steps: u8) -> Rgb565 {
+    let mix = |a: u8, b: u8| {
+        let (a, b) = (a as i32, b as i32);
+        (a + (b - a) * step as i32 / steps as i32) as u8
+    };
+    Rgb565::new(
+        mix(from.r(), to.r()),
+        mix(from.g(), to.g()),
+        mix(from.b(), to.b()),
+    )
+}
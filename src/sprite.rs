@@ -0,0 +1,88 @@
+// 同じ大きさの画像 (フレーム) を格子状に並べたスプライトシート
+// 透過色と同じ色のピクセルは描画しないので、背景の上に重ねて描画できます
+use crate::image::Asset;
+use eg::drawable::Pixel;
+use eg::pixelcolor::Rgb565;
+use eg::prelude::*;
+use embedded_graphics as eg;
+
+pub struct SpriteSheet<'a> {
+    image: Asset<'a>,
+    frame_width: u16,
+    frame_height: u16,
+    key: Option<Rgb565>,
+}
+
+impl<'a> SpriteSheet<'a> {
+    // 画像の幅と高さは、フレームの幅と高さで割り切れる必要があります
+    pub fn new(
+        image: Asset<'a>,
+        frame_width: u16,
+        frame_height: u16,
+    ) -> SpriteSheet<'a> {
+        assert!(frame_width > 0 && image.width().is_multiple_of(frame_width));
+        assert!(
+            frame_height > 0 && image.height().is_multiple_of(frame_height)
+        );
+        SpriteSheet {
+            image,
+            frame_width,
+            frame_height,
+            key: None,
+        }
+    }
+
+    // 透過色を設定します (tools/imgconvの--keyと同じ色を指定します)
+    pub fn with_key(mut self, key: Rgb565) -> SpriteSheet<'a> {
+        self.key = Some(key);
+        self
+    }
+
+    pub fn frame_size(&self) -> Size {
+        Size::new(self.frame_width as u32, self.frame_height as u32)
+    }
+
+    // フレームは左上から行の順に番号を付けます
+    pub fn frame_count(&self) -> usize {
+        self.columns() * (self.image.height() / self.frame_height) as usize
+    }
+
+    fn columns(&self) -> usize {
+        (self.image.width() / self.frame_width) as usize
+    }
+
+    // frame番目のフレームを、左上がtop_leftになるように描画します
+    pub fn draw_frame<T>(
+        &self,
+        display: &mut T,
+        frame: usize,
+        top_left: Point,
+    ) -> Result<(), T::Error>
+    where
+        T: DrawTarget<Rgb565>,
+    {
+        if frame >= self.frame_count() {
+            return Ok(());
+        }
+        let width = self.image.width() as usize;
+        let (fw, fh) = (self.frame_width as usize, self.frame_height as usize);
+        let x0 = frame % self.columns() * fw;
+        let y0 = frame / self.columns() * fh;
+        let mut colors = self.image.colors();
+        // フレームの先頭の行まで読み飛ばす
+        // 圧縮されている場合は、先頭から順に展開することになる
+        let skip = y0 * width;
+        if skip > 0 {
+            colors.nth(skip - 1);
+        }
+        for (i, color) in colors.take((y0 + fh) * width - skip).enumerate() {
+            let (x, y) = ((skip + i) % width, (skip + i) / width);
+            if x < x0 || x >= x0 + fw || Some(color) == self.key {
+                continue;
+            }
+            let point = Point::new((x - x0) as i32, (y - y0) as i32);
+            display.draw_pixel(Pixel(top_left + point, color))?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,409 @@
+// 8-1 ストップウォッチとカウントダウンタイマーのモデル
+// ボタンのイベントと現在時刻を渡すと状態が変わり、ブザーや保存などの出力を返します
+// ハードウェアに依存しないので、ホストでテストできます (tests/stopwatch.rs)
+use crate::input::{Event, Key};
+use crate::laps::{Laps, Session};
+use core::fmt::Write;
+use heapless::consts::*;
+use heapless::String;
+
+// 時刻の単位 [tick/s]。TC3のクロック (XOSC32K) の1カウントを1tickとします
+pub const TICKS_PER_SECOND: u32 = 32_768;
+// TC3の比較値 (CC0)。MFRQモードのカウンタは0からCC0まで数えて0に戻るので、
+// オーバーフローの周期はCC0 + 1 [tick] になります
+pub const TC3_CC0: u32 = TICKS_PER_SECOND / 16 - 1;
+// TC3が1回オーバーフローするまでのtick数 (62.5[ms])
+pub const TICKS_PER_OVERFLOW: u32 = TC3_CC0 + 1;
+// カウントダウンで設定できる最大の時間 [s] (99:59)
+pub const MAX_COUNTDOWN_S: u32 = 99 * 60 + 59;
+// カウントダウンの設定時間の初期値 [s]
+pub const DEFAULT_COUNTDOWN_S: u32 = 3 * 60;
+// アラームの鳴らし方 (1/16秒ごとにブザーを鳴らすかどうか)
+pub const ALARM_PATTERN: [bool; 16] = [
+    true, false, true, false, true, false, true, false, false, false, false,
+    false, false, false, false, false,
+];
+pub const ALARM_HZ: u32 = 2000;
+// この時間 [s] 鳴らしても止められなければ、アラームを止める
+pub const ALARM_DURATION_S: u32 = 30;
+// ラップの一覧に一度に表示する行数
+pub const LAP_ROWS: usize = 5;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum State {
+    Idle,    // 停止中
+    Running, // 動作中 (時刻カウント中)
+    Alarm,   // カウントダウン終了のアラーム鳴動中
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Mode {
+    Stopwatch, // 経過時間を計る
+    Countdown, // 設定した時間から0まで数える
+}
+
+// カウントダウンの時間を入力するときに選んでいる桁
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum TimeField {
+    Minutes,
+    Seconds,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Beep {
+    pub frequency_hz: u32,
+    pub duration_ms: u16,
+}
+
+// update()の結果として、呼び出し側がすること
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct Output {
+    // ブザーを短く鳴らす
+    pub beep: Option<Beep>,
+    // 計測結果 (session()) をフラッシュに保存する
+    pub save: bool,
+    // タイトル、カーソル、ラップの一覧を描き直す
+    pub redraw: bool,
+    // ラップの一覧を描き直す
+    pub laps_changed: bool,
+}
+
+const fn beep(frequency_hz: u32, duration_ms: u16) -> Option<Beep> {
+    Some(Beep {
+        frequency_hz,
+        duration_ms,
+    })
+}
+
+pub fn ticks_to_ms(ticks: u32) -> u32 {
+    (ticks as u64 * 1000 / TICKS_PER_SECOND as u64) as u32
+}
+
+pub fn ms_to_ticks(ms: u32) -> u32 {
+    (ms as u64 * TICKS_PER_SECOND as u64 / 1000) as u32
+}
+
+// 現在時刻の計り方
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Timing {
+    // オーバーフローの回数だけを数える (62.5[ms]単位)。カウンタを読む必要がない
+    Overflows,
+    // オーバーフローの回数にカウンタの値を足す (1[tick]単位)
+    SubTick,
+}
+
+impl Timing {
+    // 現在時刻 [tick] を求めます
+    // Overflowsの場合は、countとoverflow_pendingを使いません
+    pub fn timestamp(
+        self,
+        overflows: u32,
+        count: u32,
+        overflow_pending: bool,
+    ) -> u32 {
+        match self {
+            Timing::Overflows => overflows.wrapping_mul(TICKS_PER_OVERFLOW),
+            Timing::SubTick => timestamp(overflows, count, overflow_pending),
+        }
+    }
+}
+
+// TC3のオーバーフローの回数とカウンタの値から、現在時刻 [tick] を求めます
+// overflow_pendingは、カウンタを読んだときにまだ割り込みで数えていない
+// オーバーフローがあったかどうかです。カウンタが小さければ、一周した後に読んだとみなします
+pub fn timestamp(overflows: u32, count: u32, overflow_pending: bool) -> u32 {
+    let count = count.min(TICKS_PER_OVERFLOW - 1);
+    let overflows = if overflow_pending && count < TICKS_PER_OVERFLOW / 2 {
+        overflows.wrapping_add(1)
+    } else {
+        overflows
+    };
+    overflows.wrapping_mul(TICKS_PER_OVERFLOW).wrapping_add(count)
+}
+
+// 経過時間を "MM:SS.ss" (分:秒.1/100秒) の形式にします
+pub fn format_time(elapsed_ms: u32) -> String<U16> {
+    let hundredths = elapsed_ms / 10;
+    let mut text = String::new();
+    write!(
+        text,
+        "{:02}:{:02}.{:02}",
+        (hundredths / 6000) % 100,
+        (hundredths / 100) % 60,
+        hundredths % 100
+    )
+    .unwrap();
+    text
+}
+
+// 設定した時間 [s] の桁fieldをdeltaだけ増減します (桁ごとに一周する)
+pub fn adjust_time(seconds: u32, field: TimeField, delta: i32) -> u32 {
+    let (minutes, seconds) = (seconds / 60, seconds % 60);
+    let wrap = |value: u32, count: u32| {
+        (value as i32 + delta).rem_euclid(count as i32) as u32
+    };
+    match field {
+        TimeField::Minutes => wrap(minutes, 100) * 60 + seconds,
+        TimeField::Seconds => minutes * 60 + wrap(seconds, 60),
+    }
+    .min(MAX_COUNTDOWN_S)
+}
+
+pub struct Stopwatch {
+    mode: Mode,
+    state: State,
+    // 計測を開始・再開した時刻と、それまでに積算した時間 [tick]
+    started: u32,
+    accumulated: u32,
+    // カウントダウンの間に退避しておく、ストップウォッチの経過時間 [tick]
+    saved: u32,
+    countdown_s: u32,
+    field: TimeField,
+    laps: Laps,
+    // ラップの一覧の一番上に表示するラップ (最新のラップから数えた位置)
+    scroll: usize,
+    alarm_started: u32,
+}
+
+impl Stopwatch {
+    pub fn new() -> Stopwatch {
+        Stopwatch {
+            mode: Mode::Stopwatch,
+            state: State::Idle,
+            started: 0,
+            accumulated: 0,
+            saved: 0,
+            countdown_s: DEFAULT_COUNTDOWN_S,
+            field: TimeField::Minutes,
+            laps: Laps::new(),
+            scroll: 0,
+            alarm_started: 0,
+        }
+    }
+
+    // フラッシュに保存した計測結果から、停止した状態で再開します
+    pub fn restore(&mut self, session: Session) {
+        *self = Stopwatch::new();
+        self.accumulated = ms_to_ticks(session.elapsed_ms);
+        self.laps = session.laps;
+    }
+
+    // 保存する計測結果 (ストップウォッチの経過時間とラップ)
+    pub fn session(&self, now: u32) -> Session {
+        let elapsed = match self.mode {
+            Mode::Stopwatch => self.elapsed(now),
+            Mode::Countdown => self.saved,
+        };
+        Session {
+            elapsed_ms: ticks_to_ms(elapsed),
+            laps: self.laps.clone(),
+        }
+    }
+
+    pub fn mode(&self) -> Mode {
+        self.mode
+    }
+
+    pub fn state(&self) -> State {
+        self.state
+    }
+
+    pub fn laps(&self) -> &Laps {
+        &self.laps
+    }
+
+    pub fn scroll(&self) -> usize {
+        self.scroll
+    }
+
+    pub fn countdown_seconds(&self) -> u32 {
+        self.countdown_s
+    }
+
+    // 今のモードで計測した時間 [tick]
+    pub fn elapsed(&self, now: u32) -> u32 {
+        match self.state {
+            State::Running => self
+                .accumulated
+                .wrapping_add(now.wrapping_sub(self.started)),
+            _ => self.accumulated,
+        }
+    }
+
+    // 表示する時間 [ms]。カウントダウンでは残り時間です
+    pub fn display_ms(&self, now: u32) -> u32 {
+        let elapsed_ms = ticks_to_ms(self.elapsed(now));
+        match self.mode {
+            Mode::Stopwatch => elapsed_ms,
+            Mode::Countdown => {
+                (self.countdown_s * 1000).saturating_sub(elapsed_ms)
+            }
+        }
+    }
+
+    pub fn display_text(&self, now: u32) -> String<U16> {
+        format_time(self.display_ms(now))
+    }
+
+    pub fn title(&self) -> &'static str {
+        match (self.mode, self.state) {
+            (Mode::Stopwatch, _) => "STOPWATCH",
+            (Mode::Countdown, State::Alarm) => "TIME UP!",
+            (Mode::Countdown, _) => "COUNTDOWN",
+        }
+    }
+
+    // カウントダウンの時間を設定できるのは、開始する前だけ
+    fn is_editing(&self) -> bool {
+        self.mode == Mode::Countdown
+            && self.state == State::Idle
+            && self.accumulated == 0
+    }
+
+    // 時間の設定中に、選んでいる桁
+    pub fn cursor(&self) -> Option<TimeField> {
+        if self.is_editing() {
+            Some(self.field)
+        } else {
+            None
+        }
+    }
+
+    // アラームのパターンに合わせて、今ブザーを鳴らすかどうか
+    pub fn buzzer_on(&self, now: u32) -> bool {
+        if self.state != State::Alarm {
+            return false;
+        }
+        let step =
+            now.wrapping_sub(self.alarm_started) / TICKS_PER_OVERFLOW;
+        ALARM_PATTERN[step as usize % ALARM_PATTERN.len()]
+    }
+
+    // ボタンのイベントと現在時刻 [tick] を渡して、状態を進めます
+    // イベントがなくても、カウントダウンの終了やアラームの停止のために繰り返し呼び出します
+    pub fn update(&mut self, event: Option<Event>, now: u32) -> Output {
+        let pressed = match event {
+            Some(Event::Pressed(key)) => Some(key),
+            _ => None,
+        };
+        let mut output = Output::default();
+        match self.state {
+            State::Idle => self.idle(pressed, now, &mut output),
+            State::Running => self.running(pressed, now, &mut output),
+            State::Alarm => {
+                let elapsed = now.wrapping_sub(self.alarm_started);
+                if pressed.is_some()
+                    || elapsed >= ALARM_DURATION_S * TICKS_PER_SECOND
+                {
+                    self.accumulated = 0;
+                    self.state = State::Idle;
+                    output.redraw = true;
+                }
+            }
+        }
+
+        // ストップウォッチのモードでは、ラップの一覧をいつでもスクロールできる
+        let max_scroll = self.laps.len().saturating_sub(LAP_ROWS);
+        match (self.mode, pressed) {
+            (Mode::Stopwatch, Some(Key::Up)) if self.scroll > 0 => {
+                self.scroll -= 1;
+                output.laps_changed = true;
+            }
+            (Mode::Stopwatch, Some(Key::Down)) if self.scroll < max_scroll => {
+                self.scroll += 1;
+                output.laps_changed = true;
+            }
+            _ => {}
+        }
+        output
+    }
+
+    fn idle(&mut self, pressed: Option<Key>, now: u32, output: &mut Output) {
+        let editing = self.is_editing();
+        match (self.mode, pressed) {
+            (_, Some(Key::Button3))
+                if self.mode == Mode::Stopwatch || self.countdown_s > 0 =>
+            {
+                self.started = now;
+                self.state = State::Running;
+                output.beep = beep(2000, 50);
+                output.redraw = editing;
+            }
+            (_, Some(Key::Press)) => {
+                // モードを切り替える。経過時間は片方ずつ退避しておく
+                core::mem::swap(&mut self.accumulated, &mut self.saved);
+                self.mode = match self.mode {
+                    Mode::Stopwatch => Mode::Countdown,
+                    Mode::Countdown => Mode::Stopwatch,
+                };
+                output.beep = beep(1000, 20);
+                output.redraw = true;
+            }
+            (Mode::Stopwatch, Some(Key::Button1)) => {
+                self.accumulated = 0;
+                self.laps.clear();
+                self.scroll = 0;
+                output.beep = beep(1000, 50);
+                output.save = true;
+                output.laps_changed = true;
+            }
+            (Mode::Countdown, Some(Key::Button1)) => {
+                // 設定した時間に戻す
+                self.accumulated = 0;
+                output.beep = beep(1000, 50);
+                output.redraw = true;
+            }
+            (Mode::Countdown, Some(Key::Left))
+            | (Mode::Countdown, Some(Key::Right))
+                if editing =>
+            {
+                self.field = match self.field {
+                    TimeField::Minutes => TimeField::Seconds,
+                    TimeField::Seconds => TimeField::Minutes,
+                };
+                output.redraw = true;
+            }
+            (Mode::Countdown, Some(Key::Up)) if editing => {
+                self.countdown_s = adjust_time(self.countdown_s, self.field, 1);
+            }
+            (Mode::Countdown, Some(Key::Down)) if editing => {
+                self.countdown_s =
+                    adjust_time(self.countdown_s, self.field, -1);
+            }
+            _ => {}
+        }
+    }
+
+    fn running(&mut self, pressed: Option<Key>, now: u32, output: &mut Output) {
+        match (self.mode, pressed) {
+            (_, Some(Key::Button2)) => {
+                self.accumulated = self.elapsed(now);
+                self.state = State::Idle;
+                output.beep = beep(1500, 50);
+                output.save = self.mode == Mode::Stopwatch;
+            }
+            (Mode::Stopwatch, Some(Key::Press)) if !self.laps.is_full() => {
+                self.laps.record(ticks_to_ms(self.elapsed(now)));
+                self.scroll = 0;
+                output.beep = beep(3000, 20);
+                output.laps_changed = true;
+            }
+            (Mode::Countdown, _)
+                if self.elapsed(now) >= self.countdown_s * TICKS_PER_SECOND =>
+            {
+                // 時間になったらカウントを止めて、アラームを鳴らし始める
+                self.accumulated = self.countdown_s * TICKS_PER_SECOND;
+                self.state = State::Alarm;
+                self.alarm_started = now;
+                output.redraw = true;
+            }
+            _ => {}
+        }
+    }
+}
+
+impl Default for Stopwatch {
+    fn default() -> Stopwatch {
+        Stopwatch::new()
+    }
+}
@@ -0,0 +1,151 @@
+// フォーカスを持てるボタン
+use super::{draw_text, fill, layout, Alignment, Theme, Widget};
+use eg::fonts::Font;
+use eg::pixelcolor::Rgb565;
+use eg::prelude::*;
+use eg::primitives::Rectangle;
+use eg::style::PrimitiveStyle;
+use embedded_graphics as eg;
+
+pub struct Button<'a, F> {
+    bounds: Rectangle,
+    font: F,
+    theme: Theme,
+    text: &'a str,
+    focused: bool,
+    pressed: bool,
+    dirty: bool,
+}
+
+impl<'a, F: Font + Copy> Button<'a, F> {
+    pub fn new(
+        bounds: Rectangle,
+        font: F,
+        theme: Theme,
+        text: &'a str,
+    ) -> Button<'a, F> {
+        Button {
+            bounds,
+            font,
+            theme,
+            text,
+            focused: false,
+            pressed: false,
+            dirty: true,
+        }
+    }
+
+    pub fn is_focused(&self) -> bool {
+        self.focused
+    }
+
+    pub fn set_focused(&mut self, focused: bool) {
+        if self.focused != focused {
+            self.focused = focused;
+            self.dirty = true;
+        }
+    }
+
+    pub fn is_pressed(&self) -> bool {
+        self.pressed
+    }
+
+    // 押されている間は塗りつぶして表示します
+    pub fn set_pressed(&mut self, pressed: bool) {
+        if self.pressed != pressed {
+            self.pressed = pressed;
+            self.dirty = true;
+        }
+    }
+}
+
+impl<'a, F: Font + Copy> Widget for Button<'a, F> {
+    fn bounds(&self) -> Rectangle {
+        self.bounds
+    }
+
+    fn is_dirty(&self) -> bool {
+        self.dirty
+    }
+
+    fn invalidate(&mut self) {
+        self.dirty = true;
+    }
+
+    fn draw<T>(&mut self, display: &mut T) -> Result<bool, T::Error>
+    where
+        T: DrawTarget<Rgb565>,
+    {
+        if !self.dirty {
+            return Ok(false);
+        }
+        let (face, text) = if self.pressed {
+            (self.theme.accent, self.theme.background)
+        } else {
+            (self.theme.background, self.theme.foreground)
+        };
+        fill(display, self.bounds, face)?;
+        // フォーカスがあるときは枠を太く、強調色で描く
+        let (border, width) = if self.focused {
+            (self.theme.accent, 2)
+        } else {
+            (self.theme.muted, 1)
+        };
+        for i in 0..width {
+            layout::inset(self.bounds, i)
+                .into_styled(PrimitiveStyle::with_stroke(border, 1))
+                .draw(display)?;
+        }
+        draw_text(
+            display,
+            self.text,
+            self.font,
+            self.bounds,
+            Alignment::Center,
+            text,
+        )?;
+        self.dirty = false;
+        Ok(true)
+    }
+}
+
+// 複数のウィジェットのうち、どれにフォーカスがあるかを管理します
+// ジョイスティックやボタンの入力でnext()/previous()を呼び出します
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Focus {
+    count: usize,
+    index: usize,
+}
+
+impl Focus {
+    pub fn new(count: usize) -> Focus {
+        Focus { count, index: 0 }
+    }
+
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    pub fn is_focused(&self, index: usize) -> bool {
+        self.index == index
+    }
+
+    // 最後の次は最初に戻ります
+    pub fn next(&mut self) {
+        if self.count > 0 {
+            self.index = (self.index + 1) % self.count;
+        }
+    }
+
+    pub fn previous(&mut self) {
+        if self.count > 0 {
+            self.index = (self.index + self.count - 1) % self.count;
+        }
+    }
+
+    pub fn set(&mut self, index: usize) {
+        if index < self.count {
+            self.index = index;
+        }
+    }
+}
@@ -0,0 +1,160 @@
+// 半円の目盛りと針で値を表示するメーター
+use super::{Theme, Widget};
+use eg::pixelcolor::Rgb565;
+use eg::prelude::*;
+use eg::primitives::{Circle, Line, Rectangle};
+use eg::style::PrimitiveStyle;
+use embedded_graphics as eg;
+
+// 目盛りの数 (左端から右端まで)
+const TICKS: i32 = 10;
+// sin/cosを1024倍した値で計算する
+const UNIT: i32 = 1024;
+
+pub struct Gauge {
+    center: Point,
+    radius: i32,
+    theme: Theme,
+    min: i32,
+    max: i32,
+    value: i32,
+    // 前回描画した針の角度 [度]。Noneなら目盛りから描画する
+    drawn: Option<i32>,
+}
+
+impl Gauge {
+    // centerは半円の中心 (下端の中央) です
+    pub fn new(
+        center: Point,
+        radius: u32,
+        theme: Theme,
+        min: i32,
+        max: i32,
+    ) -> Gauge {
+        Gauge {
+            center,
+            radius: radius as i32,
+            theme,
+            min,
+            max: max.max(min + 1),
+            value: min,
+            drawn: None,
+        }
+    }
+
+    pub fn value(&self) -> i32 {
+        self.value
+    }
+
+    // 範囲外の値は、範囲の端に針を置きます
+    pub fn set_value(&mut self, value: i32) {
+        self.value = value.max(self.min).min(self.max);
+    }
+
+    // 針の角度 [度]。最小値で180度 (左)、最大値で0度 (右)
+    fn angle(&self) -> i32 {
+        180 - (self.value - self.min) * 180 / (self.max - self.min)
+    }
+
+    // 中心から角度angle、長さlengthの点
+    fn point_at(&self, angle: i32, length: i32) -> Point {
+        let x = length * sin_deg(90 - angle) / UNIT;
+        let y = length * sin_deg(angle) / UNIT;
+        self.center + Point::new(x, -y)
+    }
+
+    fn draw_needle<T>(
+        &self,
+        display: &mut T,
+        angle: i32,
+        color: Rgb565,
+    ) -> Result<(), T::Error>
+    where
+        T: DrawTarget<Rgb565>,
+    {
+        Line::new(self.center, self.point_at(angle, self.radius - 8))
+            .into_styled(PrimitiveStyle::with_stroke(color, 2))
+            .draw(display)
+    }
+
+    fn draw_scale<T>(&self, display: &mut T) -> Result<(), T::Error>
+    where
+        T: DrawTarget<Rgb565>,
+    {
+        let style = PrimitiveStyle::with_stroke(self.theme.muted, 1);
+        for i in 0..=TICKS {
+            let angle = 180 - i * 180 / TICKS;
+            Line::new(
+                self.point_at(angle, self.radius - 5),
+                self.point_at(angle, self.radius),
+            )
+            .into_styled(style)
+            .draw(display)?;
+        }
+        // 円弧は5度ごとの折れ線で描く
+        for angle in (0..180).step_by(5) {
+            Line::new(
+                self.point_at(angle, self.radius),
+                self.point_at(angle + 5, self.radius),
+            )
+            .into_styled(style)
+            .draw(display)?;
+        }
+        Ok(())
+    }
+}
+
+impl Widget for Gauge {
+    fn bounds(&self) -> Rectangle {
+        Rectangle::new(
+            self.center - Point::new(self.radius, self.radius),
+            self.center + Point::new(self.radius, 3),
+        )
+    }
+
+    fn is_dirty(&self) -> bool {
+        self.drawn != Some(self.angle())
+    }
+
+    fn invalidate(&mut self) {
+        self.drawn = None;
+    }
+
+    // 目盛りは最初だけ描き、針は前回の針を消してから描き直します
+    fn draw<T>(&mut self, display: &mut T) -> Result<bool, T::Error>
+    where
+        T: DrawTarget<Rgb565>,
+    {
+        if !self.is_dirty() {
+            return Ok(false);
+        }
+        match self.drawn {
+            Some(old) => {
+                self.draw_needle(display, old, self.theme.background)?
+            }
+            None => {
+                super::fill(display, self.bounds(), self.theme.background)?;
+                self.draw_scale(display)?;
+            }
+        }
+        let angle = self.angle();
+        self.draw_needle(display, angle, self.theme.accent)?;
+        Circle::new(self.center, 3)
+            .into_styled(PrimitiveStyle::with_fill(self.theme.foreground))
+            .draw(display)?;
+        self.drawn = Some(angle);
+        Ok(true)
+    }
+}
+
+// sin(degrees) * 1024 を整数だけで近似します (Bhaskara Iの近似式)
+// 浮動小数点の三角関数を使わないので、appフィーチャなしでも使えます
+pub fn sin_deg(degrees: i32) -> i32 {
+    let mut d = degrees % 360;
+    if d < 0 {
+        d += 360;
+    }
+    let (d, sign) = if d > 180 { (d - 180, -1) } else { (d, 1) };
+    let p = d * (180 - d);
+    sign * 4 * p * UNIT / (40500 - p)
+}
@@ -0,0 +1,189 @@
+// 文字列と数値の表示
+use super::{draw_text, fill, Alignment, Theme, Widget};
+use core::fmt::Write;
+use eg::fonts::Font;
+use eg::pixelcolor::Rgb565;
+use eg::prelude::*;
+use eg::primitives::Rectangle;
+use embedded_graphics as eg;
+use heapless::consts::*;
+use heapless::String;
+
+// 1行の文字列を表示するラベル
+pub struct Label<F> {
+    bounds: Rectangle,
+    font: F,
+    align: Alignment,
+    theme: Theme,
+    text: String<U40>,
+    dirty: bool,
+}
+
+impl<F: Font + Copy> Label<F> {
+    pub fn new(bounds: Rectangle, font: F, theme: Theme) -> Label<F> {
+        Label {
+            bounds,
+            font,
+            align: Alignment::Left,
+            theme,
+            text: String::new(),
+            dirty: true,
+        }
+    }
+
+    pub fn with_alignment(mut self, align: Alignment) -> Label<F> {
+        self.align = align;
+        self
+    }
+
+    pub fn text(&self) -> &str {
+        self.text.as_str()
+    }
+
+    // 文字列が変わったときだけ、次のdraw()で描画し直します
+    // 40文字を超える部分は切り捨てます
+    pub fn set_text(&mut self, text: &str) {
+        if self.text.as_str() == text {
+            return;
+        }
+        // heapless 0.5のString::clear()は範囲外を参照するので、空の文字列で置き換える
+        self.text = String::new();
+        for c in text.chars() {
+            if self.text.push(c).is_err() {
+                break;
+            }
+        }
+        self.dirty = true;
+    }
+
+    // 文字色を変えます (警告表示など)
+    pub fn set_color(&mut self, color: Rgb565) {
+        if self.theme.foreground != color {
+            self.theme.foreground = color;
+            self.dirty = true;
+        }
+    }
+}
+
+impl<F: Font + Copy> Widget for Label<F> {
+    fn bounds(&self) -> Rectangle {
+        self.bounds
+    }
+
+    fn is_dirty(&self) -> bool {
+        self.dirty
+    }
+
+    fn invalidate(&mut self) {
+        self.dirty = true;
+    }
+
+    fn draw<T>(&mut self, display: &mut T) -> Result<bool, T::Error>
+    where
+        T: DrawTarget<Rgb565>,
+    {
+        if !self.dirty {
+            return Ok(false);
+        }
+        fill(display, self.bounds, self.theme.background)?;
+        draw_text(
+            display,
+            self.text.as_str(),
+            self.font,
+            self.bounds,
+            self.align,
+            self.theme.foreground,
+        )?;
+        self.dirty = false;
+        Ok(true)
+    }
+}
+
+// 固定小数点の数値と単位を表示します
+// 例えば値1234、小数点以下2桁、単位"V"なら "12.34V" と表示します
+pub struct NumericReadout<F> {
+    label: Label<F>,
+    value: Option<i32>,
+    decimals: u8,
+    unit: &'static str,
+}
+
+impl<F: Font + Copy> NumericReadout<F> {
+    pub fn new(
+        bounds: Rectangle,
+        font: F,
+        theme: Theme,
+        decimals: u8,
+        unit: &'static str,
+    ) -> NumericReadout<F> {
+        NumericReadout {
+            label: Label::new(bounds, font, theme)
+                .with_alignment(Alignment::Right),
+            value: None,
+            decimals,
+            unit,
+        }
+    }
+
+    pub fn value(&self) -> Option<i32> {
+        self.value
+    }
+
+    pub fn set_value(&mut self, value: i32) {
+        if self.value == Some(value) {
+            return;
+        }
+        self.value = Some(value);
+        let mut text = String::<U40>::new();
+        format_fixed(&mut text, value, self.decimals).ok();
+        text.push_str(self.unit).ok();
+        self.label.set_text(text.as_str());
+    }
+
+    pub fn set_color(&mut self, color: Rgb565) {
+        self.label.set_color(color);
+    }
+}
+
+impl<F: Font + Copy> Widget for NumericReadout<F> {
+    fn bounds(&self) -> Rectangle {
+        self.label.bounds()
+    }
+
+    fn is_dirty(&self) -> bool {
+        self.label.is_dirty()
+    }
+
+    fn invalidate(&mut self) {
+        self.label.invalidate();
+    }
+
+    fn draw<T>(&mut self, display: &mut T) -> Result<bool, T::Error>
+    where
+        T: DrawTarget<Rgb565>,
+    {
+        self.label.draw(display)
+    }
+}
+
+// valueを10^decimalsで割った値として書き込みます
+pub fn format_fixed<W: Write>(
+    out: &mut W,
+    value: i32,
+    decimals: u8,
+) -> core::fmt::Result {
+    let sign = if value < 0 { "-" } else { "" };
+    let value = (value as i64).abs();
+    if decimals == 0 {
+        return write!(out, "{}{}", sign, value);
+    }
+    let scale = 10i64.pow(decimals as u32);
+    write!(
+        out,
+        "{}{}.{:0width$}",
+        sign,
+        value / scale,
+        value % scale,
+        width = decimals as usize
+    )
+}
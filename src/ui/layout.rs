@@ -0,0 +1,96 @@
+// ウィジェットを並べるための矩形の計算
+// 矩形は embedded-graphics と同じく、右下の点を含みます
+use eg::geometry::{Point, Size};
+use eg::primitives::Rectangle;
+use embedded_graphics as eg;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Alignment {
+    Left,
+    Center,
+    Right,
+}
+
+// 画面全体の矩形
+pub fn screen() -> Rectangle {
+    Rectangle::new(Point::zero(), Point::new(319, 239))
+}
+
+pub fn width(area: Rectangle) -> i32 {
+    area.bottom_right.x - area.top_left.x + 1
+}
+
+pub fn height(area: Rectangle) -> i32 {
+    area.bottom_right.y - area.top_left.y + 1
+}
+
+// 四辺をmarginだけ内側に縮めます
+pub fn inset(area: Rectangle, margin: i32) -> Rectangle {
+    Rectangle::new(
+        area.top_left + Point::new(margin, margin),
+        area.bottom_right - Point::new(margin, margin),
+    )
+}
+
+// 上からheightの部分と、残りの部分に分けます
+pub fn split_top(area: Rectangle, height: i32) -> (Rectangle, Rectangle) {
+    let y = area.top_left.y + height;
+    (
+        Rectangle::new(area.top_left, Point::new(area.bottom_right.x, y - 1)),
+        Rectangle::new(Point::new(area.top_left.x, y), area.bottom_right),
+    )
+}
+
+// 左からwidthの部分と、残りの部分に分けます
+pub fn split_left(area: Rectangle, width: i32) -> (Rectangle, Rectangle) {
+    let x = area.top_left.x + width;
+    (
+        Rectangle::new(area.top_left, Point::new(x - 1, area.bottom_right.y)),
+        Rectangle::new(Point::new(x, area.top_left.y), area.bottom_right),
+    )
+}
+
+// areaを縦にcount個に等分したうちのindex番目 (間隔gap [px])
+pub fn row(area: Rectangle, index: i32, count: i32, gap: i32) -> Rectangle {
+    let (top, bottom) =
+        divide(area.top_left.y, height(area), index, count, gap);
+    Rectangle::new(
+        Point::new(area.top_left.x, top),
+        Point::new(area.bottom_right.x, bottom),
+    )
+}
+
+// areaを横にcount個に等分したうちのindex番目 (間隔gap [px])
+pub fn column(area: Rectangle, index: i32, count: i32, gap: i32) -> Rectangle {
+    let (left, right) = divide(area.top_left.x, width(area), index, count, gap);
+    Rectangle::new(
+        Point::new(left, area.top_left.y),
+        Point::new(right, area.bottom_right.y),
+    )
+}
+
+// 端数は後ろの要素に割り振る
+fn divide(
+    start: i32,
+    length: i32,
+    index: i32,
+    count: i32,
+    gap: i32,
+) -> (i32, i32) {
+    let usable = length - gap * (count - 1);
+    let begin = start + usable * index / count + gap * index;
+    let end = start + usable * (index + 1) / count + gap * index - 1;
+    (begin, end)
+}
+
+// sizeの要素をareaの中に置いたときの左上の点 (縦は中央揃え)
+pub fn align(size: Size, area: Rectangle, alignment: Alignment) -> Point {
+    let free_x = width(area) - size.width as i32;
+    let x = match alignment {
+        Alignment::Left => 0,
+        Alignment::Center => free_x / 2,
+        Alignment::Right => free_x,
+    };
+    let y = (height(area) - size.height as i32) / 2;
+    area.top_left + Point::new(x, y)
+}
@@ -0,0 +1,155 @@
+// 縦に並んだ項目から1つを選ぶメニュー
+// 表示しきれない項目は、選択に合わせてスクロールします
+use super::{draw_text, fill, layout, Alignment, Theme, Widget};
+use eg::fonts::Font;
+use eg::pixelcolor::Rgb565;
+use eg::prelude::*;
+use eg::primitives::Rectangle;
+use embedded_graphics as eg;
+
+// 項目の上下の余白 [px]
+const ROW_PADDING: i32 = 4;
+
+pub struct ListMenu<'a, F> {
+    bounds: Rectangle,
+    font: F,
+    theme: Theme,
+    items: &'a [&'a str],
+    selected: usize,
+    // 一番上に表示している項目
+    first: usize,
+    // 前回描画したときの選択とスクロール位置。Noneなら全体を描画する
+    drawn: Option<(usize, usize)>,
+}
+
+impl<'a, F: Font + Copy> ListMenu<'a, F> {
+    pub fn new(
+        bounds: Rectangle,
+        font: F,
+        theme: Theme,
+        items: &'a [&'a str],
+    ) -> ListMenu<'a, F> {
+        ListMenu {
+            bounds,
+            font,
+            theme,
+            items,
+            selected: 0,
+            first: 0,
+            drawn: None,
+        }
+    }
+
+    pub fn selected(&self) -> usize {
+        self.selected
+    }
+
+    pub fn selected_item(&self) -> Option<&'a str> {
+        self.items.get(self.selected).copied()
+    }
+
+    fn row_height() -> i32 {
+        F::CHARACTER_SIZE.height as i32 + ROW_PADDING * 2
+    }
+
+    // 一度に表示できる項目の数
+    pub fn visible_rows(&self) -> usize {
+        (layout::height(self.bounds) / Self::row_height()).max(1) as usize
+    }
+
+    // 最後の次は最初に戻ります
+    pub fn select_next(&mut self) {
+        if !self.items.is_empty() {
+            self.select((self.selected + 1) % self.items.len());
+        }
+    }
+
+    pub fn select_previous(&mut self) {
+        if !self.items.is_empty() {
+            let len = self.items.len();
+            self.select((self.selected + len - 1) % len);
+        }
+    }
+
+    pub fn select(&mut self, index: usize) {
+        if index >= self.items.len() {
+            return;
+        }
+        self.selected = index;
+        let rows = self.visible_rows();
+        if index < self.first {
+            self.first = index;
+        } else if index >= self.first + rows {
+            self.first = index + 1 - rows;
+        }
+    }
+
+    fn draw_row<T>(&self, display: &mut T, index: usize) -> Result<(), T::Error>
+    where
+        T: DrawTarget<Rgb565>,
+    {
+        let top = self.bounds.top_left.y
+            + (index - self.first) as i32 * Self::row_height();
+        let area = Rectangle::new(
+            Point::new(self.bounds.top_left.x, top),
+            Point::new(
+                self.bounds.bottom_right.x,
+                top + Self::row_height() - 1,
+            ),
+        );
+        let (background, color) = if index == self.selected {
+            (self.theme.accent, self.theme.background)
+        } else {
+            (self.theme.background, self.theme.foreground)
+        };
+        fill(display, area, background)?;
+        draw_text(
+            display,
+            self.items[index],
+            self.font,
+            layout::inset(area, ROW_PADDING),
+            Alignment::Left,
+            color,
+        )
+    }
+}
+
+impl<'a, F: Font + Copy> Widget for ListMenu<'a, F> {
+    fn bounds(&self) -> Rectangle {
+        self.bounds
+    }
+
+    fn is_dirty(&self) -> bool {
+        self.drawn != Some((self.selected, self.first))
+    }
+
+    fn invalidate(&mut self) {
+        self.drawn = None;
+    }
+
+    // スクロールしていなければ、選択が変わった2行だけを描き直します
+    fn draw<T>(&mut self, display: &mut T) -> Result<bool, T::Error>
+    where
+        T: DrawTarget<Rgb565>,
+    {
+        if !self.is_dirty() {
+            return Ok(false);
+        }
+        match self.drawn {
+            Some((previous, first)) if first == self.first => {
+                self.draw_row(display, previous)?;
+                self.draw_row(display, self.selected)?;
+            }
+            _ => {
+                fill(display, self.bounds, self.theme.background)?;
+                let last =
+                    (self.first + self.visible_rows()).min(self.items.len());
+                for index in self.first..last {
+                    self.draw_row(display, index)?;
+                }
+            }
+        }
+        self.drawn = Some((self.selected, self.first));
+        Ok(true)
+    }
+}
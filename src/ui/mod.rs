@@ -0,0 +1,108 @@
+// 320x240の画面向けの小さなUIツールキット
+// ウィジェットは表示する値を保持していて、値が変わったときだけ描画し直します
+// 毎フレームdraw()を呼び出しても、変化のないウィジェットは何も描画しません
+use eg::fonts::{Font, Text};
+use eg::pixelcolor::Rgb565;
+use eg::prelude::*;
+use eg::primitives::Rectangle;
+use eg::style::{PrimitiveStyle, TextStyle};
+use embedded_graphics as eg;
+
+pub mod button;
+pub mod gauge;
+pub mod label;
+pub mod layout;
+pub mod menu;
+pub mod progress;
+pub mod status_bar;
+
+pub use self::button::{Button, Focus};
+pub use self::gauge::Gauge;
+pub use self::label::{Label, NumericReadout};
+pub use self::layout::Alignment;
+pub use self::menu::ListMenu;
+pub use self::progress::ProgressBar;
+pub use self::status_bar::StatusBar;
+
+// ウィジェットの配色
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Theme {
+    pub foreground: Rgb565,
+    pub background: Rgb565,
+    // 値のバーや選択中の項目など、強調する部分の色
+    pub accent: Rgb565,
+    // 枠や目盛りなど、目立たせない部分の色
+    pub muted: Rgb565,
+}
+
+impl Theme {
+    pub const DARK: Theme = Theme {
+        foreground: Rgb565::WHITE,
+        background: Rgb565::BLACK,
+        accent: Rgb565::new(0, 48, 31),
+        muted: Rgb565::new(10, 20, 10),
+    };
+}
+
+impl Default for Theme {
+    fn default() -> Theme {
+        Theme::DARK
+    }
+}
+
+pub trait Widget {
+    // ウィジェットが描画する範囲
+    fn bounds(&self) -> Rectangle;
+
+    // 次のdraw()で描画する必要があるかどうか
+    fn is_dirty(&self) -> bool;
+
+    // 画面を消去したときなどに呼び出して、次のdraw()で全体を描画させます
+    fn invalidate(&mut self);
+
+    // 変化があった場合だけ描画します。描画した場合はtrueを返します
+    fn draw<T>(&mut self, display: &mut T) -> Result<bool, T::Error>
+    where
+        T: DrawTarget<Rgb565>;
+}
+
+// 矩形を塗りつぶします
+pub fn fill<T>(
+    display: &mut T,
+    area: Rectangle,
+    color: Rgb565,
+) -> Result<(), T::Error>
+where
+    T: DrawTarget<Rgb565>,
+{
+    area.into_styled(PrimitiveStyle::with_fill(color))
+        .draw(display)
+}
+
+// 固定幅フォントで描画したときの文字列の幅 [px]
+pub fn text_width<F: Font>(text: &str) -> i32 {
+    text.chars().count() as i32 * F::CHARACTER_SIZE.width as i32
+}
+
+// areaの中に、縦は中央、横はalignに揃えて文字列を描画します
+pub fn draw_text<T, F>(
+    display: &mut T,
+    text: &str,
+    font: F,
+    area: Rectangle,
+    align: Alignment,
+    color: Rgb565,
+) -> Result<(), T::Error>
+where
+    T: DrawTarget<Rgb565>,
+    F: Font + Copy,
+{
+    let position = layout::align(
+        Size::new(text_width::<F>(text) as u32, F::CHARACTER_SIZE.height),
+        area,
+        align,
+    );
+    Text::new(text, position)
+        .into_styled(TextStyle::new(font, color))
+        .draw(display)
+}
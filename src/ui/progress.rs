@@ -0,0 +1,106 @@
+// 横向きのプログレスバー
+use super::{fill, layout, Theme, Widget};
+use eg::pixelcolor::Rgb565;
+use eg::prelude::*;
+use eg::primitives::Rectangle;
+use eg::style::PrimitiveStyleBuilder;
+use embedded_graphics as eg;
+
+pub struct ProgressBar {
+    bounds: Rectangle,
+    theme: Theme,
+    value: u32,
+    max: u32,
+    // 前回描画したバーの長さ [px]。Noneなら枠から描画する
+    drawn: Option<i32>,
+}
+
+impl ProgressBar {
+    pub fn new(bounds: Rectangle, theme: Theme, max: u32) -> ProgressBar {
+        ProgressBar {
+            bounds,
+            theme,
+            value: 0,
+            max: max.max(1),
+            drawn: None,
+        }
+    }
+
+    pub fn value(&self) -> u32 {
+        self.value
+    }
+
+    // maxを超える値はmaxとして扱います
+    pub fn set_value(&mut self, value: u32) {
+        self.value = value.min(self.max);
+    }
+
+    // 枠の内側の領域
+    fn inner(&self) -> Rectangle {
+        layout::inset(self.bounds, 2)
+    }
+
+    fn filled_width(&self) -> i32 {
+        let width = layout::width(self.inner()) as u64;
+        (width * self.value as u64 / self.max as u64) as i32
+    }
+}
+
+impl Widget for ProgressBar {
+    fn bounds(&self) -> Rectangle {
+        self.bounds
+    }
+
+    fn is_dirty(&self) -> bool {
+        self.drawn != Some(self.filled_width())
+    }
+
+    fn invalidate(&mut self) {
+        self.drawn = None;
+    }
+
+    // 前回から伸び縮みした部分だけを描画します
+    fn draw<T>(&mut self, display: &mut T) -> Result<bool, T::Error>
+    where
+        T: DrawTarget<Rgb565>,
+    {
+        if !self.is_dirty() {
+            return Ok(false);
+        }
+        let inner = self.inner();
+        let old = match self.drawn {
+            Some(old) => old,
+            None => {
+                self.bounds
+                    .into_styled(
+                        PrimitiveStyleBuilder::new()
+                            .stroke_color(self.theme.muted)
+                            .stroke_width(1)
+                            .fill_color(self.theme.background)
+                            .build(),
+                    )
+                    .draw(display)?;
+                0
+            }
+        };
+        let new = self.filled_width();
+        let (from, to, color) = if new > old {
+            (old, new, self.theme.accent)
+        } else {
+            (new, old, self.theme.background)
+        };
+        if to > from {
+            let left = inner.top_left.x;
+            fill(
+                display,
+                Rectangle::new(
+                    Point::new(left + from, inner.top_left.y),
+                    Point::new(left + to - 1, inner.bottom_right.y),
+                ),
+                color,
+            )?;
+        }
+        self.drawn = Some(new);
+        Ok(true)
+    }
+}
@@ -0,0 +1,112 @@
+// 画面上端のステータスバー
+// 左にタイトル、右に時刻や電池残量などの短い文字列を表示します
+use super::{draw_text, fill, layout, Alignment, Theme, Widget};
+use eg::fonts::{Font, Font6x8};
+use eg::pixelcolor::Rgb565;
+use eg::prelude::*;
+use eg::primitives::Rectangle;
+use embedded_graphics as eg;
+use heapless::consts::*;
+use heapless::String;
+
+// ステータスバーの高さ [px]
+pub const HEIGHT: i32 = 12;
+
+pub struct StatusBar {
+    bounds: Rectangle,
+    theme: Theme,
+    title: String<U32>,
+    status: String<U16>,
+    dirty: bool,
+}
+
+impl StatusBar {
+    // 画面上端に置きます。残りの領域は layout::split_top(screen, HEIGHT) で求めます
+    pub fn new(theme: Theme) -> StatusBar {
+        StatusBar {
+            bounds: layout::split_top(layout::screen(), HEIGHT).0,
+            theme,
+            title: String::new(),
+            status: String::new(),
+            dirty: true,
+        }
+    }
+
+    pub fn set_title(&mut self, title: &str) {
+        if set_string(&mut self.title, title) {
+            self.dirty = true;
+        }
+    }
+
+    pub fn set_status(&mut self, status: &str) {
+        if set_string(&mut self.status, status) {
+            self.dirty = true;
+        }
+    }
+}
+
+// 内容が変わった場合はtrueを返します。入りきらない部分は切り捨てます
+fn set_string<N>(string: &mut String<N>, text: &str) -> bool
+where
+    N: heapless::ArrayLength<u8>,
+{
+    if string.as_str() == text {
+        return false;
+    }
+    // heapless 0.5のString::clear()は範囲外を参照するので、空の文字列で置き換える
+    *string = String::new();
+    for c in text.chars() {
+        if string.push(c).is_err() {
+            break;
+        }
+    }
+    true
+}
+
+impl Widget for StatusBar {
+    fn bounds(&self) -> Rectangle {
+        self.bounds
+    }
+
+    fn is_dirty(&self) -> bool {
+        self.dirty
+    }
+
+    fn invalidate(&mut self) {
+        self.dirty = true;
+    }
+
+    fn draw<T>(&mut self, display: &mut T) -> Result<bool, T::Error>
+    where
+        T: DrawTarget<Rgb565>,
+    {
+        if !self.dirty {
+            return Ok(false);
+        }
+        fill(display, self.bounds, self.theme.muted)?;
+        let area = layout::inset(self.bounds, 2);
+        let margin = (HEIGHT - Font6x8::CHARACTER_SIZE.height as i32) / 2;
+        let area = Rectangle::new(
+            Point::new(area.top_left.x + margin, area.top_left.y),
+            Point::new(area.bottom_right.x - margin, area.bottom_right.y),
+        );
+        draw_text(
+            display,
+            self.title.as_str(),
+            Font6x8,
+            area,
+            Alignment::Left,
+            self.theme.foreground,
+        )?;
+        draw_text(
+            display,
+            self.status.as_str(),
+            Font6x8,
+            area,
+            Alignment::Right,
+            self.theme.foreground,
+        )?;
+        self.dirty = false;
+        Ok(true)
+    }
+}
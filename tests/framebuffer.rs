@@ -0,0 +1,340 @@
+// 描画した部分だけを転送するフレームバッファ (src/framebuffer.rs) のテスト
+// 転送された矩形を記録する画面に描画して、転送した範囲と結果を確認します
+// ホストで実行します
+//   $ cargo test --target x86_64-unknown-linux-gnu --tests
+use embedded_graphics::drawable::Pixel;
+use embedded_graphics::geometry::{Point, Size};
+use embedded_graphics::pixelcolor::raw::RawU16;
+use embedded_graphics::pixelcolor::{Rgb565, RgbColor};
+use embedded_graphics::DrawTarget;
+use wio_examples::framebuffer::*;
+
+const WIDTH: usize = 320;
+const HEIGHT: usize = 240;
+
+// 転送された矩形 (x0, y0, x1, y1) を記録する画面
+struct Screen {
+    pixels: Vec<u16>,
+    writes: Vec<(u16, u16, u16, u16)>,
+    // Someの場合は、その回数だけ転送に成功した後に失敗する
+    fail_after: Option<usize>,
+}
+
+impl Screen {
+    fn new() -> Screen {
+        Screen {
+            // 縦長 (240x320) の画面も書き込めるように、高さも幅と同じにしておく
+            pixels: vec![0; WIDTH * WIDTH],
+            writes: Vec::new(),
+            fail_after: None,
+        }
+    }
+
+    fn pixel(&self, x: usize, y: usize) -> Rgb565 {
+        Rgb565::from(RawU16::new(self.pixels[y * WIDTH + x]))
+    }
+
+    // 転送されたピクセルの数
+    fn written(&self) -> usize {
+        self.writes
+            .iter()
+            .map(|&(x0, y0, x1, y1)| {
+                (x1 - x0 + 1) as usize * (y1 - y0 + 1) as usize
+            })
+            .sum()
+    }
+}
+
+impl PixelSink for Screen {
+    type Error = ();
+
+    fn write_pixels(
+        &mut self,
+        x0: u16,
+        y0: u16,
+        x1: u16,
+        y1: u16,
+        pixels: &[u16],
+    ) -> Result<(), ()> {
+        if let Some(count) = self.fail_after.as_mut() {
+            if *count == 0 {
+                return Err(());
+            }
+            *count -= 1;
+        }
+        let width = (x1 - x0 + 1) as usize;
+        assert_eq!(pixels.len(), width * (y1 - y0 + 1) as usize);
+        for (i, &raw) in pixels.iter().enumerate() {
+            let (x, y) = (x0 as usize + i % width, y0 as usize + i / width);
+            self.pixels[y * WIDTH + x] = raw;
+        }
+        self.writes.push((x0, y0, x1, y1));
+        Ok(())
+    }
+}
+
+fn renderer() -> StripRenderer {
+    StripRenderer::new(Size::new(WIDTH as u32, HEIGHT as u32))
+}
+
+// 両端を含む矩形を塗りつぶす
+fn fill<T: DrawTarget<Rgb565>>(
+    target: &mut T,
+    (x0, y0): (i32, i32),
+    (x1, y1): (i32, i32),
+    color: Rgb565,
+) -> Result<(), T::Error> {
+    let points = (y0..=y1).flat_map(|y| (x0..=x1).map(move |x| (x, y)));
+    target.draw_iter(points.map(|(x, y)| Pixel(Point::new(x, y), color)))
+}
+
+// 最初のフレームを描画して、転送の記録を消しておく
+fn shown(screen: &mut Screen, renderer: &mut StripRenderer) {
+    renderer.render(screen, |_| Ok(())).unwrap();
+    screen.writes.clear();
+}
+
+#[test]
+fn first_frame_fills_background() {
+    let mut screen = Screen::new();
+    screen.pixels = vec![0xFFFF; WIDTH * HEIGHT];
+    let mut renderer = renderer();
+    // 最初は画面の内容が分からないので、描画しなかった部分も背景色で転送する
+    let flushed = renderer.render(&mut screen, |_| Ok(())).unwrap();
+    assert_eq!(flushed, 10 * 15);
+    assert_eq!(screen.written(), WIDTH * HEIGHT);
+    assert_eq!(screen.pixel(319, 239), Rgb565::BLACK);
+
+    // 何も変わらなければ何も転送しない
+    screen.writes.clear();
+    assert_eq!(renderer.render(&mut screen, |_| Ok(())).unwrap(), 0);
+    assert!(screen.writes.is_empty());
+}
+
+#[test]
+fn transfers_only_changed_tiles() {
+    let mut screen = Screen::new();
+    let mut renderer = renderer();
+    shown(&mut screen, &mut renderer);
+    let scene = |frame: &mut Frame<'_, Screen>| {
+        frame.draw_pixel(Pixel(Point::new(5, 3), Rgb565::RED))?;
+        frame.draw_pixel(Pixel(Point::new(300, 200), Rgb565::GREEN))
+    };
+    let flushed = renderer.render(&mut screen, scene).unwrap();
+    // 描画したピクセルを含むタイルが転送される
+    assert_eq!(flushed, 2);
+    assert_eq!(screen.writes, [(0, 0, 31, 15), (288, 192, 319, 207)]);
+    assert_eq!(screen.pixel(5, 3), Rgb565::RED);
+    assert_eq!(screen.pixel(300, 200), Rgb565::GREEN);
+
+    // 同じ内容を描き直しても転送しない
+    screen.writes.clear();
+    assert_eq!(renderer.render(&mut screen, scene).unwrap(), 0);
+    assert!(screen.writes.is_empty());
+
+    // 描画しなくなった部分は背景色に戻る
+    assert_eq!(renderer.render(&mut screen, |_| Ok(())).unwrap(), 2);
+    assert_eq!(screen.writes, [(0, 0, 31, 15), (288, 192, 319, 207)]);
+    assert_eq!(screen.pixel(5, 3), Rgb565::BLACK);
+    assert_eq!(screen.pixel(300, 200), Rgb565::BLACK);
+}
+
+#[test]
+fn moved_object_is_erased_without_clearing() {
+    let mut screen = Screen::new();
+    let mut renderer = renderer();
+    shown(&mut screen, &mut renderer);
+    for &x in &[0, 40] {
+        renderer
+            .render(&mut screen, |frame| {
+                fill(frame, (x, 20), (x + 9, 29), Rgb565::GREEN)
+            })
+            .unwrap();
+    }
+    // 前の位置は背景色で転送される
+    assert_eq!(
+        screen.writes,
+        [(0, 16, 31, 31), (0, 16, 31, 31), (32, 16, 63, 31)]
+    );
+    assert_eq!(screen.pixel(0, 20), Rgb565::BLACK);
+    assert_eq!(screen.pixel(45, 25), Rgb565::GREEN);
+}
+
+#[test]
+fn overdrawn_pixels_are_transferred_once() {
+    let mut screen = Screen::new();
+    let mut renderer = renderer();
+    shown(&mut screen, &mut renderer);
+    renderer
+        .render(&mut screen, |frame| {
+            // 塗りつぶしてから描き直す
+            fill(frame, (0, 0), (31, 15), Rgb565::BLUE)?;
+            fill(frame, (4, 4), (7, 7), Rgb565::WHITE)
+        })
+        .unwrap();
+    // タイル1枚分がまとめて1回だけ転送される
+    assert_eq!(screen.writes, [(0, 0, 31, 15)]);
+    assert_eq!(screen.pixel(0, 0), Rgb565::BLUE);
+    assert_eq!(screen.pixel(5, 5), Rgb565::WHITE);
+}
+
+#[test]
+fn pixels_outside_screen_are_ignored() {
+    let mut screen = Screen::new();
+    let mut renderer = StripRenderer::new(Size::new(100, 50));
+    renderer
+        .render(&mut screen, |frame| {
+            assert_eq!(frame.size(), Size::new(100, 50));
+            fill(frame, (-2, -2), (1, 1), Rgb565::RED)?;
+            fill(frame, (99, 49), (120, 60), Rgb565::RED)
+        })
+        .unwrap();
+    // 画面の端のタイルは、画面に収まる部分だけを転送する
+    assert_eq!(screen.written(), 100 * 50);
+    assert!(screen
+        .writes
+        .iter()
+        .all(|&(_, _, x1, y1)| x1 < 100 && y1 < 50));
+    assert_eq!(screen.pixel(0, 0), Rgb565::RED);
+    assert_eq!(screen.pixel(2, 2), Rgb565::BLACK);
+    assert_eq!(screen.pixel(99, 49), Rgb565::RED);
+    assert_eq!(screen.pixel(100, 49), Rgb565::BLACK);
+}
+
+#[test]
+fn flushes_when_tiles_run_out() {
+    let mut screen = Screen::new();
+    let mut renderer = renderer();
+    shown(&mut screen, &mut renderer);
+    // 全画面は150枚のタイルなので、途中で何度も転送する
+    // 転送した後に描き直したタイルは、描き直したピクセルだけを転送する
+    let scene = |frame: &mut Frame<'_, Screen>| {
+        fill(frame, (0, 0), (319, 239), Rgb565::BLUE)?;
+        fill(frame, (0, 0), (319, 0), Rgb565::RED)
+    };
+    let flushed = renderer.render(&mut screen, scene).unwrap();
+    assert_eq!(flushed, screen.writes.len());
+    for x in (0..320).step_by(32) {
+        assert!(screen.writes.contains(&(x, 0, x + 31, 0)));
+    }
+    assert_eq!(screen.pixel(0, 0), Rgb565::RED);
+    assert_eq!(screen.pixel(0, 1), Rgb565::BLUE);
+    assert_eq!(screen.pixel(319, 239), Rgb565::BLUE);
+
+    // 描き直したタイルは内容が分からないので次のフレームでも転送する
+    renderer.render(&mut screen, scene).unwrap();
+    assert_eq!(screen.pixel(0, 0), Rgb565::RED);
+    assert_eq!(screen.pixel(319, 239), Rgb565::BLUE);
+}
+
+#[test]
+fn unchanged_tiles_are_skipped_when_tiles_run_out() {
+    let mut screen = Screen::new();
+    let mut renderer = renderer();
+    shown(&mut screen, &mut renderer);
+    // タイルの数より多いタイルを、1枚ずつ描き終えながら描く
+    let scene = |frame: &mut Frame<'_, Screen>| {
+        for i in 0..TILES as i32 + 6 {
+            let (x, y) = (i % 10 * 32, i / 10 * 16);
+            fill(frame, (x, y), (x + 31, y + 15), Rgb565::BLUE)?;
+        }
+        Ok(())
+    };
+    renderer.render(&mut screen, scene).unwrap();
+    assert_eq!(screen.writes.len(), TILES + 6);
+
+    // 途中で転送したタイルも、内容が変わらなければ次のフレームで転送しない
+    screen.writes.clear();
+    assert_eq!(renderer.render(&mut screen, scene).unwrap(), 0);
+}
+
+#[test]
+fn clear_changes_background() {
+    let mut screen = Screen::new();
+    let mut renderer = renderer();
+    shown(&mut screen, &mut renderer);
+    renderer
+        .render(&mut screen, |frame| {
+            fill(frame, (0, 0), (9, 9), Rgb565::RED)?;
+            frame.clear(Rgb565::BLUE)?;
+            frame.draw_pixel(Pixel(Point::new(1, 1), Rgb565::WHITE))
+        })
+        .unwrap();
+    // 消去する前に描画したピクセルは転送しない
+    assert_eq!(screen.written(), WIDTH * HEIGHT);
+    assert_eq!(screen.pixel(0, 0), Rgb565::BLUE);
+    assert_eq!(screen.pixel(1, 1), Rgb565::WHITE);
+
+    // 背景色は次のフレームでもそのまま
+    screen.writes.clear();
+    renderer
+        .render(&mut screen, |frame| {
+            frame.draw_pixel(Pixel(Point::new(1, 1), Rgb565::WHITE))
+        })
+        .unwrap();
+    assert!(screen.writes.is_empty());
+}
+
+#[test]
+fn error_transfers_whole_screen_next_time() {
+    let mut screen = Screen::new();
+    let mut renderer = renderer();
+    shown(&mut screen, &mut renderer);
+    screen.fail_after = Some(0);
+    let result = renderer.render(&mut screen, |frame| {
+        fill(frame, (0, 0), (3, 3), Rgb565::RED)
+    });
+    assert_eq!(result, Err(()));
+
+    // 失敗したフレームのピクセルは転送せず、画面全体を転送し直す
+    screen.fail_after = None;
+    renderer
+        .render(&mut screen, |frame| {
+            frame.draw_pixel(Pixel(Point::new(100, 100), Rgb565::RED))
+        })
+        .unwrap();
+    assert_eq!(screen.written(), WIDTH * HEIGHT);
+    assert_eq!(screen.pixel(0, 0), Rgb565::BLACK);
+    assert_eq!(screen.pixel(100, 100), Rgb565::RED);
+
+    // invalidate()でも画面全体を転送し直す
+    screen.writes.clear();
+    renderer.invalidate();
+    renderer.render(&mut screen, |_| Ok(())).unwrap();
+    assert_eq!(screen.written(), WIDTH * HEIGHT);
+}
+
+#[test]
+fn set_size_transfers_whole_rotated_screen() {
+    let mut screen = Screen::new();
+    let mut renderer = renderer();
+    shown(&mut screen, &mut renderer);
+    // 縦長の画面 (240x320) にしたら、変わっていない部分も含めて画面全体を転送する
+    renderer.set_size(Size::new(240, 320));
+    renderer
+        .render(&mut screen, |frame| {
+            assert_eq!(frame.size(), Size::new(240, 320));
+            fill(frame, (230, 310), (239, 319), Rgb565::RED)
+        })
+        .unwrap();
+    assert_eq!(screen.written(), 240 * 320);
+    assert!(screen
+        .writes
+        .iter()
+        .all(|&(_, _, x1, y1)| x1 < 240 && y1 < 320));
+
+    // 次のフレームからは変わったタイルだけを転送する
+    screen.writes.clear();
+    renderer
+        .render(&mut screen, |frame| {
+            fill(frame, (230, 310), (239, 319), Rgb565::RED)
+        })
+        .unwrap();
+    assert!(screen.writes.is_empty());
+
+    // 最大サイズより大きい場合は収まる大きさにする
+    renderer.set_size(Size::new(400, 400));
+    assert_eq!(renderer.size(), Size::new(320, 240));
+    renderer.set_size(Size::new(200, 400));
+    assert_eq!(renderer.size(), Size::new(200, 320));
+}
@@ -0,0 +1,307 @@
+// Groveモジュールのドライバ (src/grove) のテスト
+// ホストで実行します
+//   $ cargo test --target x86_64-unknown-linux-gnu --tests
+use core::convert::Infallible;
+use embedded_hal::blocking::delay::{DelayMs, DelayUs};
+use embedded_hal::digital::v2::{InputPin, OutputPin};
+use embedded_hal_mock::delay::MockNoop;
+use embedded_hal_mock::i2c::{Mock as I2cMock, Transaction as I2cTransaction};
+use embedded_hal_mock::pin::{
+    Mock as PinMock, State, Transaction as PinTransaction,
+};
+use std::cell::RefCell;
+use std::rc::Rc;
+use wio_examples::grove::dht::{self, Dht, Kind};
+use wio_examples::grove::relay::Relay;
+use wio_examples::grove::sht3x::{self, Repeatability, Sht3x};
+use wio_examples::grove::ultrasonic::{self, Ultrasonic};
+
+// 1本の信号線を模擬します
+// ドライバがLowを出力していないときは、センサが出力する波形を返します
+// 波形は、ドライバが最後にHighを出力した (線を解放した) 時刻から始まります
+#[derive(Default)]
+struct Line {
+    now_us: u32,
+    driven_low: bool,
+    released_at: u32,
+    // センサが出力する (レベル, 長さ [us]) の列。終わった後はHigh
+    wave: Vec<(bool, u32)>,
+    // ドライバが出力したレベルと時刻
+    writes: Vec<(u32, bool)>,
+}
+
+impl Line {
+    fn new(wave: Vec<(bool, u32)>) -> Rc<RefCell<Line>> {
+        Rc::new(RefCell::new(Line {
+            wave,
+            ..Line::default()
+        }))
+    }
+
+    fn level(&self) -> bool {
+        if self.driven_low {
+            return false;
+        }
+        let mut t = self.now_us - self.released_at;
+        for &(level, length) in &self.wave {
+            if t < length {
+                return level;
+            }
+            t -= length;
+        }
+        true
+    }
+}
+
+struct LinePin(Rc<RefCell<Line>>);
+
+impl OutputPin for LinePin {
+    type Error = Infallible;
+
+    fn set_low(&mut self) -> Result<(), Infallible> {
+        let mut line = self.0.borrow_mut();
+        line.driven_low = true;
+        let now = line.now_us;
+        line.writes.push((now, false));
+        Ok(())
+    }
+
+    fn set_high(&mut self) -> Result<(), Infallible> {
+        let mut line = self.0.borrow_mut();
+        line.driven_low = false;
+        line.released_at = line.now_us;
+        let now = line.now_us;
+        line.writes.push((now, true));
+        Ok(())
+    }
+}
+
+impl InputPin for LinePin {
+    type Error = Infallible;
+
+    fn is_high(&self) -> Result<bool, Infallible> {
+        Ok(self.0.borrow().level())
+    }
+
+    fn is_low(&self) -> Result<bool, Infallible> {
+        Ok(!self.0.borrow().level())
+    }
+}
+
+// 待った時間だけLineの時刻を進めます
+struct LineDelay(Rc<RefCell<Line>>);
+
+impl DelayMs<u8> for LineDelay {
+    fn delay_ms(&mut self, ms: u8) {
+        self.0.borrow_mut().now_us += ms as u32 * 1000;
+    }
+}
+
+impl DelayUs<u16> for LineDelay {
+    fn delay_us(&mut self, us: u16) {
+        self.0.borrow_mut().now_us += us as u32;
+    }
+}
+
+// SHT3x
+
+fn sht3x_read(data: Vec<u8>) -> I2cTransaction {
+    I2cTransaction::read(sht3x::ADDRESS, data)
+}
+
+#[test]
+fn sht3x_measure_decodes_temperature_and_humidity() {
+    let mut mock = I2cMock::new(&[
+        // 繰り返し精度 高
+        I2cTransaction::write(sht3x::ADDRESS, vec![0x24, 0x00]),
+        // 0x6666 → 25.0[℃], 0x8000 → 50.0[%]
+        sht3x_read(vec![0x66, 0x66, 0x93, 0x80, 0x00, 0xA2]),
+        I2cTransaction::write(sht3x::ADDRESS, vec![0x24, 0x16]),
+        // 0x0000 → -45.0[℃]
+        sht3x_read(vec![0x00, 0x00, 0x81, 0x00, 0x00, 0x81]),
+    ]);
+    let mut sensor = Sht3x::new(mock.clone(), sht3x::ADDRESS);
+    let m = sensor
+        .measure(Repeatability::High, &mut MockNoop::new())
+        .unwrap();
+    assert!((m.temperature - 25.0).abs() < 0.01);
+    assert!((m.humidity - 50.0).abs() < 0.01);
+    let m = sensor
+        .measure(Repeatability::Low, &mut MockNoop::new())
+        .unwrap();
+    assert_eq!(m.temperature, -45.0);
+    assert_eq!(m.humidity, 0.0);
+    mock.done();
+}
+
+#[test]
+fn sht3x_crc() {
+    // データシートの例: 0xBEEFのCRCは0x92
+    let mut mock = I2cMock::new(&[
+        I2cTransaction::write(sht3x::ADDRESS, vec![0xF3, 0x2D]),
+        sht3x_read(vec![0xBE, 0xEF, 0x92]),
+        I2cTransaction::write(sht3x::ADDRESS, vec![0xF3, 0x2D]),
+        sht3x_read(vec![0xBE, 0xEF, 0x93]),
+    ]);
+    let mut sensor = Sht3x::new(mock.clone(), sht3x::ADDRESS);
+    assert_eq!(sensor.status().unwrap(), 0xBEEF);
+    assert!(matches!(sensor.status(), Err(sht3x::Error::Crc)));
+    mock.done();
+}
+
+#[test]
+fn sht3x_crc_mismatch_in_humidity() {
+    let mut mock = I2cMock::new(&[
+        I2cTransaction::write(sht3x::ADDRESS, vec![0x24, 0x0B]),
+        // 湿度のCRCだけが壊れている
+        sht3x_read(vec![0x66, 0x66, 0x93, 0x80, 0x10, 0xA2]),
+    ]);
+    let mut sensor = Sht3x::new(mock.clone(), sht3x::ADDRESS);
+    let result = sensor.measure(Repeatability::Medium, &mut MockNoop::new());
+    assert!(matches!(result, Err(sht3x::Error::Crc)));
+    mock.done();
+}
+
+#[test]
+fn sht3x_heater_and_reset_commands() {
+    let mut mock = I2cMock::new(&[
+        I2cTransaction::write(sht3x::ALTERNATE_ADDRESS, vec![0x30, 0x6D]),
+        I2cTransaction::write(sht3x::ALTERNATE_ADDRESS, vec![0x30, 0x66]),
+        I2cTransaction::write(sht3x::ALTERNATE_ADDRESS, vec![0x30, 0xA2]),
+    ]);
+    let mut sensor = Sht3x::new(mock.clone(), sht3x::ALTERNATE_ADDRESS);
+    sensor.set_heater(true).unwrap();
+    sensor.set_heater(false).unwrap();
+    sensor.reset(&mut MockNoop::new()).unwrap();
+    mock.done();
+}
+
+// DHT
+
+// センサの応答と40ビットのデータの波形
+fn dht_wave(data: [u8; 5]) -> Vec<(bool, u32)> {
+    let mut wave = vec![(true, 30), (false, 80), (true, 80)];
+    for byte in data.iter() {
+        for i in (0..8).rev() {
+            let one = byte & (1 << i) != 0;
+            wave.push((false, 50));
+            wave.push((true, if one { 70 } else { 27 }));
+        }
+    }
+    wave.push((false, 50));
+    wave
+}
+
+fn dht_read(
+    kind: Kind,
+    wave: Vec<(bool, u32)>,
+) -> (
+    Result<wio_examples::grove::Measurement, dht::Error<Infallible>>,
+    Rc<RefCell<Line>>,
+) {
+    let line = Line::new(wave);
+    let mut sensor = Dht::new(LinePin(line.clone()), kind).unwrap();
+    let result = sensor.read(&mut LineDelay(line.clone()));
+    (result, line)
+}
+
+#[test]
+fn dht11_decodes_bits() {
+    let (result, line) = dht_read(Kind::Dht11, dht_wave([55, 0, 24, 3, 82]));
+    let m = result.unwrap();
+    assert_eq!(m.humidity, 55.0);
+    assert!((m.temperature - 24.3).abs() < 0.01);
+    // スタート信号は20[ms]のLow
+    let writes = &line.borrow().writes;
+    assert_eq!(writes[1..], [(0, false), (20_000, true)]);
+}
+
+#[test]
+fn dht22_decodes_negative_temperature() {
+    // 65.2[%], -10.1[℃]
+    let (result, line) =
+        dht_read(Kind::Dht22, dht_wave([0x02, 0x8C, 0x80, 0x65, 0x73]));
+    let m = result.unwrap();
+    assert!((m.humidity - 65.2).abs() < 0.01);
+    assert!((m.temperature + 10.1).abs() < 0.01);
+    // スタート信号は2[ms]のLow
+    let writes = &line.borrow().writes;
+    assert_eq!(writes[1..], [(0, false), (2_000, true)]);
+}
+
+#[test]
+fn dht_checksum_failure() {
+    let (result, _) = dht_read(Kind::Dht11, dht_wave([55, 0, 24, 3, 83]));
+    assert!(matches!(result, Err(dht::Error::Checksum)));
+}
+
+#[test]
+fn dht_timeout_without_sensor() {
+    // 応答がなく、線はHighのまま
+    let (result, _) = dht_read(Kind::Dht22, vec![]);
+    assert!(matches!(result, Err(dht::Error::Timeout)));
+}
+
+// 超音波距離センサ
+
+// 1回呼ばれるごとに1[us]進む時計
+fn clock(line: &Rc<RefCell<Line>>) -> impl FnMut() -> u32 + '_ {
+    move || {
+        let mut line = line.borrow_mut();
+        line.now_us += 1;
+        line.now_us
+    }
+}
+
+#[test]
+fn ultrasonic_converts_echo_width_to_distance() {
+    // トリガの後、200[us]してから1000[us]のエコーが返る
+    let line = Line::new(vec![(false, 200), (true, 1000), (false, 1000)]);
+    let mut sensor = Ultrasonic::new(LinePin(line.clone())).unwrap();
+    let distance = sensor
+        .measure_mm(&mut LineDelay(line.clone()), clock(&line))
+        .unwrap();
+    // 1000[us] × 0.343[mm/us] / 2
+    assert!((distance - 171.5).abs() < 1.0, "{}", distance);
+    // 10[us]のトリガパルスを送ってから解放する
+    let writes = &line.borrow().writes;
+    assert_eq!(
+        writes[..],
+        [(0, false), (0, false), (2, true), (12, false), (12, true)]
+    );
+}
+
+#[test]
+fn ultrasonic_timeout_without_echo() {
+    let line = Line::new(vec![(false, 100_000)]);
+    let mut sensor = Ultrasonic::new(LinePin(line.clone())).unwrap();
+    let result = sensor.measure_mm(&mut LineDelay(line.clone()), clock(&line));
+    assert!(matches!(result, Err(ultrasonic::Error::Timeout)));
+    // 約25[ms]で諦める
+    assert!(line.borrow().now_us < 26_000);
+}
+
+// リレー
+
+#[test]
+fn relay_on_off_toggle() {
+    let mut pin = PinMock::new(&[
+        // 接点を開いた状態で初期化する
+        PinTransaction::set(State::Low),
+        PinTransaction::set(State::High),
+        PinTransaction::set(State::Low),
+        PinTransaction::set(State::High),
+        PinTransaction::set(State::Low),
+    ]);
+    let mut relay = Relay::new(pin.clone()).unwrap();
+    assert!(!relay.is_on());
+    relay.turn_on().unwrap();
+    assert!(relay.is_on());
+    relay.turn_off().unwrap();
+    assert!(!relay.is_on());
+    relay.toggle().unwrap();
+    assert!(relay.is_on());
+    relay.set(false).unwrap();
+    assert!(!relay.is_on());
+    pin.done();
+}
@@ -0,0 +1,78 @@
+// I2Cバスのスキャンと共有 (src/i2c_bus.rs) のテスト
+// I2Cのモックで、スキャンとプロキシが送受信するバイト列を確認します
+// ホストで実行します
+//   $ cargo test --target x86_64-unknown-linux-gnu --tests
+use embedded_hal::blocking::i2c::{Read, Write, WriteRead};
+use embedded_hal_mock::i2c::{Mock, Transaction};
+use embedded_hal_mock::MockError;
+use std::io::ErrorKind;
+use wio_examples::i2c_bus::*;
+use wio_examples::lis3dh::{Lis3dh, ADDRESS, DEVICE_ID};
+
+// 1バイトの読み出しで応答するかどうか
+fn probe(address: u8, ack: bool) -> Transaction {
+    let transaction = Transaction::read(address, vec![0]);
+    if ack {
+        transaction
+    } else {
+        transaction.with_error(MockError::Io(ErrorKind::Other))
+    }
+}
+
+#[test]
+fn scans_full_7bit_range() {
+    // 予約されたアドレス (0x00, 0x7F) も含めてすべて調べる
+    let devices = [0x00, 0x19, 0x44, 0x7F];
+    let expected: Vec<Transaction> = (0x00..=0x7F)
+        .map(|address| probe(address, devices.contains(&address)))
+        .collect();
+    let mut mock = Mock::new(&expected);
+    let found = scan(&mut mock);
+    assert_eq!(&found[..], &devices[..]);
+    mock.done();
+}
+
+#[test]
+fn proxies_forward_transfers() {
+    let mut mock = Mock::new(&[
+        Transaction::write(0x44, vec![0x24, 0x00]),
+        Transaction::read(0x44, vec![1, 2, 3]),
+        Transaction::write_read(0x19, vec![0x0F], vec![0x33]),
+        Transaction::write(0x44, vec![0x30, 0xA2])
+            .with_error(MockError::Io(ErrorKind::Other)),
+    ]);
+    let bus: LocalBus<Mock> = SharedBus::new(mock.clone());
+    let mut first = bus.acquire();
+    let mut second = bus.acquire();
+
+    first.write(0x44, &[0x24, 0x00]).unwrap();
+    let mut buffer = [0; 3];
+    first.read(0x44, &mut buffer).unwrap();
+    assert_eq!(buffer, [1, 2, 3]);
+    let mut id = [0];
+    second.write_read(0x19, &[0x0F], &mut id).unwrap();
+    assert_eq!(id, [0x33]);
+    // エラーはそのまま返す
+    assert!(first.write(0x44, &[0x30, 0xA2]).is_err());
+    mock.done();
+}
+
+#[test]
+fn driver_and_scanner_share_bus() {
+    let mut expected = vec![
+        // Lis3dh::new()
+        Transaction::write_read(ADDRESS, vec![0x0F], vec![DEVICE_ID]),
+        Transaction::write(ADDRESS, vec![0x20, 0x57]),
+        Transaction::write(ADDRESS, vec![0x21, 0x00]),
+        Transaction::write(ADDRESS, vec![0x23, 0x88]),
+    ];
+    expected.extend(
+        (0x00..=0x7F).map(|address| probe(address, address == ADDRESS)),
+    );
+    let mut mock = Mock::new(&expected);
+    let bus: LocalBus<Mock> = SharedBus::new(mock.clone());
+    let _accel = Lis3dh::new(bus.acquire(), ADDRESS).unwrap();
+    let found = scan(&mut bus.acquire());
+    assert_eq!(&found[..], &[ADDRESS]);
+    mock.done();
+}
@@ -0,0 +1,283 @@
+// ILI9341のコマンドドライバ (src/lcd.rs) のテスト
+// SPIとピンのモックで、送信するバイト列とDC/CSの切り替えを確認します
+// ホストで実行します
+//   $ cargo test --target x86_64-unknown-linux-gnu --tests
+use embedded_hal::blocking::spi;
+use embedded_hal_mock::delay::MockNoop;
+use embedded_hal_mock::pin::{
+    Mock as PinMock, State, Transaction as PinTransaction,
+};
+use embedded_hal_mock::spi::{Mock as SpiMock, Transaction as SpiTransaction};
+use embedded_hal_mock::MockError;
+use wio_examples::lcd::*;
+
+fn high() -> PinTransaction {
+    PinTransaction::set(State::High)
+}
+
+fn low() -> PinTransaction {
+    PinTransaction::set(State::Low)
+}
+
+// new()の後、count回のコマンドの送信でLowとHighを繰り返すピン
+fn toggles(count: usize) -> Vec<PinTransaction> {
+    let mut transactions = vec![high()];
+    for _ in 0..count {
+        transactions.push(low());
+        transactions.push(high());
+    }
+    transactions
+}
+
+struct Pins {
+    dc: PinMock,
+    cs: PinMock,
+    rst: PinMock,
+}
+
+impl Pins {
+    // count回のコマンドを送るときのDCとCS
+    fn new(count: usize) -> Pins {
+        Pins {
+            dc: PinMock::new(&toggles(count)),
+            cs: PinMock::new(&toggles(count)),
+            rst: PinMock::new(&[high()]),
+        }
+    }
+
+    fn done(&mut self) {
+        self.dc.done();
+        self.cs.done();
+        self.rst.done();
+    }
+}
+
+type MockLcd = Lcd<SpiMock, PinMock, PinMock, PinMock>;
+
+fn lcd(spi: &[SpiTransaction], pins: &Pins) -> (MockLcd, SpiMock) {
+    let spi = SpiMock::new(spi);
+    let lcd = Lcd::new(
+        spi.clone(),
+        pins.dc.clone(),
+        pins.cs.clone(),
+        pins.rst.clone(),
+    )
+    .unwrap();
+    (lcd, spi)
+}
+
+fn write(bytes: &[u8]) -> SpiTransaction {
+    SpiTransaction::write(bytes.to_vec())
+}
+
+#[test]
+fn init_resets_and_turns_on_display() {
+    let mut pins = Pins::new(5);
+    // ハードウェアリセット
+    pins.rst = PinMock::new(&[high(), low(), high()]);
+    let (mut lcd, mut spi) = lcd(
+        &[
+            write(&[0x01]),
+            write(&[0x36]),
+            // MV, BGR
+            write(&[0x28]),
+            write(&[0x3A]),
+            // RGB565
+            write(&[0x55]),
+            write(&[0x11]),
+            write(&[0x29]),
+        ],
+        &pins,
+    );
+    lcd.init(MemoryAccess::landscape(), &mut MockNoop::new())
+        .unwrap();
+    spi.done();
+    pins.done();
+}
+
+#[test]
+fn set_window_sends_column_and_page_addresses() {
+    let mut pins = Pins::new(2);
+    let (mut lcd, mut spi) = lcd(
+        &[
+            write(&[0x2A]),
+            write(&[0x00, 0x0A, 0x01, 0x35]),
+            write(&[0x2B]),
+            write(&[0x00, 0x14, 0x00, 0xDB]),
+        ],
+        &pins,
+    );
+    lcd.set_window(10, 20, 309, 219).unwrap();
+    // 始点が終点より後ろの場合は何も送らない
+    assert!(matches!(
+        lcd.set_window(11, 0, 10, 0),
+        Err(Error::InvalidArea)
+    ));
+    spi.done();
+    pins.done();
+}
+
+#[test]
+fn write_pixels_sends_big_endian_data() {
+    // CASET, RASETの後、Memory WriteでCSをLowにしたままピクセルを送る
+    let mut pins = Pins::new(3);
+    let mut pixels = vec![0x1234u16; 40];
+    pixels[0] = 0xF800;
+    let mut first = [0x12, 0x34].repeat(32);
+    first[0] = 0xF8;
+    first[1] = 0x00;
+    let (mut lcd, mut spi) = lcd(
+        &[
+            write(&[0x2A]),
+            write(&[0x00, 0x00, 0x00, 0x27]),
+            write(&[0x2B]),
+            write(&[0x00, 0x05, 0x00, 0x05]),
+            write(&[0x2C]),
+            // 64バイトずつ送る
+            write(&first),
+            write(&[0x12, 0x34].repeat(8)),
+        ],
+        &pins,
+    );
+    lcd.write_pixels(0, 5, 39, 5, &pixels).unwrap();
+    spi.done();
+    pins.done();
+}
+
+#[test]
+fn read_power_mode_discards_dummy_byte() {
+    let mut pins = Pins::new(1);
+    let (mut lcd, mut spi) = lcd(
+        &[
+            write(&[0x0A]),
+            // DCをHighにしてから、ダミーの1バイトと結果を受け取る
+            SpiTransaction::transfer(vec![0x00], vec![0xFF]),
+            SpiTransaction::transfer(vec![0x00], vec![0x9C]),
+        ],
+        &pins,
+    );
+    let mode = lcd.read_power_mode().unwrap();
+    assert_eq!(mode, PowerMode(0x9C));
+    assert!(mode.booster_on() && mode.sleep_out());
+    assert!(mode.normal_mode() && mode.display_on());
+    assert!(!mode.idle_mode() && !mode.partial_mode());
+    spi.done();
+    pins.done();
+}
+
+#[test]
+fn set_scroll_area_checks_total() {
+    let mut pins = Pins::new(1);
+    let (mut lcd, mut spi) = lcd(
+        &[write(&[0x33]), write(&[0x00, 0x10, 0x01, 0x20, 0x00, 0x10])],
+        &pins,
+    );
+    lcd.set_scroll_area(16, 288, 16).unwrap();
+    assert!(matches!(
+        lcd.set_scroll_area(16, 288, 0),
+        Err(Error::InvalidArea)
+    ));
+    // 合計がu16の範囲を超える場合もパニックせずにエラーにする
+    assert!(matches!(
+        lcd.set_scroll_area(u16::MAX, 321, 0),
+        Err(Error::InvalidArea)
+    ));
+    assert!(matches!(
+        lcd.set_scroll_area(0, u16::MAX, 321),
+        Err(Error::InvalidArea)
+    ));
+    spi.done();
+    pins.done();
+}
+
+// writes回だけ書き込みに成功し、その後は失敗するSPI
+struct FailingSpi {
+    writes: usize,
+}
+
+impl spi::Write<u8> for FailingSpi {
+    type Error = MockError;
+
+    fn write(&mut self, _: &[u8]) -> Result<(), MockError> {
+        if self.writes == 0 {
+            return Err(MockError::Io(std::io::ErrorKind::Other));
+        }
+        self.writes -= 1;
+        Ok(())
+    }
+}
+
+impl spi::Transfer<u8> for FailingSpi {
+    type Error = MockError;
+
+    fn transfer<'w>(&mut self, _: &'w mut [u8]) -> Result<&'w [u8], MockError> {
+        Err(MockError::Io(std::io::ErrorKind::Other))
+    }
+}
+
+fn failing_lcd(
+    writes: usize,
+    dc: &[PinTransaction],
+    cs: &[PinTransaction],
+) -> (Lcd<FailingSpi, PinMock, PinMock, PinMock>, Pins) {
+    let pins = Pins {
+        dc: PinMock::new(dc),
+        cs: PinMock::new(cs),
+        rst: PinMock::new(&[high()]),
+    };
+    let lcd = Lcd::new(
+        FailingSpi { writes },
+        pins.dc.clone(),
+        pins.cs.clone(),
+        pins.rst.clone(),
+    )
+    .unwrap();
+    (lcd, pins)
+}
+
+#[test]
+fn command_releases_cs_on_spi_error() {
+    // コマンドの送信に失敗した場合
+    let (mut lcd, mut pins) =
+        failing_lcd(0, &[high(), low()], &[high(), low(), high()]);
+    assert!(matches!(lcd.set_display_on(true), Err(Error::Spi(_))));
+    pins.done();
+
+    // パラメータの送信に失敗した場合
+    let (mut lcd, mut pins) =
+        failing_lcd(1, &[high(), low(), high()], &[high(), low(), high()]);
+    assert!(matches!(
+        lcd.set_pixel_format(PixelFormat::Bits16),
+        Err(Error::Spi(_))
+    ));
+    pins.done();
+}
+
+#[test]
+fn read_releases_cs_on_spi_error() {
+    let (mut lcd, mut pins) =
+        failing_lcd(1, &[high(), low(), high()], &[high(), low(), high()]);
+    assert!(matches!(lcd.read_power_mode(), Err(Error::Spi(_))));
+    pins.done();
+}
+
+#[test]
+fn write_pixels_releases_cs_on_spi_error() {
+    // CASET, RASET, Memory Writeの後、ピクセルデータの送信に失敗する
+    let (mut lcd, mut pins) = failing_lcd(5, &toggles(3), &toggles(3));
+    assert!(matches!(
+        lcd.write_pixels(0, 0, 1, 0, &[0, 0]),
+        Err(Error::Spi(_))
+    ));
+    pins.done();
+
+    // Memory Writeコマンドの送信に失敗する
+    let mut dc = toggles(2);
+    dc.push(low());
+    let (mut lcd, mut pins) = failing_lcd(4, &dc, &toggles(3));
+    assert!(matches!(
+        lcd.write_pixels(0, 0, 1, 0, &[0, 0]),
+        Err(Error::Spi(_))
+    ));
+    pins.done();
+}
@@ -0,0 +1,330 @@
+// LIS3DHドライバ (src/lis3dh) のテスト
+// I2Cのモックで、ドライバが送受信するバイト列を確認します
+// ホストで実行します
+//   $ cargo test --target x86_64-unknown-linux-gnu --tests
+use embedded_hal_mock::i2c::{Mock, Transaction};
+use embedded_hal_mock::MockError;
+use wio_examples::lis3dh::register::*;
+use wio_examples::lis3dh::*;
+
+const A: u8 = ADDRESS;
+
+// レジスタ1バイトの読み出し
+fn read(register: u8, value: u8) -> Transaction {
+    Transaction::write_read(A, vec![register], vec![value])
+}
+
+// レジスタ1バイトの書き込み
+fn write(register: u8, value: u8) -> Transaction {
+    Transaction::write(A, vec![register, value])
+}
+
+// new()が送るトランザクション (WHO_AM_Iの確認とデフォルトの設定)
+fn init() -> Vec<Transaction> {
+    vec![
+        read(0x0F, DEVICE_ID),
+        // ODR=100[Hz], X/Y/Z軸有効
+        write(0x20, 0x57),
+        write(0x21, 0x00),
+        // BDU, ±2g, 高分解能
+        write(0x23, 0x88),
+    ]
+}
+
+// 初期化済みのドライバと、その後に続くトランザクションのモック
+fn lis3dh(transactions: &[Transaction]) -> (Lis3dh<Mock>, Mock) {
+    let mut expected = init();
+    expected.extend_from_slice(transactions);
+    let mock = Mock::new(&expected);
+    let lis3dh = Lis3dh::new(mock.clone(), ADDRESS).unwrap();
+    (lis3dh, mock)
+}
+
+#[test]
+fn new_checks_device_id() {
+    let mut mock = Mock::new(&[read(0x0F, 0x32)]);
+    let result = Lis3dh::new(mock.clone(), ADDRESS);
+    assert!(matches!(result, Err(Error::InvalidDevice(0x32))));
+    mock.done();
+}
+
+#[test]
+fn new_writes_default_config() {
+    let (lis3dh, mut mock) = lis3dh(&[]);
+    assert_eq!(lis3dh.config(), Config::default());
+    mock.done();
+}
+
+#[test]
+fn configure_writes_control_registers() {
+    let (mut lis3dh, mut mock) = lis3dh(&[
+        // ODR=50[Hz]
+        write(0x20, 0x47),
+        // HPM=ノーマル (リセット), HPCF=1, FDS
+        write(0x21, 0x18),
+        // BDU, ±4g
+        write(0x23, 0x90),
+        // REFERENCEの読み出しでフィルタをリセットする
+        read(0x26, 0x00),
+        // 低消費電力モード
+        write(0x20, 0x8F),
+        write(0x21, 0x00),
+        write(0x23, 0xB0),
+    ]);
+    lis3dh
+        .configure(Config {
+            range: Range::G4,
+            data_rate: DataRate::Hz50,
+            mode: Mode::Normal,
+            high_pass: Some(HighPassFilter {
+                mode: HighPassMode::NormalWithReset,
+                cutoff: 1,
+            }),
+        })
+        .unwrap();
+    lis3dh
+        .configure(Config {
+            range: Range::G16,
+            data_rate: DataRate::Hz1600LowPower,
+            mode: Mode::LowPower,
+            high_pass: None,
+        })
+        .unwrap();
+    mock.done();
+}
+
+#[test]
+fn configure_rejects_invalid_combination() {
+    let (mut lis3dh, mut mock) = lis3dh(&[]);
+    let result = lis3dh.set_data_rate(DataRate::Hz1600LowPower);
+    assert!(matches!(result, Err(Error::InvalidConfig)));
+    // 何も書き込まず、設定も変わらない
+    assert_eq!(lis3dh.config(), Config::default());
+    mock.done();
+}
+
+#[test]
+fn read_g_uses_auto_increment() {
+    let (mut lis3dh, mut mock) = lis3dh(&[Transaction::write_read(
+        A,
+        vec![0xA8],
+        // 1000, -500, 0 [mg] (12ビットの値が左詰めになっている)
+        vec![0x80, 0x3E, 0xC0, 0xE0, 0x00, 0x00],
+    )]);
+    let accel = lis3dh.read_g().unwrap();
+    assert_eq!((accel.x, accel.y, accel.z), (1.0, -0.5, 0.0));
+    mock.done();
+}
+
+#[test]
+fn status_reads_status_register() {
+    let (mut lis3dh, mut mock) = lis3dh(&[read(0x27, 0x0F)]);
+    let status = lis3dh.status().unwrap();
+    assert!(status.zyxda());
+    assert!(status.xda() && status.yda() && status.zda());
+    assert!(!status.zyxor());
+    mock.done();
+}
+
+#[test]
+fn register_fields() {
+    let mut reg = CtrlReg1::default();
+    assert_eq!(reg.bits(), 0x07);
+    reg.set_odr(0b1001).set_lpen(true).set_xen(false);
+    assert_eq!(reg.bits(), 0x9E);
+    assert_eq!(reg.odr(), 0b1001);
+    // 幅を超えるビットは無視する
+    let mut reg = FifoCtrlReg::default();
+    reg.set_fth(0xFF);
+    assert_eq!(reg.bits(), 0x1F);
+    assert_eq!(<CtrlReg6 as Register>::ADDRESS, 0x25);
+}
+
+#[test]
+fn typed_register_access() {
+    let (mut lis3dh, mut mock) = lis3dh(&[
+        read(0x25, 0x02),
+        write(0x25, 0x82),
+        read(0x31, 0x41),
+        write(0x2E, 0x45),
+    ]);
+    // 読み出した値の他のビットは変えずに書き戻す
+    lis3dh
+        .modify(|reg: &mut CtrlReg6| {
+            reg.set_i2_click(true);
+        })
+        .unwrap();
+    let reg: Int1Src = lis3dh.read().unwrap();
+    assert!(reg.ia() && reg.xl() && !reg.xh());
+    lis3dh.write(FifoCtrlReg(0x45)).unwrap();
+    mock.done();
+}
+
+#[test]
+fn adc_and_temperature() {
+    let (mut lis3dh, mut mock) = lis3dh(&[
+        // CTRL_REG4のBDUを立てる
+        read(0x23, 0x08),
+        write(0x23, 0x88),
+        // ADC_EN, TEMP_EN
+        write(0x1F, 0xC0),
+        Transaction::write_read(A, vec![0x8C], vec![0x00, 0x12]),
+        Transaction::write_read(A, vec![0x88], vec![0xC0, 0xFF]),
+    ]);
+    lis3dh.enable_adc(true).unwrap();
+    assert_eq!(lis3dh.read_adc(3).unwrap(), 0x1200);
+    assert_eq!(lis3dh.read_adc(1).unwrap(), -64);
+    assert!(matches!(lis3dh.read_adc(0), Err(Error::InvalidConfig)));
+    assert!(matches!(lis3dh.read_adc(4), Err(Error::InvalidConfig)));
+    mock.done();
+}
+
+#[test]
+fn reboot_sets_boot_bit() {
+    let (mut lis3dh, mut mock) = lis3dh(&[read(0x24, 0x40), write(0x24, 0xC0)]);
+    lis3dh.reboot().unwrap();
+    mock.done();
+}
+
+#[test]
+fn enable_gestures_writes_thresholds_and_durations() {
+    let (mut lis3dh, mut mock) = lis3dh(&[
+        // FIFOのI1_WTMとFIFO_ENは残す
+        read(0x22, 0x04),
+        read(0x24, 0x40),
+        // ±2gでは16[mg/LSB]、100[Hz]では10[ms/LSB]
+        write(0x3A, 0x80 | 75),
+        write(0x3B, 5),
+        write(0x3C, 10),
+        write(0x3D, 30),
+        write(0x38, 0x3F),
+        write(0x32, 22),
+        write(0x33, 3),
+        write(0x30, 0x95),
+        write(0x36, 31),
+        write(0x37, 10),
+        write(0x34, 0xFF),
+        write(0x24, 0x4A),
+        write(0x22, 0xE4),
+        // 保持されていた割り込みを解除する
+        read(0x39, 0x00),
+        read(0x31, 0x00),
+        read(0x35, 0x00),
+    ]);
+    lis3dh
+        .enable_gestures(&GestureConfig {
+            tap: Some(TapConfig::default()),
+            free_fall: Some(FreeFallConfig::default()),
+            orientation: true,
+        })
+        .unwrap();
+    mock.done();
+}
+
+#[test]
+fn disable_gestures_clears_engines() {
+    let (mut lis3dh, mut mock) = lis3dh(&[
+        read(0x22, 0xE4),
+        read(0x24, 0x4A),
+        write(0x38, 0x00),
+        write(0x30, 0x00),
+        write(0x34, 0x00),
+        write(0x24, 0x40),
+        write(0x22, 0x04),
+        read(0x39, 0x00),
+        read(0x31, 0x00),
+        read(0x35, 0x00),
+    ]);
+    lis3dh.enable_gestures(&GestureConfig::default()).unwrap();
+    mock.done();
+}
+
+#[test]
+fn read_events_decodes_sources() {
+    let (mut lis3dh, mut mock) = lis3dh(&[
+        // ダブルクリック
+        read(0x39, 0x60),
+        read(0x31, 0x40),
+        // Z軸が上向き
+        read(0x35, 0x60),
+    ]);
+    let events = lis3dh.read_events().unwrap();
+    assert_eq!(
+        &events[..],
+        &[
+            Event::DoubleTap,
+            Event::FreeFall,
+            Event::Orientation(Orientation::FaceUp)
+        ]
+    );
+    mock.done();
+}
+
+#[test]
+fn enable_fifo_sets_mode_and_watermark() {
+    let (mut lis3dh, mut mock) = lis3dh(&[
+        write(0x2E, 0x00),
+        read(0x24, 0x00),
+        write(0x24, 0x40),
+        // Streamモード, ウォーターマーク16
+        write(0x2E, 0x90),
+        read(0x22, 0x00),
+        write(0x22, 0x04),
+    ]);
+    lis3dh.enable_fifo(FifoMode::Stream, 16).unwrap();
+    let result = lis3dh.enable_fifo(FifoMode::Stream, 32);
+    assert!(matches!(result, Err(Error::InvalidConfig)));
+    mock.done();
+}
+
+#[test]
+fn read_fifo_bursts_stored_samples() {
+    let (mut lis3dh, mut mock) = lis3dh(&[
+        // 3サンプル格納されている
+        read(0x2F, 0x03),
+        Transaction::write_read(
+            A,
+            vec![0xA8],
+            vec![
+                0x10, 0x00, 0x00, 0x00, 0x00, 0x00, //
+                0x20, 0x00, 0x00, 0x00, 0x00, 0x00, //
+                0x00, 0x00, 0x00, 0x00, 0x80, 0x3E,
+            ],
+        ),
+        // 空
+        read(0x2F, 0x20),
+    ]);
+    let batch = lis3dh.read_fifo(1_000_000).unwrap();
+    assert_eq!(batch.len(), 3);
+    let samples: Vec<Sample> = batch.iter().collect();
+    // 100[Hz]なので10[ms]間隔
+    let timestamps: Vec<u32> = samples.iter().map(|s| s.timestamp_us).collect();
+    assert_eq!(timestamps, [980_000, 990_000, 1_000_000]);
+    assert_eq!(batch.raw(0).x, 1);
+    assert_eq!(batch.raw(1).x, 2);
+    assert_eq!(samples[2].accel.z, 1.0);
+
+    let batch = lis3dh.read_fifo(1_010_000).unwrap();
+    assert!(batch.is_empty());
+    mock.done();
+}
+
+#[test]
+fn read_fifo_after_overrun_reads_full_depth() {
+    let (mut lis3dh, mut mock) = lis3dh(&[
+        read(0x2F, 0x5F),
+        Transaction::write_read(A, vec![0xA8], vec![0; 6 * FIFO_DEPTH]),
+    ]);
+    let batch = lis3dh.read_fifo(0).unwrap();
+    assert_eq!(batch.len(), FIFO_DEPTH);
+    mock.done();
+}
+
+#[test]
+fn i2c_error_is_returned() {
+    let (mut lis3dh, mut mock) =
+        lis3dh(&[Transaction::write_read(A, vec![0x27], vec![0x00])
+            .with_error(MockError::Io(std::io::ErrorKind::Other))]);
+    assert!(matches!(lis3dh.status(), Err(Error::I2c(_))));
+    mock.done();
+}
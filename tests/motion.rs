@@ -0,0 +1,150 @@
+// 傾きの推定と歩数計 (src/motion.rs) のテスト
+// tests/data/*.csv の加速度の波形 (50Hz) を入力します
+// 波形は実機で記録したものではなく、LIS3DHの出力を模して合成したものです
+// ホストで実行します
+//   $ cargo test --target x86_64-unknown-linux-gnu --tests --features app
+use accelerometer::vector::F32x3;
+use wio_examples::motion::*;
+
+const WALK_16_STEPS: &str = include_str!("data/walk_16_steps.csv");
+const WALK_PAUSE: &str = include_str!("data/walk_pause.csv");
+const SHAKE: &str = include_str!("data/shake.csv");
+
+// 許容する傾きの誤差 [rad] (約1度)
+const ATTITUDE_TOLERANCE: f32 = 0.02;
+
+// "x,y,z"の行を読みます。#で始まる行はコメントです
+fn samples(csv: &str) -> Vec<F32x3> {
+    csv.lines()
+        .filter(|line| !line.starts_with('#') && !line.trim().is_empty())
+        .map(|line| {
+            let v: Vec<f32> =
+                line.split(',').map(|s| s.trim().parse().unwrap()).collect();
+            F32x3::new(v[0], v[1], v[2])
+        })
+        .collect()
+}
+
+// "# 本体の傾き: pitch P rad, roll R rad"の行から傾きを読みます
+fn expected_attitude(csv: &str) -> Attitude {
+    let line = csv.lines().find(|line| line.contains("pitch")).unwrap();
+    let words: Vec<&str> = line.split_whitespace().collect();
+    let value = |name: &str| {
+        let i = words.iter().position(|&w| w == name).unwrap();
+        words[i + 1].parse().unwrap()
+    };
+    Attitude {
+        pitch: value("pitch"),
+        roll: value("roll"),
+    }
+}
+
+fn count_steps(csv: &str) -> u32 {
+    let mut counter = StepCounter::new(StepCounterConfig::default());
+    for accel in samples(csv) {
+        counter.update(accel);
+    }
+    counter.steps()
+}
+
+fn assert_attitude(actual: Attitude, expected: Attitude) {
+    assert!(
+        (actual.pitch - expected.pitch).abs() < ATTITUDE_TOLERANCE
+            && (actual.roll - expected.roll).abs() < ATTITUDE_TOLERANCE,
+        "{:?} != {:?}",
+        actual,
+        expected
+    );
+}
+
+#[test]
+fn attitude_from_gravity() {
+    let level = attitude(F32x3::new(0.0, 0.0, 1.0));
+    assert_eq!(level, Attitude::default());
+    // 手前に90度起こすと、Y軸が重力の方向を向く
+    let standing = attitude(F32x3::new(0.0, 1.0, 0.0));
+    assert!((standing.roll_degrees() - 90.0).abs() < 0.01);
+    let pitched = attitude(F32x3::new(-1.0, 0.0, 0.0));
+    assert!((pitched.pitch_degrees() - 90.0).abs() < 0.01);
+}
+
+#[test]
+fn estimator_tracks_attitude_while_walking() {
+    for &csv in &[WALK_16_STEPS, WALK_PAUSE, SHAKE] {
+        let expected = expected_attitude(csv);
+        let mut estimator = AttitudeEstimator::new(0.1, 0.3);
+        assert_eq!(estimator.attitude(), None);
+        let mut worst = 0.0f32;
+        for (i, accel) in samples(csv).into_iter().enumerate() {
+            let estimate = estimator.update(accel).unwrap();
+            // 最初の1秒は収束するのを待つ
+            if i >= 50 {
+                worst = worst
+                    .max((estimate.pitch - expected.pitch).abs())
+                    .max((estimate.roll - expected.roll).abs());
+            }
+        }
+        // 歩いたり振ったりしている間も、推定値は大きくずれない
+        assert!(worst < 0.1, "{}", worst);
+        // 最後は静止しているので、ほぼ正確な値になる
+        assert_attitude(estimator.attitude().unwrap(), expected);
+    }
+}
+
+#[test]
+fn estimator_ignores_large_acceleration() {
+    let mut estimator = AttitudeEstimator::new(0.1, 0.3);
+    let level = estimator.update(F32x3::new(0.0, 0.0, 1.0)).unwrap();
+    // 1.5[g]のサンプルは使わない
+    let estimate = estimator.update(F32x3::new(1.5, 0.0, 0.0)).unwrap();
+    assert_eq!(estimate, level);
+}
+
+#[test]
+fn counts_steps_of_continuous_walk() {
+    // 歩き始めの1歩目から数える
+    assert_eq!(count_steps(WALK_16_STEPS), 16);
+}
+
+#[test]
+fn counts_steps_across_pause() {
+    // 12歩 + 10歩。途切れた後の3歩は連続した歩数に達しないので数えない
+    assert_eq!(count_steps(WALK_PAUSE), 12 + 10);
+}
+
+#[test]
+fn counts_steps_with_noise() {
+    // 合成した波形に、各軸±0.05[g]の雑音 (線形合同法による疑似乱数) を加える
+    let mut seed = 12345u32;
+    let mut noise = || {
+        seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12345);
+        ((seed >> 16) as f32 / 65535.0 - 0.5) * 0.1
+    };
+    let mut counter = StepCounter::new(StepCounterConfig::default());
+    for accel in samples(WALK_16_STEPS) {
+        counter.update(F32x3::new(
+            accel.x + noise(),
+            accel.y + noise(),
+            accel.z + noise(),
+        ));
+    }
+    // 実際の16歩から1歩以内
+    let steps = counter.steps();
+    assert!((15..=17).contains(&steps), "{}", steps);
+}
+
+#[test]
+fn shaking_is_not_counted() {
+    assert_eq!(count_steps(SHAKE), 0);
+}
+
+#[test]
+fn reset_clears_steps() {
+    let mut counter = StepCounter::new(StepCounterConfig::default());
+    for accel in samples(WALK_16_STEPS) {
+        counter.update(accel);
+    }
+    assert!(counter.steps() > 0);
+    counter.reset();
+    assert_eq!(counter.steps(), 0);
+}
@@ -0,0 +1,79 @@
+// 掃引する折れ線グラフ (src/plot.rs) のテスト
+// ホストで実行します
+//   $ cargo test --target x86_64-unknown-linux-gnu --tests
+use embedded_graphics::drawable::Pixel;
+use embedded_graphics::geometry::{Point, Size};
+use embedded_graphics::pixelcolor::Rgb565;
+use embedded_graphics::DrawTarget;
+use wio_examples::plot::Plot;
+
+// 描画されたピクセルの数を数える画面
+struct Counter {
+    pixels: usize,
+}
+
+impl DrawTarget<Rgb565> for Counter {
+    type Error = core::convert::Infallible;
+
+    fn draw_pixel(&mut self, _: Pixel<Rgb565>) -> Result<(), Self::Error> {
+        self.pixels += 1;
+        Ok(())
+    }
+
+    fn size(&self) -> Size {
+        Size::new(320, 240)
+    }
+}
+
+fn plot() -> Plot {
+    Plot::new(Point::new(0, 0), Size::new(320, 200))
+}
+
+#[test]
+fn draws_labels_of_large_values() {
+    let mut plot = plot();
+    let mut screen = Counter { pixels: 0 };
+    // 目盛りの文字列が長くなる値
+    plot.push([1.0e30, -1.0e30, 0.0]);
+    plot.draw(&mut screen).unwrap();
+    plot.push([f32::MAX, f32::MIN, 0.0]);
+    plot.invalidate();
+    plot.draw(&mut screen).unwrap();
+    assert!(screen.pixels > 0);
+}
+
+#[test]
+fn draws_only_new_columns() {
+    let mut plot = plot();
+    let mut screen = Counter { pixels: 0 };
+    plot.push([0.01, 0.0, -0.01]);
+    plot.draw(&mut screen).unwrap();
+    // 最初はグラフ全体を塗りつぶす
+    assert!(screen.pixels >= 320 * 200);
+
+    // 追加したサンプルがなければ何も描かない
+    screen.pixels = 0;
+    plot.draw(&mut screen).unwrap();
+    assert_eq!(screen.pixels, 0);
+
+    // 縦軸が変わらなければ、追加した列と空白の列だけを描く
+    plot.push([0.01, 0.0, -0.01]);
+    plot.draw(&mut screen).unwrap();
+    assert!(screen.pixels > 0);
+    assert!(screen.pixels < 320 * 200 / 10);
+}
+
+#[test]
+fn min_span_change_redraws_everything() {
+    let mut plot = plot();
+    let mut screen = Counter { pixels: 0 };
+    plot.push([0.01, 0.0, -0.01]);
+    plot.draw(&mut screen).unwrap();
+
+    screen.pixels = 0;
+    plot.set_min_span(10.0);
+    plot.draw(&mut screen).unwrap();
+    assert!(screen.pixels >= 320 * 200);
+    let (min, max) = plot.range();
+    assert!(max - min >= 10.0);
+}
@@ -0,0 +1,120 @@
+// 傾きによる画面の自動回転 (src/rotation.rs) のテスト
+// 加速度の列は実機で記録したものではなく、角度から合成したものです
+// ホストで実行します
+//   $ cargo test --target x86_64-unknown-linux-gnu --tests
+use accelerometer::vector::F32x3;
+use embedded_graphics::geometry::Size;
+use wio_examples::lcd::MemoryAccess;
+use wio_examples::rotation::*;
+
+// 画面の面内で、上端からdegrees度だけ左に倒した向きの重力 (1[g])
+fn tilted(degrees: f32) -> F32x3 {
+    let radians = degrees.to_radians();
+    F32x3::new(radians.cos(), radians.sin(), 0.0)
+}
+
+// 加速度の列を入力して、向きが変わったときの傾きを集めます
+fn changes(rotate: &mut AutoRotate, samples: &[F32x3]) -> Vec<Tilt> {
+    samples.iter().filter_map(|&s| rotate.update(s)).collect()
+}
+
+fn rotate(initial: Tilt) -> AutoRotate {
+    AutoRotate::new(AutoRotateConfig::default(), initial)
+}
+
+#[test]
+fn detects_each_tilt() {
+    let rotate = rotate(Tilt::TopUp);
+    assert_eq!(rotate.detect(tilted(0.0)), Some(Tilt::TopUp));
+    assert_eq!(rotate.detect(tilted(180.0)), Some(Tilt::BottomUp));
+    assert_eq!(rotate.detect(tilted(90.0)), Some(Tilt::LeftUp));
+    assert_eq!(rotate.detect(tilted(-90.0)), Some(Tilt::RightUp));
+    // 机に置いたときは判定しない
+    assert_eq!(rotate.detect(F32x3::new(0.1, 0.2, 0.97)), None);
+}
+
+#[test]
+fn rotates_after_stable_samples() {
+    let mut rotate = rotate(Tilt::TopUp);
+    let stable = AutoRotateConfig::default().stable_samples as usize;
+    for _ in 1..stable {
+        assert_eq!(rotate.update(tilted(90.0)), None);
+    }
+    assert_eq!(rotate.update(tilted(90.0)), Some(Tilt::LeftUp));
+    assert_eq!(rotate.tilt(), Tilt::LeftUp);
+    // 同じ向きのままなら、もう変わらない
+    assert!(changes(&mut rotate, &[tilted(90.0); 20]).is_empty());
+
+    // 逆さまにすると、縦横が同じでも回転する
+    let upside_down = changes(&mut rotate, &[tilted(-90.0); 20]);
+    assert_eq!(upside_down, [Tilt::RightUp]);
+}
+
+#[test]
+fn no_flapping_near_45_degrees() {
+    // 縦横の境目 (45度) の前後で揺れ続けても、どちらの向きからも回転しない
+    let samples: Vec<F32x3> = (0..200)
+        .map(|i| tilted(if i % 2 == 0 { 39.0 } else { 51.0 }))
+        .collect();
+    for &initial in &[Tilt::TopUp, Tilt::LeftUp] {
+        let mut rotate = rotate(initial);
+        assert!(changes(&mut rotate, &samples).is_empty());
+        assert_eq!(rotate.tilt(), initial);
+    }
+
+    // ヒステリシスを超えて傾ければ回転する
+    let mut rotate = rotate(Tilt::TopUp);
+    assert_eq!(changes(&mut rotate, &[tilted(60.0); 10]), [Tilt::LeftUp]);
+    assert_eq!(changes(&mut rotate, &[tilted(30.0); 10]), [Tilt::TopUp]);
+}
+
+#[test]
+fn no_flapping_near_flat() {
+    // 縦持ちの重力が判定の閾値 (0.5[g]) の前後で揺れる場合、
+    // 閾値を下回るたびに数え直すので回転しない
+    let samples: Vec<F32x3> = (0..200)
+        .map(|i| {
+            let y = if i % 3 == 0 { 0.45 } else { 0.55 };
+            F32x3::new(0.1, y, 0.83)
+        })
+        .collect();
+    let mut rotate = rotate(Tilt::TopUp);
+    assert!(changes(&mut rotate, &samples).is_empty());
+    assert_eq!(rotate.tilt(), Tilt::TopUp);
+}
+
+#[test]
+fn short_shakes_do_not_rotate() {
+    // 別の向きが続く回数がstable_samplesに届かなければ回転しない
+    let stable = AutoRotateConfig::default().stable_samples as usize;
+    let mut samples = Vec::new();
+    for i in 0..40 {
+        let other = if i % 2 == 0 { 90.0 } else { -90.0 };
+        samples.extend(vec![tilted(other); stable - 1]);
+        samples.push(tilted(0.0));
+    }
+    // 途中で別の向きに変わった場合も数え直す
+    samples.extend(vec![tilted(90.0); stable - 1]);
+    samples.extend(vec![tilted(-90.0); stable - 1]);
+    let mut rotate = rotate(Tilt::TopUp);
+    assert!(changes(&mut rotate, &samples).is_empty());
+    assert_eq!(rotate.tilt(), Tilt::TopUp);
+}
+
+#[test]
+fn memory_access_matches_tilt() {
+    // TopUpはLcd::init()で使う横長の設定
+    assert_eq!(Tilt::TopUp.memory_access(), MemoryAccess::landscape());
+    let bits = |tilt: Tilt| tilt.memory_access().bits();
+    assert_eq!(bits(Tilt::TopUp), 0x28);
+    // 上下逆さまは行と列の両方を反転する
+    assert_eq!(bits(Tilt::BottomUp), 0xE8);
+    // 縦持ちは行と列を入れ替えない
+    assert_eq!(bits(Tilt::LeftUp), 0x88);
+    assert_eq!(bits(Tilt::RightUp), 0x48);
+
+    assert_eq!(Tilt::TopUp.size(), Size::new(320, 240));
+    assert_eq!(Tilt::BottomUp.size(), Size::new(320, 240));
+    assert_eq!(Tilt::LeftUp.size(), Size::new(240, 320));
+    assert_eq!(Tilt::RightUp.size(), Size::new(240, 320));
+}
@@ -0,0 +1,103 @@
+// スペクトラム解析 (src/spectrum.rs) のテスト
+// ホストで実行します
+//   $ cargo test --target x86_64-unknown-linux-gnu --tests --features app
+use wio_examples::spectrum::*;
+
+const SAMPLING_RATE: f32 = 16_000.0;
+const WINDOWS: [Window; 4] = [
+    Window::Hann,
+    Window::Hamming,
+    Window::Blackman,
+    Window::FlatTop,
+];
+
+// 周波数frequency [Hz]、振幅amplitudeの正弦波をpoints個作る
+fn sine(frequency: f32, amplitude: f32, points: usize) -> Vec<f32> {
+    (0..points)
+        .map(|i| {
+            let t = i as f32 / SAMPLING_RATE;
+            amplitude * (2.0 * std::f32::consts::PI * frequency * t).sin()
+        })
+        .collect()
+}
+
+fn peak(values: &[f32]) -> usize {
+    let mut peak = 0;
+    for (i, &value) in values.iter().enumerate() {
+        if value > values[peak] {
+            peak = i;
+        }
+    }
+    peak
+}
+
+fn analyze(points: usize, window: Window, samples: &[f32]) -> Vec<f32> {
+    let mut spectrum = Spectrum::new(points, window);
+    let mut db = vec![0.0; spectrum.bins()];
+    spectrum.magnitudes_db(samples, &mut db);
+    db
+}
+
+#[test]
+fn supported_points() {
+    for &points in &[64, 128, 256, 512, 1024] {
+        assert!(is_supported(points));
+    }
+    for &points in &[0, 32, 100, 2048] {
+        assert!(!is_supported(points));
+    }
+}
+
+#[test]
+fn sine_peaks_in_its_bin() {
+    for &points in &[64, 128, 256, 512, 1024] {
+        for &window in &WINDOWS {
+            // 10番目のビンの中心の周波数
+            let frequency = 10.0 * SAMPLING_RATE / points as f32;
+            let db = analyze(points, window, &sine(frequency, 1.0, points));
+            assert_eq!(peak(&db), 10, "{} points, {:?}", points, window);
+            // 振幅1の正弦波は0[dB]になる
+            assert!(db[10].abs() < 0.5, "{} points, {:?}", points, window);
+        }
+    }
+}
+
+#[test]
+fn peak_follows_frequency() {
+    let spectrum = Spectrum::new(256, Window::Hann);
+    for &bin in &[3, 32, 100] {
+        let frequency = spectrum.bin_frequency(bin, SAMPLING_RATE);
+        let db = analyze(256, Window::Hann, &sine(frequency, 0.5, 256));
+        assert_eq!(peak(&db), bin);
+    }
+}
+
+#[test]
+fn flat_top_measures_amplitude_between_bins() {
+    // ビンとビンの間の周波数でも、フラットトップ窓なら振幅がほぼ正確に出る
+    let frequency = 20.5 * SAMPLING_RATE / 512.0;
+    let db = analyze(512, Window::FlatTop, &sine(frequency, 0.5, 512));
+    let expected = to_db(0.5);
+    assert!((db[peak(&db)] - expected).abs() < 0.2);
+    // Hann窓では1.4[dB]ほど小さくなる
+    let db = analyze(512, Window::Hann, &sine(frequency, 0.5, 512));
+    assert!(db[peak(&db)] < expected - 1.0);
+}
+
+#[test]
+fn windows_suppress_leakage() {
+    // 遠くのビンへの漏れは、Blackman窓のほうがHann窓より小さい
+    let frequency = 40.5 * SAMPLING_RATE / 256.0;
+    let samples = sine(frequency, 1.0, 256);
+    let hann = analyze(256, Window::Hann, &samples);
+    let blackman = analyze(256, Window::Blackman, &samples);
+    assert!(blackman[80] < hann[80]);
+    assert!(hann[80] < -60.0);
+}
+
+#[test]
+fn db_conversion() {
+    assert!(to_db(1.0).abs() < 0.01);
+    assert!((to_db(0.1) + 20.0).abs() < 0.01);
+    assert_eq!(to_db(0.0), MIN_DB);
+}
@@ -0,0 +1,260 @@
+// ストップウォッチのモデル (src/stopwatch.rs) のテスト
+// ホストで実行します
+//   $ cargo test --target x86_64-unknown-linux-gnu --tests
+use wio_examples::input::{Event, Key};
+use wio_examples::laps::Session;
+use wio_examples::stopwatch::*;
+
+const SECOND: u32 = TICKS_PER_SECOND;
+
+fn press(stopwatch: &mut Stopwatch, key: Key, now: u32) -> Output {
+    stopwatch.update(Some(Event::Pressed(key)), now)
+}
+
+#[test]
+fn start_stop_and_resume() {
+    let mut sw = Stopwatch::new();
+    let output = press(&mut sw, Key::Button3, 0);
+    assert_eq!(sw.state(), State::Running);
+    assert!(output.beep.is_some());
+    assert_eq!(sw.display_ms(SECOND * 2), 2000);
+
+    let output = press(&mut sw, Key::Button2, SECOND * 2);
+    assert_eq!(sw.state(), State::Idle);
+    assert!(output.save);
+    // 停止中は時間が進まない
+    assert_eq!(sw.display_ms(SECOND * 10), 2000);
+
+    press(&mut sw, Key::Button3, SECOND * 10);
+    assert_eq!(sw.display_ms(SECOND * 11), 3000);
+    assert_eq!(sw.display_text(SECOND * 11).as_str(), "00:03.00");
+}
+
+#[test]
+fn stop_button_is_ignored_while_idle() {
+    let mut sw = Stopwatch::new();
+    let output = press(&mut sw, Key::Button2, 0);
+    assert_eq!(sw.state(), State::Idle);
+    assert_eq!(output, Output::default());
+}
+
+#[test]
+fn clear_only_while_stopped() {
+    let mut sw = Stopwatch::new();
+    press(&mut sw, Key::Button3, 0);
+    press(&mut sw, Key::Button1, SECOND);
+    assert_eq!(sw.state(), State::Running);
+    press(&mut sw, Key::Press, SECOND);
+    press(&mut sw, Key::Button2, SECOND * 2);
+
+    let output = press(&mut sw, Key::Button1, SECOND * 3);
+    assert!(output.save);
+    assert!(output.laps_changed);
+    assert_eq!(sw.display_ms(SECOND * 3), 0);
+    assert!(sw.laps().is_empty());
+}
+
+#[test]
+fn time_keeps_counting_when_the_clock_wraps() {
+    let mut sw = Stopwatch::new();
+    let start = u32::MAX - SECOND / 2;
+    press(&mut sw, Key::Button3, start);
+    assert_eq!(sw.display_ms(start.wrapping_add(SECOND)), 1000);
+}
+
+#[test]
+fn format_time_rolls_over_after_100_minutes() {
+    assert_eq!(format_time(0).as_str(), "00:00.00");
+    assert_eq!(format_time(83_456).as_str(), "01:23.45");
+    assert_eq!(format_time(100 * 60 * 1000 + 1500).as_str(), "00:01.50");
+}
+
+#[test]
+fn timestamp_adds_counter_to_overflows() {
+    assert_eq!(timestamp(0, 0, false), 0);
+    assert_eq!(timestamp(3, 100, false), 3 * TICKS_PER_OVERFLOW + 100);
+    // 1/100秒 (約328tick) の違いが表示に出る
+    let t = timestamp(16, 328, false);
+    assert_eq!(format_time(ticks_to_ms(t)).as_str(), "00:01.01");
+}
+
+#[test]
+fn timestamp_with_pending_overflow() {
+    // カウンタが一周した後に読んだ場合は、数えていないオーバーフローを足す
+    assert_eq!(timestamp(3, 5, true), 4 * TICKS_PER_OVERFLOW + 5);
+    // 一周する直前に読んだ場合は足さない
+    let count = TICKS_PER_OVERFLOW - 1;
+    assert_eq!(timestamp(3, count, true), 3 * TICKS_PER_OVERFLOW + count);
+    // 時刻は一周しても構わない
+    let last = u32::MAX / TICKS_PER_OVERFLOW;
+    assert_eq!(timestamp(last + 1, 0, false), 0);
+}
+
+#[test]
+fn overflow_period_is_cc0_plus_one() {
+    // MFRQモードの周期はCC0 + 1なので、CC0は2047にする
+    assert_eq!(TC3_CC0, 2047);
+    assert_eq!(TICKS_PER_OVERFLOW * 16, TICKS_PER_SECOND);
+}
+
+#[test]
+fn timing_modes() {
+    // オーバーフローの回数だけの場合は、カウンタの値を無視する
+    assert_eq!(Timing::Overflows.timestamp(16, 328, true), SECOND);
+    assert_eq!(
+        Timing::SubTick.timestamp(16, 328, false),
+        timestamp(16, 328, false)
+    );
+    assert_eq!(
+        Timing::SubTick.timestamp(3, 5, true),
+        4 * TICKS_PER_OVERFLOW + 5
+    );
+}
+
+#[test]
+fn laps_with_best_and_worst() {
+    let mut sw = Stopwatch::new();
+    press(&mut sw, Key::Button3, 0);
+    for &split in &[10, 18, 30] {
+        let output = press(&mut sw, Key::Press, SECOND * split);
+        assert!(output.laps_changed);
+    }
+    let laps = sw.laps();
+    assert_eq!(laps.len(), 3);
+    assert_eq!(laps.lap(1), 8000);
+    assert_eq!(laps.split(2), 30_000);
+    assert_eq!(laps.best(), Some(1));
+    assert_eq!(laps.worst(), Some(2));
+}
+
+#[test]
+fn lap_list_scrolls_within_range() {
+    let mut sw = Stopwatch::new();
+    press(&mut sw, Key::Button3, 0);
+    for i in 1..=(LAP_ROWS as u32 + 2) {
+        press(&mut sw, Key::Press, SECOND * i);
+    }
+    assert!(!press(&mut sw, Key::Up, SECOND * 10).laps_changed);
+    assert!(press(&mut sw, Key::Down, SECOND * 10).laps_changed);
+    assert!(press(&mut sw, Key::Down, SECOND * 10).laps_changed);
+    assert!(!press(&mut sw, Key::Down, SECOND * 10).laps_changed);
+    assert_eq!(sw.scroll(), 2);
+    // 新しいラップを記録すると先頭に戻る
+    press(&mut sw, Key::Press, SECOND * 11);
+    assert_eq!(sw.scroll(), 0);
+}
+
+#[test]
+fn session_round_trip() {
+    let mut sw = Stopwatch::new();
+    press(&mut sw, Key::Button3, 0);
+    press(&mut sw, Key::Press, SECOND * 5);
+    press(&mut sw, Key::Button2, SECOND * 7);
+
+    let record = sw.session(SECOND * 7).encode();
+    let session = Session::decode(&record).unwrap();
+    assert_eq!(session.elapsed_ms, 7000);
+
+    let mut restored = Stopwatch::new();
+    restored.restore(session);
+    assert_eq!(restored.state(), State::Idle);
+    assert_eq!(restored.display_ms(0), 7000);
+    assert_eq!(restored.laps().split(0), 5000);
+
+    // 消去されたフラッシュや壊れたデータは読み込まない
+    assert!(Session::decode(&[0xff; 512]).is_none());
+    let mut broken = record;
+    broken[20] ^= 1;
+    assert!(Session::decode(&broken).is_none());
+}
+
+#[test]
+fn countdown_time_entry() {
+    let mut sw = Stopwatch::new();
+    press(&mut sw, Key::Press, 0);
+    assert_eq!(sw.mode(), Mode::Countdown);
+    assert_eq!(sw.cursor(), Some(TimeField::Minutes));
+    assert_eq!(sw.display_ms(0), DEFAULT_COUNTDOWN_S * 1000);
+
+    press(&mut sw, Key::Down, 0);
+    press(&mut sw, Key::Right, 0);
+    assert_eq!(sw.cursor(), Some(TimeField::Seconds));
+    press(&mut sw, Key::Down, 0);
+    // 2:00 の秒を減らすと 2:59 になる (桁ごとに一周する)
+    assert_eq!(sw.countdown_seconds(), 2 * 60 + 59);
+    assert_eq!(sw.display_text(0).as_str(), "02:59.00");
+
+    assert_eq!(adjust_time(0, TimeField::Minutes, -1), 99 * 60);
+    assert_eq!(adjust_time(MAX_COUNTDOWN_S, TimeField::Seconds, 1), 99 * 60);
+}
+
+#[test]
+fn countdown_pause_resume_and_alarm() {
+    let mut sw = Stopwatch::new();
+    press(&mut sw, Key::Press, 0);
+    for _ in 0..3 {
+        press(&mut sw, Key::Down, 0);
+    }
+    press(&mut sw, Key::Right, 0);
+    for _ in 0..10 {
+        press(&mut sw, Key::Up, 0);
+    }
+    assert_eq!(sw.countdown_seconds(), 10);
+
+    let output = press(&mut sw, Key::Button3, 0);
+    assert!(output.redraw);
+    assert_eq!(sw.cursor(), None);
+    assert_eq!(sw.display_ms(SECOND * 4), 6000);
+
+    // 一時停止中は時間を変更できない
+    let output = press(&mut sw, Key::Button2, SECOND * 4);
+    assert!(!output.save);
+    press(&mut sw, Key::Up, SECOND * 5);
+    assert_eq!(sw.countdown_seconds(), 10);
+    assert_eq!(sw.display_ms(SECOND * 20), 6000);
+
+    press(&mut sw, Key::Button3, SECOND * 20);
+    assert!(!sw.update(None, SECOND * 25).redraw);
+    let output = sw.update(None, SECOND * 26);
+    assert!(output.redraw);
+    assert_eq!(sw.state(), State::Alarm);
+    assert_eq!(sw.title(), "TIME UP!");
+    assert_eq!(sw.display_ms(SECOND * 27), 0);
+    assert!(sw.buzzer_on(SECOND * 26));
+    assert!(!sw.buzzer_on(SECOND * 26 + SECOND / 16));
+
+    // どのボタンでもアラームを止めて、設定した時間に戻る
+    press(&mut sw, Key::Button1, SECOND * 27);
+    assert_eq!(sw.state(), State::Idle);
+    assert!(!sw.buzzer_on(SECOND * 27));
+    assert_eq!(sw.display_ms(SECOND * 27), 10_000);
+    assert_eq!(sw.cursor(), Some(TimeField::Seconds));
+}
+
+#[test]
+fn alarm_stops_by_itself() {
+    let mut sw = Stopwatch::new();
+    press(&mut sw, Key::Press, 0);
+    press(&mut sw, Key::Button3, 0);
+    sw.update(None, DEFAULT_COUNTDOWN_S * SECOND);
+    assert_eq!(sw.state(), State::Alarm);
+    let end = (DEFAULT_COUNTDOWN_S + ALARM_DURATION_S) * SECOND;
+    sw.update(None, end - 1);
+    assert_eq!(sw.state(), State::Alarm);
+    sw.update(None, end);
+    assert_eq!(sw.state(), State::Idle);
+}
+
+#[test]
+fn modes_keep_their_own_time() {
+    let mut sw = Stopwatch::new();
+    press(&mut sw, Key::Button3, 0);
+    press(&mut sw, Key::Button2, SECOND * 5);
+    press(&mut sw, Key::Press, SECOND * 5);
+    assert_eq!(sw.display_ms(SECOND * 5), DEFAULT_COUNTDOWN_S * 1000);
+    // カウントダウン中でも、保存するのはストップウォッチの計測結果
+    assert_eq!(sw.session(SECOND * 5).elapsed_ms, 5000);
+    press(&mut sw, Key::Press, SECOND * 6);
+    assert_eq!(sw.mode(), Mode::Stopwatch);
+    assert_eq!(sw.display_ms(SECOND * 6), 5000);
+}
@@ -0,0 +1,31 @@
+// ウィジェット (src/ui) のテスト
+// ホストで実行します
+//   $ cargo test --target x86_64-unknown-linux-gnu --tests
+use embedded_graphics::fonts::Font8x16;
+use embedded_graphics::geometry::Point;
+use embedded_graphics::primitives::Rectangle;
+use wio_examples::ui::{Label, StatusBar, Theme, Widget};
+
+#[test]
+fn label_replaces_text() {
+    let bounds = Rectangle::new(Point::new(0, 0), Point::new(99, 15));
+    let mut label = Label::new(bounds, Font8x16, Theme::DARK);
+    label.set_text("first");
+    label.set_text("second");
+    assert_eq!(label.text(), "second");
+    assert!(label.is_dirty());
+
+    // 40文字を超える部分は切り捨てる
+    let long = "0123456789".repeat(5);
+    label.set_text(&long);
+    assert_eq!(label.text(), &long[..40]);
+}
+
+#[test]
+fn status_bar_replaces_status() {
+    let mut bar = StatusBar::new(Theme::DARK);
+    bar.set_title("title");
+    bar.set_status("REC");
+    bar.set_status("FREEZE");
+    assert!(bar.is_dirty());
+}
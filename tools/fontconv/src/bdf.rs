@@ -0,0 +1,93 @@
+// BDF形式のビットマップフォントを読み込みます
+// 文字コードがUnicode (ISO10646) かASCII/ISO8859-1のフォントだけに対応します
+// ビルドスクリプトからも読み込むので、標準ライブラリだけを使います
+use super::encode::{FontData, Glyph};
+
+fn numbers(fields: &[&str]) -> Result<Vec<i32>, String> {
+    fields
+        .iter()
+        .map(|f| f.parse().map_err(|_| format!("invalid number: {}", f)))
+        .collect()
+}
+
+// wantedがtrueを返す文字だけを読み込みます
+pub fn parse<F>(source: &str, mut wanted: F) -> Result<FontData, String>
+where
+    F: FnMut(char) -> bool,
+{
+    let mut font = FontData {
+        ascent: 0,
+        descent: 0,
+        line_height: 0,
+        glyphs: Vec::new(),
+        kerning: Vec::new(),
+    };
+    let mut lines = source.lines();
+    let mut codepoint: Option<u32> = None;
+    let mut advance = 0;
+    let mut bbx = [0; 4];
+    while let Some(line) = lines.next() {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        match fields.as_slice() {
+            ["FONT_ASCENT", value] => font.ascent = numbers(&[value])?[0],
+            ["FONT_DESCENT", value] => font.descent = numbers(&[value])?[0],
+            ["CHARSET_REGISTRY", registry] => {
+                let registry = registry.trim_matches('"').to_uppercase();
+                if !["ISO10646", "ISO8859", "ASCII"].contains(&&*registry) {
+                    return Err(format!("unsupported charset: {}", registry));
+                }
+            }
+            ["STARTCHAR", ..] => {
+                codepoint = None;
+                advance = 0;
+                bbx = [0; 4];
+            }
+            ["ENCODING", value, ..] => {
+                // -1 は文字コードが割り当てられていない
+                let value = numbers(&[value])?[0];
+                codepoint = if value >= 0 { Some(value as u32) } else { None };
+            }
+            ["DWIDTH", dx, ..] => advance = numbers(&[dx])?[0],
+            ["BBX", rest @ ..] if rest.len() == 4 => {
+                let v = numbers(rest)?;
+                bbx = [v[0], v[1], v[2], v[3]];
+            }
+            ["BITMAP"] => {
+                let [width, height, x_offset, y_offset] = bbx;
+                let mut coverage = Vec::new();
+                for _ in 0..height {
+                    let row = lines.next().ok_or("unexpected end of file")?;
+                    let bits = u128::from_str_radix(row.trim(), 16)
+                        .map_err(|_| format!("invalid bitmap row: {}", row))?;
+                    let total = row.trim().len() as i32 * 4;
+                    for x in 0..width.min(total) {
+                        let set = bits >> (total - 1 - x) & 1 != 0;
+                        coverage.push(if set { 255 } else { 0 });
+                    }
+                    // 行のデータが幅より短ければ、残りは透明にする
+                    let filled =
+                        coverage.len() + (width - total).max(0) as usize;
+                    coverage.resize(filled, 0);
+                }
+                let c = codepoint.and_then(std::char::from_u32);
+                if let Some(c) = c.filter(|&c| wanted(c)) {
+                    font.glyphs.push(Glyph {
+                        codepoint: c as u32,
+                        width: width as usize,
+                        height: height as usize,
+                        x_offset,
+                        y_offset: y_offset + height,
+                        advance,
+                        coverage,
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+    if font.ascent == 0 && font.descent == 0 {
+        return Err("FONT_ASCENT and FONT_DESCENT are missing".into());
+    }
+    font.line_height = font.ascent + font.descent;
+    Ok(font)
+}
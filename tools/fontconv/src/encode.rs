@@ -0,0 +1,160 @@
+// フォントを src/font/mod.rs の形式に書き出します
+// ビルドスクリプトからも読み込むので、標準ライブラリだけを使います
+
+pub struct Glyph {
+    pub codepoint: u32,
+    pub width: usize,
+    pub height: usize,
+    // ベースラインの左端から見た、ビットマップの左端の位置
+    pub x_offset: i32,
+    // ベースラインから見た、ビットマップの上端の高さ
+    pub y_offset: i32,
+    pub advance: i32,
+    // 1ピクセルごとの濃さ (0〜255)
+    pub coverage: Vec<u8>,
+}
+
+pub struct FontData {
+    pub ascent: i32,
+    pub descent: i32,
+    pub line_height: i32,
+    pub glyphs: Vec<Glyph>,
+    // (左の文字, 右の文字, 調整量 [px])
+    pub kerning: Vec<(u32, u32, i32)>,
+}
+
+// 濃さをbppビットに丸めます
+fn quantize(coverage: u8, bpp: u8) -> u8 {
+    let max = (1u32 << bpp) - 1;
+    ((coverage as u32 * max + 127) / 255) as u8
+}
+
+// 丸めた後のビットマップから、周囲の透明な行と列を取り除きます
+fn crop(glyph: &Glyph, bpp: u8) -> (Vec<u8>, usize, usize, i32, i32) {
+    let alpha: Vec<u8> =
+        glyph.coverage.iter().map(|&c| quantize(c, bpp)).collect();
+    let at = |x: usize, y: usize| alpha[y * glyph.width + x];
+    let rows: Vec<usize> = (0..glyph.height)
+        .filter(|&y| (0..glyph.width).any(|x| at(x, y) != 0))
+        .collect();
+    let columns: Vec<usize> = (0..glyph.width)
+        .filter(|&x| (0..glyph.height).any(|y| at(x, y) != 0))
+        .collect();
+    let (top, left) = match (rows.first(), columns.first()) {
+        (Some(&top), Some(&left)) => (top, left),
+        // 空白などの何も描かない文字
+        _ => return (Vec::new(), 0, 0, 0, 0),
+    };
+    let (bottom, right) = (*rows.last().unwrap(), *columns.last().unwrap());
+    let mut cropped = Vec::new();
+    for y in top..=bottom {
+        for x in left..=right {
+            cropped.push(at(x, y));
+        }
+    }
+    (
+        cropped,
+        right - left + 1,
+        bottom - top + 1,
+        glyph.x_offset + left as i32,
+        glyph.y_offset - top as i32,
+    )
+}
+
+fn to_u8(value: i32, what: &str) -> Result<u8, String> {
+    if (0..=u8::MAX as i32).contains(&value) {
+        Ok(value as u8)
+    } else {
+        Err(format!("{} out of range: {}", what, value))
+    }
+}
+
+fn to_i8(value: i32, what: &str) -> Result<u8, String> {
+    if (i8::MIN as i32..=i8::MAX as i32).contains(&value) {
+        Ok(value as i8 as u8)
+    } else {
+        Err(format!("{} out of range: {}", what, value))
+    }
+}
+
+fn to_u16(value: i32, what: &str) -> Result<[u8; 2], String> {
+    if (0..=u16::MAX as i32).contains(&value) {
+        Ok((value as u16).to_le_bytes())
+    } else {
+        Err(format!("{} out of range: {}", what, value))
+    }
+}
+
+// bppビット (1, 2, 4) のフォントに変換します
+pub fn encode(font: &FontData, bpp: u8) -> Result<Vec<u8>, String> {
+    if bpp != 1 && bpp != 2 && bpp != 4 {
+        return Err(format!("unsupported bits per pixel: {}", bpp));
+    }
+    let mut glyphs: Vec<&Glyph> = font.glyphs.iter().collect();
+    glyphs.sort_by_key(|g| g.codepoint);
+    glyphs.dedup_by_key(|g| g.codepoint);
+    let index_of = |codepoint: u32| {
+        glyphs
+            .binary_search_by_key(&codepoint, |g| g.codepoint)
+            .ok()
+    };
+    let mut kerning: Vec<(usize, usize, i32)> = font
+        .kerning
+        .iter()
+        .filter(|&&(_, _, adjust)| adjust != 0)
+        .filter_map(|&(l, r, adjust)| {
+            Some((index_of(l)?, index_of(r)?, adjust))
+        })
+        .collect();
+    kerning.sort();
+    kerning.dedup_by_key(|k| (k.0, k.1));
+    if glyphs.len() > u16::MAX as usize || kerning.len() > u16::MAX as usize {
+        return Err("too many glyphs or kerning pairs".into());
+    }
+
+    let mut out = b"WFNT".to_vec();
+    out.push(bpp);
+    out.push(0);
+    out.extend_from_slice(&to_u16(font.ascent, "ascent")?);
+    out.extend_from_slice(&to_u16(font.descent, "descent")?);
+    out.extend_from_slice(&to_u16(font.line_height, "line height")?);
+    out.extend_from_slice(&(glyphs.len() as u16).to_le_bytes());
+    out.extend_from_slice(&(kerning.len() as u16).to_le_bytes());
+
+    let mut bitmaps = Vec::new();
+    for glyph in &glyphs {
+        let (alpha, width, height, x_offset, y_offset) = crop(glyph, bpp);
+        let what = |name: &str| format!("U+{:04X} {}", glyph.codepoint, name);
+        out.extend_from_slice(&glyph.codepoint.to_le_bytes());
+        out.extend_from_slice(&(bitmaps.len() as u32).to_le_bytes());
+        out.push(to_u8(width as i32, &what("width"))?);
+        out.push(to_u8(height as i32, &what("height"))?);
+        out.push(to_i8(x_offset, &what("x offset"))?);
+        out.push(to_i8(y_offset, &what("y offset"))?);
+        out.push(to_u8(glyph.advance, &what("advance"))?);
+        out.push(0);
+        // 上位ビットから詰めて、グリフごとにバイト境界に揃える
+        let mut byte = 0u8;
+        let mut bits = 0;
+        for a in alpha {
+            byte |= a << (8 - bpp - bits);
+            bits += bpp;
+            if bits == 8 {
+                bitmaps.push(byte);
+                byte = 0;
+                bits = 0;
+            }
+        }
+        if bits > 0 {
+            bitmaps.push(byte);
+        }
+    }
+    for (left, right, adjust) in kerning {
+        out.extend_from_slice(&(left as u16).to_le_bytes());
+        out.extend_from_slice(&(right as u16).to_le_bytes());
+        out.push(to_i8(adjust, "kerning")?);
+        out.push(0);
+    }
+    out.extend_from_slice(&bitmaps);
+    Ok(out)
+}
@@ -0,0 +1,145 @@
+//! TrueType/OpenType (TTF/OTF) またはBDFのフォントを、
+//! Wio TerminalのLCDで使うプロポーショナルフォントに変換します。
+//! 形式は `src/font/mod.rs` を参照してください。
+//! TTF/OTFは `--size` の大きさでラスタライズし、`--bpp` のビット数でアンチエイリアスします。
+//! BDFはビットマップのまま1ビットで変換します。
+//! 含める文字は `--chars` に渡したファイルに含まれる文字で、
+//! 指定しなければASCIIの表示できる文字 (0x20〜0x7E) です。
+//!
+//! ### 実行方法
+//! ```sh
+//! $ cd tools/fontconv
+//! $ cargo run --target x86_64-unknown-linux-gnu -- DejaVuSans.ttf sans16.bin --size 16 --bpp 4
+//! $ cargo run --target x86_64-unknown-linux-gnu -- unifont.bdf jp16.bin --chars message.txt
+//! ```
+
+mod bdf;
+mod encode;
+mod ttf;
+
+use std::path::PathBuf;
+use std::process;
+use wio_examples::font::Font;
+
+struct Options {
+    input: PathBuf,
+    output: PathBuf,
+    size: f32,
+    bpp: Option<u8>,
+    chars: Option<PathBuf>,
+}
+
+fn parse_args() -> Option<Options> {
+    let mut paths = Vec::new();
+    let mut size = 16.0;
+    let mut bpp = None;
+    let mut chars = None;
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--size" => size = args.next()?.parse().ok()?,
+            "--bpp" => bpp = Some(args.next()?.parse().ok()?),
+            "--chars" => chars = Some(PathBuf::from(args.next()?)),
+            _ if arg.starts_with("--") => return None,
+            _ => paths.push(PathBuf::from(arg)),
+        }
+    }
+    if paths.len() != 2 {
+        return None;
+    }
+    let output = paths.pop()?;
+    let input = paths.pop()?;
+    Some(Options {
+        input,
+        output,
+        size,
+        bpp,
+        chars,
+    })
+}
+
+fn fail(message: String) -> ! {
+    eprintln!("{}", message);
+    process::exit(1);
+}
+
+fn main() {
+    let options = match parse_args() {
+        Some(options) => options,
+        None => {
+            eprintln!(
+                "usage: fontconv <INPUT.ttf|INPUT.otf|INPUT.bdf> <OUTPUT> \
+                 [--size PX] [--bpp 1|2|4] [--chars FILE]"
+            );
+            process::exit(2);
+        }
+    };
+
+    // 含める文字の一覧 (代用に使う '?' は必ず含める)
+    let mut chars: Vec<char> = match &options.chars {
+        Some(path) => std::fs::read_to_string(path)
+            .unwrap_or_else(|e| fail(format!("{}: {}", path.display(), e)))
+            .chars()
+            .filter(|c| !c.is_control())
+            .collect(),
+        None => (0x20u8..=0x7e).map(char::from).collect(),
+    };
+    chars.push('?');
+    chars.sort_unstable();
+    chars.dedup();
+
+    let source = std::fs::read(&options.input).unwrap_or_else(|e| {
+        fail(format!("{}: {}", options.input.display(), e))
+    });
+    let is_bdf = options
+        .input
+        .extension()
+        .map_or(false, |e| e.eq_ignore_ascii_case("bdf"));
+    let (font, bpp) = if is_bdf {
+        let source = String::from_utf8_lossy(&source);
+        let font = bdf::parse(&source, |c| chars.binary_search(&c).is_ok());
+        (font, options.bpp.unwrap_or(1))
+    } else {
+        let font = ttf::rasterize(&source, options.size, &chars);
+        (font, options.bpp.unwrap_or(4))
+    };
+    let font = font.unwrap_or_else(|e| {
+        fail(format!("{}: {}", options.input.display(), e))
+    });
+    let out = encode::encode(&font, bpp).unwrap_or_else(|e| fail(e));
+
+    // ライブラリの読み込み処理で、すべての文字が引けることを確認する
+    let parsed = match Font::parse(&out) {
+        Ok(parsed) => parsed,
+        Err(e) => fail(format!("internal error: {:?}", e)),
+    };
+    for glyph in &font.glyphs {
+        let c = std::char::from_u32(glyph.codepoint).unwrap();
+        if parsed.glyph(c).is_none() {
+            fail(format!(
+                "internal error: U+{:04X} is missing",
+                glyph.codepoint
+            ));
+        }
+    }
+    let missing: String = chars
+        .iter()
+        .filter(|&&c| parsed.glyph(c).is_none())
+        .collect();
+    if !missing.is_empty() {
+        eprintln!("warning: not in the font: {}", missing);
+    }
+
+    if let Err(e) = std::fs::write(&options.output, &out) {
+        fail(format!("{}: {}", options.output.display(), e));
+    }
+    println!(
+        "{}: {} glyphs, {} kerning pairs, {} bpp, line height {}px, {} bytes",
+        options.output.display(),
+        parsed.glyph_count(),
+        font.kerning.len(),
+        bpp,
+        parsed.line_height(),
+        out.len(),
+    );
+}
@@ -0,0 +1,58 @@
+// TrueType/OpenTypeのフォントをfontdueでラスタライズします
+use crate::encode::{FontData, Glyph};
+
+pub fn rasterize(
+    source: &[u8],
+    size: f32,
+    chars: &[char],
+) -> Result<FontData, String> {
+    let font = fontdue::Font::from_bytes(
+        source,
+        fontdue::FontSettings {
+            scale: size,
+            ..Default::default()
+        },
+    )?;
+    let metrics = font
+        .horizontal_line_metrics(size)
+        .ok_or("font has no horizontal metrics")?;
+    let ascent = metrics.ascent.ceil() as i32;
+    let descent = (-metrics.descent).ceil() as i32;
+    let mut glyphs = Vec::new();
+    for &c in chars {
+        // フォントにない文字は飛ばす (描画するときは '?' で代用される)
+        if font.lookup_glyph_index(c) == 0 {
+            continue;
+        }
+        let (m, coverage) = font.rasterize(c, size);
+        glyphs.push(Glyph {
+            codepoint: c as u32,
+            width: m.width,
+            height: m.height,
+            x_offset: m.xmin,
+            y_offset: m.ymin + m.height as i32,
+            advance: m.advance_width.round() as i32,
+            coverage,
+        });
+    }
+    let mut kerning = Vec::new();
+    for left in &glyphs {
+        for right in &glyphs {
+            let (l, r) = (left.codepoint, right.codepoint);
+            let adjust = std::char::from_u32(l)
+                .zip(std::char::from_u32(r))
+                .and_then(|(l, r)| font.horizontal_kern(l, r, size))
+                .map_or(0, |k| k.round() as i32);
+            if adjust != 0 {
+                kerning.push((l, r, adjust));
+            }
+        }
+    }
+    Ok(FontData {
+        ascent,
+        descent,
+        line_height: metrics.new_line_size.ceil() as i32,
+        glyphs,
+        kerning,
+    })
+}
@@ -0,0 +1,88 @@
+use wio_examples::image::{
+    qoi_hash, split, MAX_PACKET_PIXELS, QOI_DIFF, QOI_INDEX_SIZE, QOI_LUMA,
+    QOI_MAX_RUN, QOI_RGB565, QOI_RUN, RLE_RUN,
+};
+
+// 先頭から同じ色が続く数 (最大でMAX_PACKET_PIXELS)
+fn run_length(pixels: &[u16]) -> usize {
+    pixels
+        .iter()
+        .take(MAX_PACKET_PIXELS)
+        .take_while(|&&p| p == pixels[0])
+        .count()
+}
+
+pub fn encode_rle(pixels: &[u16], out: &mut Vec<u8>) {
+    let mut i = 0;
+    while i < pixels.len() {
+        let run = run_length(&pixels[i..]);
+        if run >= 2 {
+            out.push(RLE_RUN | (run - 1) as u8);
+            out.extend_from_slice(&pixels[i].to_be_bytes());
+            i += run;
+            continue;
+        }
+        // 次に同じ色が2つ以上続くところまでをそのまま書き出す
+        let start = i;
+        while i < pixels.len()
+            && i - start < MAX_PACKET_PIXELS
+            && run_length(&pixels[i..]) < 2
+        {
+            i += 1;
+        }
+        out.push((i - start - 1) as u8);
+        for pixel in &pixels[start..i] {
+            out.extend_from_slice(&pixel.to_be_bytes());
+        }
+    }
+}
+
+pub fn encode_qoi(pixels: &[u16], out: &mut Vec<u8>) {
+    let mut index = [0u16; QOI_INDEX_SIZE];
+    let mut previous = 0u16;
+    let mut run = 0;
+    for (i, &pixel) in pixels.iter().enumerate() {
+        if pixel == previous {
+            run += 1;
+            if run == QOI_MAX_RUN || i + 1 == pixels.len() {
+                out.push(QOI_RUN | (run - 1) as u8);
+                run = 0;
+            }
+            continue;
+        }
+        if run > 0 {
+            out.push(QOI_RUN | (run - 1) as u8);
+            run = 0;
+        }
+
+        let hash = qoi_hash(pixel);
+        if index[hash] == pixel {
+            out.push(hash as u8);
+        } else {
+            index[hash] = pixel;
+            let (r, g, b) = split(pixel);
+            let (pr, pg, pb) = split(previous);
+            let (dr, dg, db) = (r - pr, g - pg, b - pb);
+            let small = |d: i16| (-2..=1).contains(&d);
+            let (dr_dg, db_dg) = (dr - dg, db - dg);
+            if small(dr) && small(dg) && small(db) {
+                out.push(
+                    QOI_DIFF
+                        | ((dr + 2) as u8) << 4
+                        | ((dg + 2) as u8) << 2
+                        | (db + 2) as u8,
+                );
+            } else if (-32..=31).contains(&dg)
+                && (-8..=7).contains(&dr_dg)
+                && (-8..=7).contains(&db_dg)
+            {
+                out.push(QOI_LUMA | (dg + 32) as u8);
+                out.push(((dr_dg + 8) as u8) << 4 | (db_dg + 8) as u8);
+            } else {
+                out.push(QOI_RGB565);
+                out.extend_from_slice(&pixel.to_be_bytes());
+            }
+        }
+        previous = pixel;
+    }
+}
@@ -0,0 +1,152 @@
+//! PNG/BMPの画像を、Wio TerminalのLCDで表示できるRGB565のアセットに変換します。
+//! 形式は `src/image.rs` を参照してください。
+//! 透明なピクセルは黒と合成します。`--key` を指定した場合は、
+//! 半分以上透明なピクセルをスプライトの透過色 (RRGGBB) に置き換えます。
+//!
+//! ### 実行方法
+//! ```sh
+//! $ cd tools/imgconv
+//! $ cargo run --target x86_64-unknown-linux-gnu -- logo.png logo.bin --rle
+//! $ cargo run --target x86_64-unknown-linux-gnu -- logo.png logo.raw --no-header
+//! $ cargo run --target x86_64-unknown-linux-gnu -- sprites.png sprites.bin --qoi --key FF00FF
+//! ```
+
+mod encode;
+
+use embedded_graphics::pixelcolor::raw::{RawData, RawU16};
+use std::path::PathBuf;
+use std::process;
+use wio_examples::image::{Asset, Format, HEADER_SIZE, MAGIC};
+
+struct Options {
+    input: PathBuf,
+    output: PathBuf,
+    format: Format,
+    header: bool,
+    key: Option<u16>,
+}
+
+fn parse_args() -> Option<Options> {
+    let mut paths = Vec::new();
+    let mut format = Format::Raw;
+    let mut header = true;
+    let mut key = None;
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--rle" => format = Format::Rle,
+            "--qoi" => format = Format::Qoi,
+            "--no-header" => header = false,
+            "--key" => {
+                let rgb = u32::from_str_radix(&args.next()?, 16).ok()?;
+                let [_, r, g, b] = rgb.to_be_bytes();
+                key = Some(to_rgb565([r, g, b, 255], None));
+            }
+            _ if arg.starts_with("--") => return None,
+            _ => paths.push(PathBuf::from(arg)),
+        }
+    }
+    // ヘッダがないと圧縮されているかどうか区別できない
+    if paths.len() != 2 || (format != Format::Raw && !header) {
+        return None;
+    }
+    let output = paths.pop()?;
+    let input = paths.pop()?;
+    Some(Options {
+        input,
+        output,
+        format,
+        header,
+        key,
+    })
+}
+
+// 8ビットのRGBAをRGB565に変換します
+fn to_rgb565([r, g, b, a]: [u8; 4], key: Option<u16>) -> u16 {
+    if let (Some(key), true) = (key, a < 128) {
+        return key;
+    }
+    let blend = |c: u8| c as u16 * a as u16 / 255;
+    let (r, g, b) = (blend(r), blend(g), blend(b));
+    ((r >> 3) << 11) | ((g >> 2) << 5) | (b >> 3)
+}
+
+fn main() {
+    let options = match parse_args() {
+        Some(options) => options,
+        None => {
+            eprintln!(
+                "usage: imgconv <INPUT> <OUTPUT> [--rle|--qoi] [--no-header] \
+                 [--key RRGGBB]\n\
+                 --no-header cannot be combined with --rle or --qoi"
+            );
+            process::exit(2);
+        }
+    };
+
+    let image = match image::open(&options.input) {
+        Ok(image) => image.to_rgba8(),
+        Err(e) => {
+            eprintln!("{}: {}", options.input.display(), e);
+            process::exit(1);
+        }
+    };
+    let (width, height) = image.dimensions();
+    if width > u16::MAX as u32 || height > u16::MAX as u32 {
+        eprintln!("image too large: {}x{}", width, height);
+        process::exit(1);
+    }
+    let pixels: Vec<u16> = image
+        .pixels()
+        .map(|p| to_rgb565(p.0, options.key))
+        .collect();
+
+    let mut out = Vec::new();
+    if options.header {
+        out.extend_from_slice(&MAGIC);
+        out.push(options.format as u8);
+        out.extend_from_slice(&(width as u16).to_le_bytes());
+        out.extend_from_slice(&(height as u16).to_le_bytes());
+    }
+    match options.format {
+        Format::Raw => {
+            for pixel in &pixels {
+                out.extend_from_slice(&pixel.to_be_bytes());
+            }
+        }
+        Format::Rle => encode::encode_rle(&pixels, &mut out),
+        Format::Qoi => encode::encode_qoi(&pixels, &mut out),
+    }
+
+    // ライブラリの読み込み処理で展開して、元の画像と一致することを確認する
+    let asset = if options.header {
+        Asset::parse(&out)
+    } else {
+        Asset::from_raw(&out, width as u16)
+    };
+    let decoded: Option<Vec<u16>> = asset.ok().map(|asset| {
+        asset
+            .colors()
+            .map(|c| RawU16::from(c).into_inner())
+            .collect()
+    });
+    if decoded.as_ref() != Some(&pixels) {
+        eprintln!("internal error: encoded asset does not round-trip");
+        process::exit(1);
+    }
+
+    if let Err(e) = std::fs::write(&options.output, &out) {
+        eprintln!("{}: {}", options.output.display(), e);
+        process::exit(1);
+    }
+    let data_size = out.len() - if options.header { HEADER_SIZE } else { 0 };
+    println!(
+        "{}: {}x{} {:?}, {} bytes ({} bytes uncompressed)",
+        options.output.display(),
+        width,
+        height,
+        options.format,
+        data_size,
+        pixels.len() * 2,
+    );
+}
@@ -0,0 +1,111 @@
+use embedded_graphics::drawable::Pixel;
+use embedded_graphics::geometry::{Point, Size};
+use embedded_graphics::pixelcolor::raw::RawU16;
+use embedded_graphics::pixelcolor::{Rgb565, Rgb888, RgbColor};
+use embedded_graphics::DrawTarget;
+use std::convert::Infallible;
+use std::fs::File;
+use std::io::{self, BufWriter};
+use std::path::Path;
+use wio_examples::framebuffer::PixelSink;
+
+pub const WIDTH: usize = 320;
+pub const HEIGHT: usize = 240;
+
+// LCDの代わりにメモリ上に描画するディスプレイです
+pub struct SimDisplay {
+    pixels: Vec<Rgb565>,
+}
+
+impl SimDisplay {
+    pub fn new() -> SimDisplay {
+        SimDisplay {
+            pixels: vec![Rgb565::BLACK; WIDTH * HEIGHT],
+        }
+    }
+
+    // RGB各8ビットに変換した画素の並び
+    pub fn to_rgb8(&self) -> Vec<u8> {
+        let mut data = Vec::with_capacity(WIDTH * HEIGHT * 3);
+        for &pixel in self.pixels.iter() {
+            let color = Rgb888::from(pixel);
+            data.extend_from_slice(&[color.r(), color.g(), color.b()]);
+        }
+        data
+    }
+
+    pub fn save_png(&self, path: &Path) -> io::Result<()> {
+        let file = BufWriter::new(File::create(path)?);
+        let mut encoder = png::Encoder::new(file, WIDTH as u32, HEIGHT as u32);
+        encoder.set_color(png::ColorType::RGB);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header().map_err(into_io_error)?;
+        writer
+            .write_image_data(&self.to_rgb8())
+            .map_err(into_io_error)
+    }
+}
+
+// PNGファイルを読み込んで、RGB各8ビットの画素の並びを返します
+pub fn load_png(path: &Path) -> io::Result<Vec<u8>> {
+    let decoder = png::Decoder::new(File::open(path)?);
+    let (info, mut reader) = decoder.read_info().map_err(into_io_error)?;
+    if info.color_type != png::ColorType::RGB
+        || info.width as usize != WIDTH
+        || info.height as usize != HEIGHT
+    {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "unexpected image format",
+        ));
+    }
+    let mut data = vec![0; info.buffer_size()];
+    reader.next_frame(&mut data).map_err(into_io_error)?;
+    Ok(data)
+}
+
+fn into_io_error<E: std::error::Error + Send + Sync + 'static>(
+    error: E,
+) -> io::Error {
+    io::Error::new(io::ErrorKind::Other, error)
+}
+
+impl DrawTarget<Rgb565> for SimDisplay {
+    type Error = Infallible;
+
+    fn draw_pixel(&mut self, pixel: Pixel<Rgb565>) -> Result<(), Infallible> {
+        let Pixel(Point { x, y }, color) = pixel;
+        if x >= 0 && y >= 0 && (x as usize) < WIDTH && (y as usize) < HEIGHT {
+            self.pixels[y as usize * WIDTH + x as usize] = color;
+        }
+        Ok(())
+    }
+
+    fn size(&self) -> Size {
+        Size::new(WIDTH as u32, HEIGHT as u32)
+    }
+}
+
+// StripRendererの転送先としても使えるようにする
+impl PixelSink for SimDisplay {
+    type Error = Infallible;
+
+    fn write_pixels(
+        &mut self,
+        x0: u16,
+        y0: u16,
+        x1: u16,
+        y1: u16,
+        pixels: &[u16],
+    ) -> Result<(), Infallible> {
+        let width = (x1 - x0 + 1) as usize;
+        for (i, &raw) in pixels.iter().enumerate() {
+            let x = x0 as usize + i % width;
+            let y = y0 as usize + i / width;
+            if x <= x1 as usize && y <= y1 as usize {
+                self.pixels[y * WIDTH + x] = Rgb565::from(RawU16::new(raw));
+            }
+        }
+        Ok(())
+    }
+}
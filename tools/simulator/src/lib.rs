@@ -0,0 +1,182 @@
+// サンプルの画面をホスト上のメモリに描画します
+// 実行ファイル (src/main.rs) とスナップショットのテスト (tests/snapshots.rs) で使います
+pub mod display;
+
+use display::SimDisplay;
+use embedded_graphics::fonts::{Font12x16, Font8x16};
+use embedded_graphics::geometry::{Point, Size};
+use embedded_graphics::pixelcolor::Rgb565;
+use embedded_graphics::prelude::*;
+use embedded_graphics::primitives::Rectangle;
+use embedded_graphics::style::PrimitiveStyle;
+use wio_examples::font::{self, Font, TextStyle};
+use wio_examples::framebuffer::StripRenderer;
+use wio_examples::image::Asset;
+use wio_examples::laps::Laps;
+use wio_examples::plot::Plot;
+use wio_examples::screens;
+use wio_examples::sprite::SpriteSheet;
+use wio_examples::stopwatch::TimeField;
+use wio_examples::ui::{layout, status_bar};
+use wio_examples::ui::{
+    Button, Gauge, ListMenu, NumericReadout, ProgressBar, StatusBar, Theme,
+    Widget,
+};
+
+const FERRIS: &[u8] = include_bytes!("../../../examples/assets/ferris_qoi.bin");
+const SANS16: &[u8] = include_bytes!("../../../examples/assets/sans16.bin");
+
+// 画面の名前と描画処理
+pub const SCREENS: &[(&str, fn(&mut SimDisplay))] = &[
+    ("hello", hello),
+    ("stopwatch", stopwatch),
+    ("countdown", countdown),
+    ("spectrum", spectrum),
+    ("plot", plot),
+    ("strip_framebuffer", strip_framebuffer),
+    ("sprites", sprites),
+    ("ui", ui),
+    ("fonts", fonts),
+];
+
+fn hello(display: &mut SimDisplay) {
+    screens::hello(display).unwrap();
+}
+
+fn stopwatch(display: &mut SimDisplay) {
+    screens::clear(display).unwrap();
+    screens::stopwatch_title(display, "STOPWATCH").unwrap();
+    screens::stopwatch(display, 83_456).unwrap();
+    let mut laps = Laps::new();
+    for &split in &[12_340, 25_170, 36_920, 52_050, 63_810, 76_580] {
+        laps.record(split);
+    }
+    screens::laps(display, &laps, 0).unwrap();
+}
+
+fn countdown(display: &mut SimDisplay) {
+    screens::clear(display).unwrap();
+    screens::stopwatch_title(display, "COUNTDOWN").unwrap();
+    screens::stopwatch(display, 180_000).unwrap();
+    screens::time_cursor(display, Some(TimeField::Seconds)).unwrap();
+}
+
+fn spectrum(display: &mut SimDisplay) {
+    const BARS: usize = 128;
+    let mut heights = [0u8; BARS];
+    for (i, height) in heights.iter_mut().enumerate() {
+        // 低い周波数ほど大きく、途中にピークを持つ形にする
+        let peak = if i == 20 || i == 40 { 120 } else { 0 };
+        *height = (200 / (i + 2) + peak) as u8;
+    }
+    let mut previous = [0u8; BARS];
+    screens::clear(display).unwrap();
+    screens::spectrum(display, &heights, &mut previous).unwrap();
+}
+
+fn plot(display: &mut SimDisplay) {
+    let mut plot = Plot::new(Point::new(0, 0), Size::new(320, 240));
+    for i in 0..plot.capacity() {
+        let t = i as f32 / 20.0;
+        plot.push([t.sin(), (t * 0.5).cos() * 0.5, 1.0]);
+    }
+    plot.draw(display).unwrap();
+}
+
+fn strip_framebuffer(display: &mut SimDisplay) {
+    let mut renderer = StripRenderer::new(Size::new(320, 240));
+    renderer
+        .render(display, |frame| {
+            screens::stopwatch_title(frame, "STOPWATCH")?;
+            screens::stopwatch(frame, 3_723_000)
+        })
+        .unwrap();
+    // 前の表示を消さずに画面全体を描き直し、変わったタイルだけを転送する
+    renderer
+        .render(display, |frame| screens::stopwatch(frame, 3_723_450))
+        .unwrap();
+}
+
+fn sprites(display: &mut SimDisplay) {
+    Rectangle::new(Point::zero(), Point::new(319, 239))
+        .into_styled(PrimitiveStyle::with_fill(Rgb565::new(4, 24, 20)))
+        .draw(display)
+        .unwrap();
+    let ferris = Asset::parse(FERRIS).unwrap();
+    // 圧縮された画像を行ごとに展開して転送する
+    ferris.write_to(display, Point::new(117, 24)).unwrap();
+    let sheet = SpriteSheet::new(ferris, ferris.width(), ferris.height())
+        .with_key(Rgb565::BLACK);
+    for x in (0..320).step_by(80) {
+        sheet.draw_frame(display, 0, Point::new(x, 160)).unwrap();
+    }
+}
+
+fn ui(display: &mut SimDisplay) {
+    let theme = Theme::DARK;
+    let (_, body) = layout::split_top(layout::screen(), status_bar::HEIGHT);
+    let body = layout::inset(body, 8);
+    let (left, right) = layout::split_left(body, 150);
+    let mut status = StatusBar::new(theme);
+    status.set_title("Simulator");
+    status.set_status("12:34");
+    let mut readout = NumericReadout::new(
+        layout::row(left, 0, 4, 4),
+        Font12x16,
+        theme,
+        2,
+        "V",
+    );
+    readout.set_value(-1234);
+    let mut progress = ProgressBar::new(layout::row(left, 1, 4, 4), theme, 10);
+    progress.set_value(7);
+    let mut button =
+        Button::new(layout::row(left, 3, 4, 4), Font8x16, theme, "OK");
+    button.set_focused(true);
+    let mut gauge = Gauge::new(
+        Point::new(
+            left.top_left.x + 75,
+            layout::row(left, 2, 4, 4).bottom_right.y,
+        ),
+        40,
+        theme,
+        0,
+        100,
+    );
+    gauge.set_value(30);
+    let items = ["LED", "Buzzer", "Light", "Accel", "Stopwatch", "FFT"];
+    let mut menu = ListMenu::new(right, Font8x16, theme, &items);
+    menu.select(2);
+
+    status.draw(display).unwrap();
+    readout.draw(display).unwrap();
+    progress.draw(display).unwrap();
+    button.draw(display).unwrap();
+    gauge.draw(display).unwrap();
+    menu.draw(display).unwrap();
+    // 値を変えたときは、変化した部分だけが描き直される
+    progress.set_value(4);
+    gauge.set_value(80);
+    menu.select_next();
+    progress.draw(display).unwrap();
+    gauge.draw(display).unwrap();
+    menu.draw(display).unwrap();
+}
+
+fn fonts(display: &mut SimDisplay) {
+    let theme = Theme::DARK;
+    display.clear(theme.background).unwrap();
+    let sans = Font::parse(SANS16).unwrap();
+    let style = TextStyle::new(theme.foreground, theme.background);
+    let text = "AVAST! To Wio Terminal: kerning, anti-aliasing and wrapping.";
+    let area = layout::inset(layout::screen(), 8);
+    let alignments = [
+        layout::Alignment::Left,
+        layout::Alignment::Center,
+        layout::Alignment::Right,
+    ];
+    for (i, &align) in alignments.iter().enumerate() {
+        let area = layout::row(area, i as i32, 3, 8);
+        font::draw_wrapped(display, &sans, text, area, align, &style).unwrap();
+    }
+}
@@ -0,0 +1,36 @@
+//! サンプルの画面をホスト上で描画して、PNGファイルに保存するシミュレータです。
+//! 保存済みのPNGファイル (snapshots/) との比較は `cargo test` で行います。
+//!
+//! ### 実行方法
+//! ```sh
+//! $ cd tools/simulator
+//! $ cargo run --target x86_64-unknown-linux-gnu -- --out output
+//! ```
+
+use simulator::display::SimDisplay;
+use simulator::SCREENS;
+use std::path::PathBuf;
+use std::process;
+
+fn main() {
+    let mut out = PathBuf::from("output");
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match (arg.as_str(), args.next()) {
+            ("--out", Some(dir)) => out = PathBuf::from(dir),
+            _ => {
+                eprintln!("usage: simulator [--out DIR]");
+                process::exit(2);
+            }
+        }
+    }
+
+    std::fs::create_dir_all(&out).unwrap();
+    for (name, draw) in SCREENS {
+        let mut display = SimDisplay::new();
+        draw(&mut display);
+        let file = out.join(format!("{}.png", name));
+        display.save_png(&file).unwrap();
+        println!("{}: {}", name, file.display());
+    }
+}
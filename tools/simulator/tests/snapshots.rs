@@ -0,0 +1,46 @@
+// サンプルの画面を描画して、snapshots/ に保存したPNGファイルと比較します
+// ホストで実行します
+//   $ cargo test --target x86_64-unknown-linux-gnu
+// 描画を変更したときは、環境変数 UPDATE_SNAPSHOTS を設定して実行するとPNGファイルを作り直します
+//   $ UPDATE_SNAPSHOTS=1 cargo test --target x86_64-unknown-linux-gnu
+use simulator::display::{load_png, SimDisplay};
+use simulator::SCREENS;
+use std::path::PathBuf;
+
+fn snapshot_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("snapshots")
+}
+
+#[test]
+fn screens_match_snapshots() {
+    let dir = snapshot_dir();
+    let update = std::env::var_os("UPDATE_SNAPSHOTS").is_some();
+    if update {
+        std::fs::create_dir_all(&dir).unwrap();
+    }
+    let mut failures = Vec::new();
+    for (name, draw) in SCREENS {
+        let mut display = SimDisplay::new();
+        draw(&mut display);
+        let file = dir.join(format!("{}.png", name));
+        if update {
+            display.save_png(&file).unwrap();
+            continue;
+        }
+        match load_png(&file) {
+            Ok(expected) if expected == display.to_rgb8() => {}
+            Ok(_) => failures.push(format!("{}: differs from snapshot", name)),
+            Err(e) => failures.push(format!(
+                "{}: cannot read {} ({})",
+                name,
+                file.display(),
+                e
+            )),
+        }
+    }
+    assert!(
+        failures.is_empty(),
+        "{}\nrun with UPDATE_SNAPSHOTS=1 to update the snapshots",
+        failures.join("\n")
+    );
+}